@@ -0,0 +1,4 @@
+//! A parser (and, increasingly, writer) for Goxel's `.gox` voxel format.
+
+pub mod parser;
+pub mod png;
@@ -0,0 +1,26 @@
+//! Parses a `.gox` file and meshes it with the `block-mesh` crate's greedy
+//! quads algorithm, printing the resulting quad count. Only runs with the
+//! `block-mesh` feature enabled:
+//!
+//! ```sh
+//! cargo run --example block_mesh_export --features block-mesh -- model.gox
+//! ```
+
+#[cfg(feature = "block-mesh")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: block_mesh_export <path.gox>")?;
+
+    let goxel = gox::parser::parse_verified(&std::fs::read(path)?)?;
+    let model = goxel.model()?;
+
+    let (buffer, ..) = model.to_block_mesh_buffer();
+    println!("{} quads", buffer.quads.num_quads());
+    Ok(())
+}
+
+#[cfg(not(feature = "block-mesh"))]
+fn main() {
+    eprintln!("this example requires --features block-mesh");
+}
@@ -4,13 +4,17 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::{map, verify},
+    combinator::{consumed, map, map_parser, verify},
     multi::{fold_many1, length_count, length_data, many0},
     number::complete::{le_i32, le_u32},
-    sequence::{preceded, terminated, tuple},
+    sequence::{preceded, tuple},
     IResult,
 };
+use crate::png;
+use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
 
 #[derive(Debug)]
 pub struct Goxel {
@@ -29,7 +33,7 @@ pub struct Block {
 #[derive(Debug)]
 pub enum Chunk {
     Img {
-        dict: HashMap<String, Vec<u8>>,
+        dict: IndexMap<String, Vec<u8>>,
     },
     Prev {
         data: Vec<u8>,
@@ -39,16 +43,279 @@ pub enum Chunk {
     },
     Layr {
         blocks: Vec<Block>,
-        dict: HashMap<String, Vec<u8>>,
+        dict: IndexMap<String, Vec<u8>>,
     },
     Camr {
-        dict: HashMap<String, Vec<u8>>,
+        dict: IndexMap<String, Vec<u8>>,
     },
     Ligh {
-        dict: HashMap<String, Vec<u8>>,
+        dict: IndexMap<String, Vec<u8>>,
     },
 }
 
+/// Errors returned by [`parse`] and [`parse_verified`], each carrying the
+/// byte offset within the input where the problem was found.
+#[derive(Debug, thiserror::Error)]
+pub enum GoxError {
+    #[error("not a .gox file: missing the \"GOX \" magic header")]
+    BadMagic,
+
+    #[error("unknown chunk type {tag:?} at offset {offset}")]
+    UnknownChunk { tag: [u8; 4], offset: usize },
+
+    #[error("CRC-32 mismatch at offset {offset}: expected {expected:#010x}, found {found:#010x}")]
+    CrcMismatch {
+        expected: u32,
+        found: u32,
+        offset: usize,
+    },
+
+    #[error("chunk at offset {offset} is truncated")]
+    TruncatedChunk { offset: usize },
+
+    #[error("failed to decode chunk dict at offset {offset}")]
+    DictDecode { offset: usize },
+}
+
+fn read_f32(bytes: &[u8]) -> Option<f32> {
+    Some(f32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i32(bytes: &[u8]) -> Option<i32> {
+    Some(i32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_bool(bytes: &[u8]) -> Option<bool> {
+    Some(read_i32(bytes)? != 0)
+}
+
+fn read_mat4(bytes: &[u8]) -> Option<[f32; 16]> {
+    if bytes.len() != 64 {
+        return None;
+    }
+    let mut mat = [0f32; 16];
+    for (dst, src) in mat.iter_mut().zip(bytes.chunks_exact(4)) {
+        *dst = read_f32(src)?;
+    }
+    Some(mat)
+}
+
+/// A decoded `CAMR` dict: the camera's transform and projection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraView {
+    pub mat: [f32; 16],
+    pub dist: f32,
+    pub ortho: bool,
+}
+
+/// A decoded `LAYR` dict: the layer's name, transform and flags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerView {
+    pub name: String,
+    pub mat: [f32; 16],
+    pub visible: bool,
+    pub base_id: i32,
+    pub material: i32,
+}
+
+/// A decoded `LIGH` dict: the scene light's direction and strength.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightView {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub intensity: f32,
+}
+
+/// A decoded `IMG` dict: the image's bounding box transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageView {
+    pub bounding_box: [f32; 16],
+}
+
+impl Chunk {
+    /// Decodes this chunk's dict as a camera view, if it's a `CAMR` chunk
+    /// with a well-formed `mat` and `dist`.
+    pub fn as_camera(&self) -> Option<CameraView> {
+        let Chunk::Camr { dict } = self else {
+            return None;
+        };
+        Some(CameraView {
+            mat: read_mat4(dict.get("mat")?)?,
+            dist: read_f32(dict.get("dist")?)?,
+            ortho: dict.get("ortho").and_then(|v| read_bool(v)).unwrap_or(false),
+        })
+    }
+
+    /// Decodes this chunk's dict as a layer view, if it's a `LAYR` chunk
+    /// with a well-formed `name` and `mat`.
+    pub fn as_layer(&self) -> Option<LayerView> {
+        let Chunk::Layr { dict, .. } = self else {
+            return None;
+        };
+        Some(LayerView {
+            name: String::from_utf8(dict.get("name")?.clone()).ok()?,
+            mat: read_mat4(dict.get("mat")?)?,
+            visible: dict
+                .get("visible")
+                .and_then(|v| read_bool(v))
+                .unwrap_or(true),
+            base_id: dict.get("base_id").and_then(|v| read_i32(v)).unwrap_or(-1),
+            material: dict.get("material").and_then(|v| read_i32(v)).unwrap_or(-1),
+        })
+    }
+
+    /// Decodes this chunk's dict as a light view, if it's a `LIGH` chunk
+    /// with well-formed `pitch`, `yaw` and `intensity` values.
+    pub fn as_light(&self) -> Option<LightView> {
+        let Chunk::Ligh { dict } = self else {
+            return None;
+        };
+        Some(LightView {
+            pitch: read_f32(dict.get("pitch")?)?,
+            yaw: read_f32(dict.get("yaw")?)?,
+            intensity: read_f32(dict.get("intensity")?)?,
+        })
+    }
+
+    /// Decodes this chunk's dict as an image view, if it's an `IMG` chunk
+    /// with a well-formed `box` bounding box matrix.
+    pub fn as_image(&self) -> Option<ImageView> {
+        let Chunk::Img { dict } = self else {
+            return None;
+        };
+        Some(ImageView {
+            bounding_box: read_mat4(dict.get("box")?)?,
+        })
+    }
+}
+
+/// A decoded 16×16×16 block of voxels, indexed `[x][y][z]`. Each voxel is an
+/// RGBA color; an alpha of `0` means the voxel is empty.
+pub type Voxels = [[[[u8; 4]; 16]; 16]; 16];
+
+#[derive(Debug)]
+pub enum VoxelError {
+    /// A `Block` referenced a `BL16` chunk index that doesn't exist.
+    MissingBl16 { index: i32 },
+    /// The referenced `BL16` chunk's PNG payload couldn't be decoded.
+    Png(png::PngError),
+    /// A `BL16` chunk's image wasn't the 64×64 size a voxel block expects.
+    UnexpectedImageSize { width: u32, height: u32 },
+}
+
+impl fmt::Display for VoxelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoxelError::MissingBl16 { index } => {
+                write!(f, "block references missing BL16 chunk #{}", index)
+            }
+            VoxelError::Png(err) => write!(f, "failed to decode BL16 image: {}", err),
+            VoxelError::UnexpectedImageSize { width, height } => {
+                write!(f, "BL16 image is {}x{}, expected 64x64", width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VoxelError {}
+
+impl From<png::PngError> for VoxelError {
+    fn from(err: png::PngError) -> Self {
+        VoxelError::Png(err)
+    }
+}
+
+/// Decodes a `BL16` chunk's PNG payload into its 16×16×16 voxel grid.
+fn decode_bl16(data: &[u8]) -> Result<Voxels, VoxelError> {
+    let image = png::decode(data)?;
+    if image.width != 64 || image.height != 64 {
+        return Err(VoxelError::UnexpectedImageSize {
+            width: image.width,
+            height: image.height,
+        });
+    }
+    Ok(voxels_from_image(&image))
+}
+
+/// Maps a decoded 64×64 RGBA image into a 16×16×16 voxel grid. The z-th
+/// 16×16 layer is stored at pixel offset `(x=(z % 4) * 16, y=(z / 4) * 16)`.
+fn voxels_from_image(image: &png::Image) -> Voxels {
+    let mut voxels: Voxels = [[[[0u8; 4]; 16]; 16]; 16];
+    for (x, plane) in voxels.iter_mut().enumerate() {
+        for (y, column) in plane.iter_mut().enumerate() {
+            for (z, voxel) in column.iter_mut().enumerate() {
+                let px = (z % 4) * 16 + x;
+                let py = (z / 4) * 16 + y;
+                let i = (py * image.width as usize + px) * 4;
+                voxel.copy_from_slice(&image.rgba[i..i + 4]);
+            }
+        }
+    }
+    voxels
+}
+
+impl Block {
+    /// Decodes this block's voxel grid by looking up the `BL16` chunk it
+    /// references (`index` counts only the `BL16` chunks, in file order).
+    pub fn voxels(&self, goxel: &Goxel) -> Result<Voxels, VoxelError> {
+        let data = goxel
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Bl16 { data } => Some(data),
+                _ => None,
+            })
+            .nth(self.index as usize)
+            .ok_or(VoxelError::MissingBl16 { index: self.index })?;
+        decode_bl16(data)
+    }
+}
+
+/// A sparse, world-space voxel map for a single layer, keyed by integer
+/// `(x, y, z)` coordinate.
+pub type LayerVoxels = HashMap<(i32, i32, i32), [u8; 4]>;
+
+impl Goxel {
+    /// The file format version this `.gox` was written with.
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// Reconstructs each `LAYR` chunk as a sparse, world-space voxel map
+    /// keyed by integer coordinate, skipping empty (alpha `0`) voxels.
+    pub fn layers(&self) -> Result<Vec<LayerVoxels>, VoxelError> {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some(blocks),
+                _ => None,
+            })
+            .map(|blocks| {
+                let mut voxels = HashMap::new();
+                for block in blocks {
+                    let grid = block.voxels(self)?;
+                    for (x, plane) in grid.iter().enumerate() {
+                        for (y, column) in plane.iter().enumerate() {
+                            for (z, &rgba) in column.iter().enumerate() {
+                                if rgba[3] == 0 {
+                                    continue;
+                                }
+                                let coord = (
+                                    block.x + x as i32,
+                                    block.y + y as i32,
+                                    block.z + z as i32,
+                                );
+                                voxels.insert(coord, rgba);
+                            }
+                        }
+                    }
+                }
+                Ok(voxels)
+            })
+            .collect()
+    }
+}
+
 fn entry(input: &[u8]) -> IResult<&[u8], (String, Vec<u8>)> {
     map(
         tuple((
@@ -59,45 +326,72 @@ fn entry(input: &[u8]) -> IResult<&[u8], (String, Vec<u8>)> {
     )(input)
 }
 
-fn dict(input: &[u8]) -> IResult<&[u8], HashMap<String, Vec<u8>>> {
-    fold_many1(entry, HashMap::new, |mut map, (key, value)| {
+fn dict(input: &[u8]) -> IResult<&[u8], IndexMap<String, Vec<u8>>> {
+    fold_many1(entry, IndexMap::new, |mut map, (key, value)| {
         map.insert(key, value);
         map
     })(input)
 }
 
-fn chunk_common<'a, F: 'a>(
+/// Computes the reflected CRC-32/ISO-HDLC checksum (polynomial `0xEDB88320`,
+/// initial value `0xFFFFFFFF`, final XOR `0xFFFFFFFF`) that Goxel stores as
+/// each chunk's trailer. Equivalent to what `crc32fast` produces.
+fn crc32(bytes: &[u8]) -> u32 {
+    const POLY: u32 = 0xEDB88320;
+    let mut crc = 0xFFFFFFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLY & mask);
+        }
+    }
+    crc ^ 0xFFFFFFFF
+}
+
+fn chunk_common<'a, F>(
     name: &'a str,
+    verify_crc: bool,
     parser: F,
 ) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], Chunk>
 where
-    F: FnMut(&'a [u8]) -> IResult<&'a [u8], Chunk>,
+    F: FnMut(&'a [u8]) -> IResult<&'a [u8], Chunk> + 'a,
 {
-    terminated(
-        preceded(tag(name), parser), // TODO: Collect length buffer so callers don't have to, map_parser maybe?
-        le_u32,                      // TODO: Handle CRC?
+    map(
+        verify(
+            tuple((consumed(preceded(tag(name), parser)), le_u32)),
+            move |((body, _chunk), crc): &((&[u8], Chunk), u32)| {
+                !verify_crc || crc32(body) == *crc
+            },
+        ),
+        |((_, chunk), _crc)| chunk,
     )
 }
 
-fn img(input: &[u8]) -> IResult<&[u8], Chunk> {
+fn img(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
     chunk_common(
         "IMG ",
-        map(preceded(le_u32, dict), |dict| Chunk::Img { dict }),
+        verify_crc,
+        map(map_parser(length_data(le_u32), dict), |dict| Chunk::Img {
+            dict,
+        }),
     )(input)
 }
 
-fn prev(input: &[u8]) -> IResult<&[u8], Chunk> {
+fn prev(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
     chunk_common(
         "PREV",
+        verify_crc,
         map(length_data(le_u32), |data: &[u8]| Chunk::Prev {
             data: data.to_vec(),
         }),
     )(input)
 }
 
-fn bl16(input: &[u8]) -> IResult<&[u8], Chunk> {
+fn bl16(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
     chunk_common(
         "BL16",
+        verify_crc,
         map(length_data(le_u32), |data: &[u8]| Chunk::Bl16 {
             data: data.to_vec(),
         }),
@@ -111,41 +405,213 @@ fn block(input: &[u8]) -> IResult<&[u8], Block> {
     )(input)
 }
 
-fn layr(input: &[u8]) -> IResult<&[u8], Chunk> {
+fn layr(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
     chunk_common(
         "LAYR",
+        verify_crc,
         map(
-            preceded(le_u32, tuple((length_count(le_u32, block), dict))),
+            map_parser(length_data(le_u32), tuple((length_count(le_u32, block), dict))),
             |(blocks, dict)| Chunk::Layr { blocks, dict },
         ),
     )(input)
 }
 
-fn camr(input: &[u8]) -> IResult<&[u8], Chunk> {
+fn camr(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
     chunk_common(
         "CAMR",
-        map(preceded(le_u32, dict), |dict| Chunk::Camr { dict }),
+        verify_crc,
+        map(map_parser(length_data(le_u32), dict), |dict| Chunk::Camr {
+            dict,
+        }),
     )(input)
 }
 
-fn ligh(input: &[u8]) -> IResult<&[u8], Chunk> {
+fn ligh(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
     chunk_common(
         "LIGH",
-        map(preceded(le_u32, dict), |dict| Chunk::Ligh { dict }),
+        verify_crc,
+        map(map_parser(length_data(le_u32), dict), |dict| Chunk::Ligh {
+            dict,
+        }),
     )(input)
 }
 
-fn chunk(input: &[u8]) -> IResult<&[u8], Chunk> {
-    alt((img, prev, bl16, layr, camr, ligh))(input)
+fn chunk(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
+    alt((
+        |i| img(i, verify_crc),
+        |i| prev(i, verify_crc),
+        |i| bl16(i, verify_crc),
+        |i| layr(i, verify_crc),
+        |i| camr(i, verify_crc),
+        |i| ligh(i, verify_crc),
+    ))(input)
 }
 
-pub fn parse(input: &[u8]) -> IResult<&[u8], Goxel> {
+fn parse_nom(input: &[u8], verify_crc: bool) -> IResult<&[u8], Goxel> {
     map(
-        preceded(tag("GOX "), tuple((le_i32, many0(chunk)))),
+        preceded(
+            tag("GOX "),
+            tuple((le_i32, many0(|i| chunk(i, verify_crc)))),
+        ),
         |(version, chunks)| Goxel { version, chunks },
     )(input)
 }
 
+const KNOWN_TAGS: [&[u8; 4]; 6] = [b"IMG ", b"PREV", b"BL16", b"LAYR", b"CAMR", b"LIGH"];
+
+/// Figures out, after `many0` has given up at `rest`, which `GoxError`
+/// variant best explains why the remaining bytes didn't form another
+/// chunk. `offset` is computed from how much of `original` was consumed.
+fn diagnose(original: &[u8], rest: &[u8], verify_crc: bool) -> GoxError {
+    let offset = original.len() - rest.len();
+
+    let Some(tag_bytes) = rest.get(0..4) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+    let tag: [u8; 4] = tag_bytes.try_into().unwrap();
+    if !KNOWN_TAGS.contains(&&tag) {
+        return GoxError::UnknownChunk { tag, offset };
+    }
+
+    let Some(size_bytes) = rest.get(4..8) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+    let size = u32::from_le_bytes(size_bytes.try_into().unwrap()) as usize;
+    let body_end = 8 + size;
+
+    let Some(framed) = rest.get(..body_end) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+    let Some(crc_bytes) = rest.get(body_end..body_end + 4) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+
+    let stored_crc = u32::from_le_bytes(crc_bytes.try_into().unwrap());
+    if verify_crc {
+        let computed = crc32(framed);
+        if computed != stored_crc {
+            return GoxError::CrcMismatch {
+                expected: stored_crc,
+                found: computed,
+                offset,
+            };
+        }
+    }
+
+    GoxError::DictDecode { offset }
+}
+
+fn parse_with(input: &[u8], verify_crc: bool) -> Result<Goxel, GoxError> {
+    if !input.starts_with(b"GOX ") {
+        return Err(GoxError::BadMagic);
+    }
+    if input.len() < 8 {
+        return Err(GoxError::TruncatedChunk { offset: 4 });
+    }
+
+    let (rest, goxel) =
+        parse_nom(input, verify_crc).expect("magic and version were already validated above");
+    if !rest.is_empty() {
+        return Err(diagnose(input, rest, verify_crc));
+    }
+    Ok(goxel)
+}
+
+/// Parses a `.gox` byte stream, ignoring each chunk's CRC-32 trailer.
+pub fn parse(input: &[u8]) -> Result<Goxel, GoxError> {
+    parse_with(input, false)
+}
+
+/// Parses a `.gox` byte stream, verifying each chunk's CRC-32 trailer and
+/// failing instead of silently accepting a corrupt chunk.
+pub fn parse_verified(input: &[u8]) -> Result<Goxel, GoxError> {
+    parse_with(input, true)
+}
+
+/// Encodes `data` as a `le_u32` length prefix followed by the bytes
+/// themselves, mirroring `length_data(le_u32)` on the read side.
+fn write_length_prefixed(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+}
+
+/// Encodes a dict's entries as `key_len/key/value_len/value` tuples in their
+/// `IndexMap` order (the order they were parsed in, for a parsed chunk), then
+/// the trailing 0-length-key terminator that marks the end of the dict on
+/// disk.
+fn encode_dict(dict: &IndexMap<String, Vec<u8>>) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (key, value) in dict {
+        write_length_prefixed(&mut out, key.as_bytes());
+        write_length_prefixed(&mut out, value);
+    }
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out
+}
+
+impl Block {
+    fn encode(&self) -> [u8; 20] {
+        let mut out = [0u8; 20];
+        for (i, field) in [self.index, self.x, self.y, self.z, 0].iter().enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&field.to_le_bytes());
+        }
+        out
+    }
+}
+
+impl Chunk {
+    /// Encodes this chunk's type tag, length-prefixed body and CRC-32
+    /// trailer, in the same framing `chunk_common` expects to read back.
+    fn to_bytes(&self) -> Vec<u8> {
+        let (tag, body): (&[u8; 4], Vec<u8>) = match self {
+            Chunk::Img { dict } => (b"IMG ", encode_dict(dict)),
+            Chunk::Prev { data } => (b"PREV", data.clone()),
+            Chunk::Bl16 { data } => (b"BL16", data.clone()),
+            Chunk::Layr { blocks, dict } => {
+                let mut inner = Vec::new();
+                inner.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+                for block in blocks {
+                    inner.extend_from_slice(&block.encode());
+                }
+                inner.extend_from_slice(&encode_dict(dict));
+                (b"LAYR", inner)
+            }
+            Chunk::Camr { dict } => (b"CAMR", encode_dict(dict)),
+            Chunk::Ligh { dict } => (b"LIGH", encode_dict(dict)),
+        };
+
+        let mut framed = Vec::with_capacity(4 + 4 + body.len());
+        framed.extend_from_slice(tag);
+        write_length_prefixed(&mut framed, &body);
+
+        let crc = crc32(&framed);
+        framed.extend_from_slice(&crc.to_le_bytes());
+        framed
+    }
+}
+
+impl Goxel {
+    /// Writes this `Goxel` back out as a `.gox` byte stream: the `"GOX "`
+    /// magic, the version, then each chunk in the framing `parse` expects.
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"GOX ")?;
+        w.write_all(&self.version.to_le_bytes())?;
+        for chunk in &self.chunks {
+            w.write_all(&chunk.to_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Convenience wrapper around [`Goxel::write`] for callers who just want
+    /// the encoded bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out)
+            .expect("writing to a Vec<u8> cannot fail");
+        out
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -163,6 +629,272 @@ mod test {
             0x0, 0x0, 0x0, 0x0, // CRC
         ];
 
-        let res = img(input).expect("Couldn't get img chunk");
+        img(input, false).expect("Couldn't get img chunk");
+    }
+
+    #[test]
+    fn parse_verified_rejects_bad_crc() {
+        let input: &[u8] = &[
+            b'B', b'L', b'1', b'6', // Type
+            0x3, 0x0, 0x0, 0x0, // Size
+            0xAA, 0xBB, 0xCC, // Data
+            0x0, 0x0, 0x0, 0x0, // CRC (wrong)
+        ];
+
+        bl16(input, false).expect("lenient parse should ignore the bad CRC");
+        bl16(input, true).expect_err("verified parse should reject the bad CRC");
+    }
+
+    #[test]
+    fn parse_verified_accepts_correct_crc() {
+        let mut input: Vec<u8> = vec![
+            b'B', b'L', b'1', b'6', // Type
+            0x3, 0x0, 0x0, 0x0, // Size
+            0xAA, 0xBB, 0xCC, // Data
+        ];
+        let crc = crc32(&input);
+        input.extend_from_slice(&crc.to_le_bytes());
+
+        bl16(&input, true).expect("verified parse should accept a correct CRC");
+    }
+
+    #[test]
+    fn voxels_from_image_maps_slices_to_pixel_offsets() {
+        // A 64x64 image where every pixel encodes its own (x, y) position,
+        // so we can check each z-slice was read from the right 16x16 tile.
+        let mut rgba = vec![0u8; 64 * 64 * 4];
+        for py in 0..64usize {
+            for px in 0..64usize {
+                let i = (py * 64 + px) * 4;
+                rgba[i..i + 4].copy_from_slice(&[px as u8, py as u8, 0, 255]);
+            }
+        }
+        let image = png::Image {
+            width: 64,
+            height: 64,
+            rgba,
+        };
+
+        let voxels = voxels_from_image(&image);
+        #[allow(clippy::needless_range_loop)]
+        for z in 0..16usize {
+            let tile_x = (z % 4) * 16;
+            let tile_y = (z / 4) * 16;
+            assert_eq!(
+                voxels[3][5][z],
+                [(tile_x + 3) as u8, (tile_y + 5) as u8, 0, 255]
+            );
+        }
+    }
+
+    #[test]
+    fn decode_bl16_rejects_a_wrong_sized_image() {
+        let png = include_bytes!("../tests/fixtures/fixed_huffman.png");
+
+        let err = decode_bl16(png).expect_err("a 3x2 PNG isn't a valid BL16 payload");
+        assert!(matches!(
+            err,
+            VoxelError::UnexpectedImageSize {
+                width: 3,
+                height: 2,
+            }
+        ));
+    }
+
+    #[test]
+    fn write_then_parse_then_write_round_trips_byte_identical() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img {
+                    dict: IndexMap::from([("name".to_string(), b"test".to_vec())]),
+                },
+                Chunk::Prev {
+                    data: vec![1, 2, 3, 4],
+                },
+                Chunk::Bl16 {
+                    data: vec![9, 9, 9],
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 16,
+                        y: 32,
+                        z: 48,
+                    }],
+                    dict: IndexMap::from([("mat".to_string(), vec![0u8; 4])]),
+                },
+                Chunk::Camr {
+                    dict: IndexMap::from([("dist".to_string(), vec![1, 0, 0, 0])]),
+                },
+                Chunk::Ligh {
+                    dict: IndexMap::from([("pitch".to_string(), vec![0, 0, 0, 0])]),
+                },
+            ],
+        };
+
+        let original = goxel.to_bytes();
+        let parsed = parse(&original).expect("should parse what we just wrote");
+        assert_eq!(parsed.to_bytes(), original);
+    }
+
+    #[test]
+    fn parse_then_write_round_trips_a_real_chunk_byte_identical() {
+        // A hand-assembled IMG chunk, framed exactly like a real .gox file:
+        // a two-entry dict followed by its 0-length-key terminator, all
+        // within the bounds the chunk's size field declares.
+        let mut body = Vec::new();
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(b"name");
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(b"test");
+        body.extend_from_slice(&3u32.to_le_bytes());
+        body.extend_from_slice(b"box");
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0u8]);
+        body.extend_from_slice(&0u32.to_le_bytes()); // dict terminator
+
+        let mut chunk = Vec::new();
+        chunk.extend_from_slice(b"IMG ");
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let goxel = parse_verified(&input).expect("should parse a well-formed real chunk");
+        assert_eq!(goxel.to_bytes(), input);
+    }
+
+    #[test]
+    fn parse_reports_bad_magic() {
+        let err = parse(b"NOPE").unwrap_err();
+        assert!(matches!(err, GoxError::BadMagic));
+    }
+
+    #[test]
+    fn parse_reports_unknown_chunk() {
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(b"NOPE");
+
+        let err = parse(&input).unwrap_err();
+        assert!(matches!(
+            err,
+            GoxError::UnknownChunk {
+                tag: [b'N', b'O', b'P', b'E'],
+                offset: 8,
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_verified_reports_crc_mismatch() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Prev {
+                data: vec![1, 2, 3],
+            }],
+        };
+        let mut bytes = goxel.to_bytes();
+        *bytes.last_mut().unwrap() ^= 0xFF; // corrupt the stored CRC
+
+        let err = parse_verified(&bytes).unwrap_err();
+        assert!(matches!(err, GoxError::CrcMismatch { offset: 8, .. }));
+    }
+
+    fn identity_mat() -> [f32; 16] {
+        let mut mat = [0.0f32; 16];
+        mat[0] = 1.0;
+        mat[5] = 1.0;
+        mat[10] = 1.0;
+        mat[15] = 1.0;
+        mat
+    }
+
+    fn mat_bytes(mat: [f32; 16]) -> Vec<u8> {
+        mat.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn as_camera_decodes_a_well_formed_dict() {
+        let mat = identity_mat();
+        let chunk = Chunk::Camr {
+            dict: IndexMap::from([
+                ("mat".to_string(), mat_bytes(mat)),
+                ("dist".to_string(), 10.0f32.to_le_bytes().to_vec()),
+                ("ortho".to_string(), 1i32.to_le_bytes().to_vec()),
+            ]),
+        };
+
+        let view = chunk.as_camera().expect("should decode a camera view");
+        assert_eq!(view.mat, mat);
+        assert_eq!(view.dist, 10.0);
+        assert!(view.ortho);
+    }
+
+    #[test]
+    fn as_camera_rejects_other_chunk_types() {
+        let chunk = Chunk::Ligh {
+            dict: IndexMap::new(),
+        };
+        assert_eq!(chunk.as_camera(), None);
+    }
+
+    #[test]
+    fn as_camera_rejects_missing_keys() {
+        let chunk = Chunk::Camr {
+            dict: IndexMap::new(),
+        };
+        assert_eq!(chunk.as_camera(), None);
+    }
+
+    #[test]
+    fn as_layer_decodes_a_well_formed_dict() {
+        let mat = identity_mat();
+        let chunk = Chunk::Layr {
+            blocks: vec![],
+            dict: IndexMap::from([
+                ("name".to_string(), b"Layer 1".to_vec()),
+                ("mat".to_string(), mat_bytes(mat)),
+                ("visible".to_string(), 0i32.to_le_bytes().to_vec()),
+            ]),
+        };
+
+        let view = chunk.as_layer().expect("should decode a layer view");
+        assert_eq!(view.name, "Layer 1");
+        assert_eq!(view.mat, mat);
+        assert!(!view.visible);
+        assert_eq!(view.base_id, -1);
+    }
+
+    #[test]
+    fn as_light_decodes_a_well_formed_dict() {
+        let chunk = Chunk::Ligh {
+            dict: IndexMap::from([
+                ("pitch".to_string(), 1.5f32.to_le_bytes().to_vec()),
+                ("yaw".to_string(), 2.5f32.to_le_bytes().to_vec()),
+                ("intensity".to_string(), 3.5f32.to_le_bytes().to_vec()),
+            ]),
+        };
+
+        let view = chunk.as_light().expect("should decode a light view");
+        assert_eq!(view.pitch, 1.5);
+        assert_eq!(view.yaw, 2.5);
+        assert_eq!(view.intensity, 3.5);
+    }
+
+    #[test]
+    fn as_image_decodes_a_well_formed_dict() {
+        let mat = identity_mat();
+        let chunk = Chunk::Img {
+            dict: IndexMap::from([("box".to_string(), mat_bytes(mat))]),
+        };
+
+        let view = chunk.as_image().expect("should decode an image view");
+        assert_eq!(view.bounding_box, mat);
     }
 }
@@ -0,0 +1,15 @@
+//! Builds a 3-voxel diagonal line with [`ModelBuilder`] and writes it out
+//! as `line.gox`.
+
+use gox::parser::ModelBuilder;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = ModelBuilder::new();
+    builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+    builder.set_voxel(1, 1, 1, [0, 255, 0, 255]);
+    builder.set_voxel(2, 2, 2, [0, 0, 255, 255]);
+    builder.add_layer("diagonal line");
+
+    builder.build().save("line.gox")?;
+    Ok(())
+}
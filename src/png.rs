@@ -0,0 +1,419 @@
+//! A minimal PNG decoder.
+//!
+//! Goxel stores each `BL16` block as a 64×64, 8-bit RGBA, non-interlaced
+//! PNG. This module only implements enough of the PNG/zlib/DEFLATE spec to
+//! read that shape back out: stored, fixed-Huffman and dynamic-Huffman
+//! DEFLATE blocks, and the five standard PNG filter types.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum PngError {
+    BadSignature,
+    MissingIhdr,
+    UnsupportedFormat,
+    Truncated,
+    Inflate(&'static str),
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PngError::BadSignature => write!(f, "not a PNG file"),
+            PngError::MissingIhdr => write!(f, "PNG is missing its IHDR chunk"),
+            PngError::UnsupportedFormat => {
+                write!(f, "unsupported PNG format (expected 8-bit RGBA, non-interlaced)")
+            }
+            PngError::Truncated => write!(f, "PNG data ended unexpectedly"),
+            PngError::Inflate(msg) => write!(f, "DEFLATE stream error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// 8-bit RGBA pixels, row-major, top to bottom.
+    pub rgba: Vec<u8>,
+}
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Decodes a PNG byte stream into a flat RGBA buffer.
+pub fn decode(data: &[u8]) -> Result<Image, PngError> {
+    if data.len() < 8 || data[..8] != SIGNATURE {
+        return Err(PngError::BadSignature);
+    }
+
+    let mut width = None;
+    let mut height = None;
+    let mut idat = Vec::new();
+    let mut pos = 8;
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body_start = pos + 8;
+        let body_end = body_start.checked_add(len).ok_or(PngError::Truncated)?;
+        let body = data.get(body_start..body_end).ok_or(PngError::Truncated)?;
+
+        match kind {
+            b"IHDR" => {
+                if body.len() < 13 {
+                    return Err(PngError::Truncated);
+                }
+                width = Some(u32::from_be_bytes(body[0..4].try_into().unwrap()));
+                height = Some(u32::from_be_bytes(body[4..8].try_into().unwrap()));
+                let bit_depth = body[8];
+                let color_type = body[9];
+                let interlace = body[12];
+                if bit_depth != 8 || color_type != 6 || interlace != 0 {
+                    return Err(PngError::UnsupportedFormat);
+                }
+            }
+            b"IDAT" => idat.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+
+        pos = body_end + 4; // skip the trailing CRC
+    }
+
+    let width = width.ok_or(PngError::MissingIhdr)?;
+    let height = height.ok_or(PngError::MissingIhdr)?;
+
+    let raw = inflate_zlib(&idat)?;
+    let rgba = unfilter(&raw, width, height)?;
+
+    Ok(Image {
+        width,
+        height,
+        rgba,
+    })
+}
+
+fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
+    let (a, b, c) = (a as i32, b as i32, c as i32);
+    let p = a + b - c;
+    let pa = (p - a).abs();
+    let pb = (p - b).abs();
+    let pc = (p - c).abs();
+    if pa <= pb && pa <= pc {
+        a as u8
+    } else if pb <= pc {
+        b as u8
+    } else {
+        c as u8
+    }
+}
+
+fn unfilter(raw: &[u8], width: u32, height: u32) -> Result<Vec<u8>, PngError> {
+    const BPP: usize = 4; // 8-bit RGBA
+    let stride = width as usize * BPP;
+    let mut out = vec![0u8; stride * height as usize];
+
+    let mut src = raw;
+    for row in 0..height as usize {
+        let filter = *src.first().ok_or(PngError::Truncated)?;
+        let line = src.get(1..1 + stride).ok_or(PngError::Truncated)?;
+        src = &src[1 + stride..];
+
+        let (prev, cur) = out.split_at_mut(row * stride);
+        let cur = &mut cur[..stride];
+        let prev = if row == 0 {
+            None
+        } else {
+            Some(&prev[(row - 1) * stride..row * stride])
+        };
+
+        for i in 0..stride {
+            let a = if i >= BPP { cur[i - BPP] } else { 0 };
+            let b = prev.map_or(0, |p| p[i]);
+            let c = if i >= BPP {
+                prev.map_or(0, |p| p[i - BPP])
+            } else {
+                0
+            };
+            cur[i] = match filter {
+                0 => line[i],
+                1 => line[i].wrapping_add(a),
+                2 => line[i].wrapping_add(b),
+                3 => line[i].wrapping_add(((a as u16 + b as u16) / 2) as u8),
+                4 => line[i].wrapping_add(paeth_predictor(a, b, c)),
+                _ => return Err(PngError::Inflate("unknown filter type")),
+            };
+        }
+    }
+
+    Ok(out)
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bitbuf: u32,
+    bitcnt: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            pos: 0,
+            bitbuf: 0,
+            bitcnt: 0,
+        }
+    }
+
+    fn take(&mut self, n: u32) -> Result<u32, PngError> {
+        while self.bitcnt < n {
+            let byte = *self.data.get(self.pos).ok_or(PngError::Truncated)?;
+            self.pos += 1;
+            self.bitbuf |= (byte as u32) << self.bitcnt;
+            self.bitcnt += 8;
+        }
+        let v = self.bitbuf & ((1u32 << n) - 1);
+        self.bitbuf >>= n;
+        self.bitcnt -= n;
+        Ok(v)
+    }
+
+    fn align_to_byte(&mut self) {
+        self.bitbuf = 0;
+        self.bitcnt = 0;
+    }
+}
+
+/// A canonical Huffman decoder built from a list of per-symbol code lengths,
+/// decoded bit-by-bit as described in RFC 1951 §3.2.2.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn build(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for len in 1..16 {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (sym, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = sym as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, br: &mut BitReader) -> Result<u16, PngError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..16 {
+            code |= br.take(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(PngError::Inflate("invalid Huffman code"))
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lit_lengths = [0u8; 288];
+    for (i, len) in lit_lengths.iter_mut().enumerate() {
+        *len = match i {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    let dist_lengths = [5u8; 30];
+    (Huffman::build(&lit_lengths), Huffman::build(&dist_lengths))
+}
+
+fn dynamic_tables(br: &mut BitReader) -> Result<(Huffman, Huffman), PngError> {
+    let hlit = br.take(5)? as usize + 257;
+    let hdist = br.take(5)? as usize + 1;
+    let hclen = br.take(4)? as usize + 4;
+
+    let mut cl_lengths = [0u8; 19];
+    for &i in CODE_LENGTH_ORDER.iter().take(hclen) {
+        cl_lengths[i] = br.take(3)? as u8;
+    }
+    let cl_huffman = Huffman::build(&cl_lengths);
+
+    let mut lengths = Vec::with_capacity(hlit + hdist);
+    while lengths.len() < hlit + hdist {
+        match cl_huffman.decode(br)? {
+            sym @ 0..=15 => lengths.push(sym as u8),
+            16 => {
+                let prev = *lengths.last().ok_or(PngError::Inflate("repeat with no previous code"))?;
+                let rep = br.take(2)? + 3;
+                for _ in 0..rep {
+                    lengths.push(prev);
+                }
+            }
+            17 => {
+                let rep = br.take(3)? + 3;
+                lengths.resize(lengths.len() + rep as usize, 0);
+            }
+            18 => {
+                let rep = br.take(7)? + 11;
+                lengths.resize(lengths.len() + rep as usize, 0);
+            }
+            _ => return Err(PngError::Inflate("invalid code length symbol")),
+        }
+    }
+
+    let dist_lengths = lengths.split_off(hlit);
+    Ok((Huffman::build(&lengths), Huffman::build(&dist_lengths)))
+}
+
+fn inflate_block(
+    br: &mut BitReader,
+    lit: &Huffman,
+    dist: &Huffman,
+    out: &mut Vec<u8>,
+) -> Result<(), PngError> {
+    loop {
+        let sym = lit.decode(br)?;
+        match sym {
+            0..=255 => out.push(sym as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let i = (sym - 257) as usize;
+                let len = LENGTH_BASE[i] as usize + br.take(LENGTH_EXTRA[i] as u32)? as usize;
+                let dsym = dist.decode(br)? as usize;
+                let distance =
+                    DIST_BASE[dsym] as usize + br.take(DIST_EXTRA[dsym] as u32)? as usize;
+                let start = out
+                    .len()
+                    .checked_sub(distance)
+                    .ok_or(PngError::Inflate("back-reference distance too large"))?;
+                for i in 0..len {
+                    let byte = out[start + i];
+                    out.push(byte);
+                }
+            }
+            _ => return Err(PngError::Inflate("invalid length code")),
+        }
+    }
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>, PngError> {
+    let mut br = BitReader::new(data);
+    let mut out = Vec::new();
+    loop {
+        let bfinal = br.take(1)?;
+        let btype = br.take(2)?;
+        match btype {
+            0 => {
+                br.align_to_byte();
+                let len = *br.data.get(br.pos).ok_or(PngError::Truncated)? as usize
+                    | (*br.data.get(br.pos + 1).ok_or(PngError::Truncated)? as usize) << 8;
+                br.pos += 4; // LEN + NLEN (NLEN is the one's complement of LEN, unchecked)
+                let bytes = br.data.get(br.pos..br.pos + len).ok_or(PngError::Truncated)?;
+                out.extend_from_slice(bytes);
+                br.pos += len;
+            }
+            1 => {
+                let (lit, dist) = fixed_tables();
+                inflate_block(&mut br, &lit, &dist, &mut out)?;
+            }
+            2 => {
+                let (lit, dist) = dynamic_tables(&mut br)?;
+                inflate_block(&mut br, &lit, &dist, &mut out)?;
+            }
+            _ => return Err(PngError::Inflate("reserved block type")),
+        }
+        if bfinal == 1 {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+/// Strips the 2-byte zlib header (and ignores the trailing Adler-32) before
+/// inflating the raw DEFLATE stream within.
+fn inflate_zlib(data: &[u8]) -> Result<Vec<u8>, PngError> {
+    if data.len() < 6 {
+        return Err(PngError::Truncated);
+    }
+    inflate(&data[2..data.len() - 4])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unfilter_none_is_passthrough() {
+        let raw = [0, 1, 2, 3, 4]; // filter byte 0, one RGBA pixel
+        let rgba = unfilter(&raw, 1, 1).expect("should unfilter");
+        assert_eq!(rgba, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn paeth_predictor_picks_closest_neighbour() {
+        assert_eq!(paeth_predictor(10, 20, 10), 20);
+        assert_eq!(paeth_predictor(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn decodes_a_fixed_huffman_compressed_png() {
+        let png = include_bytes!("../tests/fixtures/fixed_huffman.png");
+        let pixels = include_bytes!("../tests/fixtures/fixed_huffman.pixels");
+
+        let image = decode(png).expect("should decode a fixed-Huffman IDAT stream");
+        assert_eq!(image.width, 3);
+        assert_eq!(image.height, 2);
+        assert_eq!(image.rgba, pixels);
+    }
+
+    #[test]
+    fn decodes_a_dynamic_huffman_compressed_png() {
+        let png = include_bytes!("../tests/fixtures/dynamic_huffman.png");
+        let pixels = include_bytes!("../tests/fixtures/dynamic_huffman.pixels");
+
+        let image = decode(png).expect("should decode a dynamic-Huffman IDAT stream");
+        assert_eq!(image.width, 16);
+        assert_eq!(image.height, 16);
+        assert_eq!(image.rgba, pixels);
+    }
+}
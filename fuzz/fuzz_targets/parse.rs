@@ -0,0 +1,19 @@
+//! Feeds arbitrary bytes to `Goxel::from_bytes`, then exercises the
+//! higher-level assembly methods that actually walk the parsed chunks
+//! (`model`, `scene`, `validate`), since those are where a well-formed but
+//! hostile `BL16`/`LAYR` combination (a dangling block reference, an
+//! out-of-range coordinate, a malformed PNG payload) would otherwise only
+//! surface under real use. Nothing here should ever panic, allocate
+//! unreasonably, or run for an unreasonable amount of time — a `Result::Err`
+//! is always an acceptable outcome.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(goxel) = gox::parser::Goxel::from_bytes(data) {
+        let _ = goxel.validate();
+        let _ = goxel.model();
+        let _ = goxel.scene();
+    }
+});
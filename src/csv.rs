@@ -0,0 +1,62 @@
+//! A CSV exporter: one row per occupied voxel, for pipelines that just
+//! want to load voxel data into a spreadsheet or pandas rather than a 3D
+//! tool.
+
+use crate::parser::Model;
+use std::io::{self, Write};
+
+/// Writes `model` to `w` as CSV: an `x,y,z,r,g,b,a` header line, then one
+/// row per occupied voxel. Streams row by row rather than building a
+/// `String` up front, so a huge model doesn't need to fit in memory twice.
+pub fn write<W: Write>(model: &Model, w: &mut W) -> io::Result<()> {
+    writeln!(w, "x,y,z,r,g,b,a")?;
+    for ([x, y, z], [r, g, b, a]) in model.iter() {
+        writeln!(w, "{x},{y},{z},{r},{g},{b},{a}")?;
+    }
+    Ok(())
+}
+
+/// Convenience wrapper around [`write`] for callers who just want the CSV
+/// text.
+pub fn to_string(model: &Model) -> String {
+    let mut out = Vec::new();
+    write(model, &mut out).expect("writing to a Vec<u8> cannot fail");
+    String::from_utf8(out).expect("CSV output is always valid utf-8")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_string_emits_a_header_and_one_row_per_voxel() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 2, 3), [10, 20, 30, 128]),
+        ]);
+
+        let csv = to_string(&model);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("x,y,z,r,g,b,a"));
+        assert_eq!(lines.clone().count(), 2);
+        assert!(csv.contains("0,0,0,255,0,0,255"));
+        assert!(csv.contains("1,2,3,10,20,30,128"));
+    }
+
+    #[test]
+    fn to_string_on_an_empty_model_is_just_the_header() {
+        let model = Model::default();
+        assert_eq!(to_string(&model), "x,y,z,r,g,b,a\n");
+    }
+
+    #[test]
+    fn write_and_to_string_produce_the_same_bytes() {
+        let mut model = Model::default();
+        model.extend([((5, 5, 5), [1, 2, 3, 4])]);
+
+        let mut buf = Vec::new();
+        write(&model, &mut buf).expect("writing to a Vec<u8> cannot fail");
+        assert_eq!(String::from_utf8(buf).unwrap(), to_string(&model));
+    }
+}
@@ -0,0 +1,95 @@
+//! A uniform interface over this crate's voxel-model exporters, so a caller
+//! can hold a `&dyn Exporter` (or be generic over `E: Exporter`) instead of
+//! hard-coding a specific output format. Implemented here for the built-in
+//! [`ObjExporter`], [`VoxExporter`] and [`PlyExporter`]; users can implement
+//! [`Exporter`] for their own formats to plug into the same pipeline.
+
+use crate::obj;
+use crate::parser::{GoxError, Model};
+use crate::ply::{self, PlyFormat};
+use crate::vox;
+
+/// Converts a [`Model`] into a specific file format's bytes.
+pub trait Exporter {
+    fn export(&self, model: &Model) -> Result<Vec<u8>, GoxError>;
+}
+
+/// Exports to Wavefront `.obj`, via [`crate::obj::export`]. This trait's
+/// single-buffer `Result<Vec<u8>, GoxError>` only has room for the `.obj`
+/// text itself; call [`crate::obj::export`] directly for the companion
+/// `.mtl` it also produces.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ObjExporter {
+    pub include_normals: bool,
+}
+
+impl Exporter for ObjExporter {
+    fn export(&self, model: &Model) -> Result<Vec<u8>, GoxError> {
+        let (obj, _mtl) = obj::export(model, self.include_normals)?;
+        Ok(obj.into_bytes())
+    }
+}
+
+/// Exports to MagicaVoxel's `.vox`, via [`crate::vox::export`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VoxExporter;
+
+impl Exporter for VoxExporter {
+    fn export(&self, model: &Model) -> Result<Vec<u8>, GoxError> {
+        Ok(vox::export(model)?)
+    }
+}
+
+/// Exports to the Stanford `.ply` point-cloud format, via
+/// [`crate::ply::export`].
+#[derive(Debug, Clone, Copy)]
+pub struct PlyExporter {
+    pub format: PlyFormat,
+}
+
+impl Default for PlyExporter {
+    fn default() -> Self {
+        PlyExporter { format: PlyFormat::Ascii }
+    }
+}
+
+impl Exporter for PlyExporter {
+    fn export(&self, model: &Model) -> Result<Vec<u8>, GoxError> {
+        Ok(ply::export(model, self.format)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_model() -> Model {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+        model
+    }
+
+    #[test]
+    fn each_built_in_exporter_produces_non_empty_bytes_through_the_trait() {
+        let model = sample_model();
+        let exporters: Vec<Box<dyn Exporter>> = vec![
+            Box::new(ObjExporter::default()),
+            Box::new(VoxExporter),
+            Box::new(PlyExporter::default()),
+        ];
+
+        for exporter in exporters {
+            let bytes = exporter.export(&model).expect("a single-voxel model should export");
+            assert!(!bytes.is_empty());
+        }
+    }
+
+    #[test]
+    fn obj_exporter_error_surfaces_as_a_gox_error() {
+        let model = Model::default();
+        assert!(matches!(
+            ObjExporter::default().export(&model),
+            Err(GoxError::Obj(obj::ObjError::Empty))
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+//! An exporter to binary glTF 2.0 (`.glb`), embedding the greedy-meshed
+//! geometry and per-vertex colors in a single self-contained buffer. Hand
+//! rolled (no `gltf`/`serde_json` dependency) the same way [`crate::png`]
+//! hand rolls PNG encoding: the format is small enough, and JSON/GLB
+//! chunking, that pulling in a whole crate for it isn't worth it.
+
+use crate::mesh::{greedy_mesh, Mesh};
+use crate::parser::Model;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GltfError {
+    #[error("model has no voxels to export")]
+    Empty,
+}
+
+const MAGIC: u32 = 0x4654_6C67; // "glTF"
+const GLTF_VERSION: u32 = 2;
+const JSON_CHUNK_TYPE: u32 = 0x4E4F_534A; // "JSON"
+const CHUNK_TYPE_BIN: u32 = 0x004E_4942; // "BIN\0"
+
+const COMPONENT_TYPE_FLOAT: u32 = 5126;
+const COMPONENT_TYPE_UNSIGNED_INT: u32 = 5125;
+const COMPONENT_TYPE_UNSIGNED_BYTE: u32 = 5121;
+
+fn pad_to_4(buf: &mut Vec<u8>, fill: u8) {
+    while !buf.len().is_multiple_of(4) {
+        buf.push(fill);
+    }
+}
+
+/// Lays out a [`Mesh`]'s positions, indices and per-vertex colors into one
+/// binary buffer, returning it alongside each section's byte range.
+/// [`Mesh`] stores one color per merged quad rather than per vertex, so
+/// each quad's 4 corners repeat that quad's color here to give every
+/// position a matching `COLOR_0` entry.
+fn pack_buffer(mesh: &Mesh) -> (Vec<u8>, [(usize, usize); 3]) {
+    let mut bin = Vec::new();
+
+    let position_offset = bin.len();
+    for position in &mesh.positions {
+        for component in position {
+            bin.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+    let position_range = (position_offset, bin.len() - position_offset);
+    pad_to_4(&mut bin, 0);
+
+    let indices_offset = bin.len();
+    for &index in &mesh.indices {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    let indices_range = (indices_offset, bin.len() - indices_offset);
+    pad_to_4(&mut bin, 0);
+
+    let color_offset = bin.len();
+    for &color in &mesh.colors {
+        for _ in 0..4 {
+            bin.extend_from_slice(&color);
+        }
+    }
+    let color_range = (color_offset, bin.len() - color_offset);
+    pad_to_4(&mut bin, 0);
+
+    (bin, [position_range, indices_range, color_range])
+}
+
+fn bounding_box(positions: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for position in positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(position[axis]);
+            max[axis] = max[axis].max(position[axis]);
+        }
+    }
+    (min, max)
+}
+
+/// Builds the glTF JSON chunk describing the single mesh primitive laid
+/// out by [`pack_buffer`]: one `POSITION`/`COLOR_0`-attributed triangle
+/// list, referencing the embedded binary buffer by byte range.
+fn build_json(mesh: &Mesh, buffer_length: usize, ranges: [(usize, usize); 3]) -> String {
+    let [(position_offset, position_length), (indices_offset, indices_length), (color_offset, color_length)] =
+        ranges;
+    let (min, max) = bounding_box(&mesh.positions);
+
+    format!(
+        "{{\
+\"asset\":{{\"version\":\"2.0\",\"generator\":\"gox-rs\"}},\
+\"buffers\":[{{\"byteLength\":{buffer_length}}}],\
+\"bufferViews\":[\
+{{\"buffer\":0,\"byteOffset\":{position_offset},\"byteLength\":{position_length},\"target\":34962}},\
+{{\"buffer\":0,\"byteOffset\":{indices_offset},\"byteLength\":{indices_length},\"target\":34963}},\
+{{\"buffer\":0,\"byteOffset\":{color_offset},\"byteLength\":{color_length},\"target\":34962}}\
+],\
+\"accessors\":[\
+{{\"bufferView\":0,\"componentType\":{COMPONENT_TYPE_FLOAT},\"count\":{vertex_count},\"type\":\"VEC3\",\
+\"min\":[{min0},{min1},{min2}],\"max\":[{max0},{max1},{max2}]}},\
+{{\"bufferView\":1,\"componentType\":{COMPONENT_TYPE_UNSIGNED_INT},\"count\":{index_count},\"type\":\"SCALAR\"}},\
+{{\"bufferView\":2,\"componentType\":{COMPONENT_TYPE_UNSIGNED_BYTE},\"normalized\":true,\"count\":{vertex_count},\"type\":\"VEC4\"}}\
+],\
+\"meshes\":[{{\"primitives\":[{{\"attributes\":{{\"POSITION\":0,\"COLOR_0\":2}},\"indices\":1,\"mode\":4}}]}}],\
+\"nodes\":[{{\"mesh\":0}}],\
+\"scenes\":[{{\"nodes\":[0]}}],\
+\"scene\":0\
+}}",
+        vertex_count = mesh.positions.len(),
+        index_count = mesh.indices.len(),
+        min0 = min[0],
+        min1 = min[1],
+        min2 = min[2],
+        max0 = max[0],
+        max1 = max[1],
+        max2 = max[2],
+    )
+}
+
+/// Wraps a JSON chunk and a binary chunk in the 12-byte GLB container
+/// header, padding each chunk's data to a 4-byte boundary as the format
+/// requires (JSON with trailing spaces, binary with trailing zeros).
+fn to_glb(json: String, mut bin: Vec<u8>) -> Vec<u8> {
+    let mut json = json.into_bytes();
+    pad_to_4(&mut json, b' ');
+    pad_to_4(&mut bin, 0);
+
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut glb = Vec::with_capacity(total_length);
+    glb.extend_from_slice(&MAGIC.to_le_bytes());
+    glb.extend_from_slice(&GLTF_VERSION.to_le_bytes());
+    glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(&JSON_CHUNK_TYPE.to_le_bytes());
+    glb.extend_from_slice(&json);
+
+    glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    glb.extend_from_slice(&CHUNK_TYPE_BIN.to_le_bytes());
+    glb.extend_from_slice(&bin);
+
+    glb
+}
+
+/// Exports `model` as a self-contained binary glTF (`.glb`): the
+/// greedy-meshed geometry (see [`crate::mesh::greedy_mesh`]) as a single
+/// mesh primitive, with one RGBA vertex color per position so importers
+/// that don't read per-face materials still render the right colors.
+pub fn export(model: &Model) -> Result<Vec<u8>, GltfError> {
+    if model.is_empty() {
+        return Err(GltfError::Empty);
+    }
+    let mesh = greedy_mesh(model);
+    let (bin, ranges) = pack_buffer(&mesh);
+    let json = build_json(&mesh, bin.len(), ranges);
+    Ok(to_glb(json, bin))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn export_rejects_an_empty_model() {
+        assert!(matches!(export(&Model::default()), Err(GltfError::Empty)));
+    }
+
+    #[test]
+    fn export_writes_a_well_formed_glb_header_and_json_chunk() {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        let glb = export(&model).expect("should export a single-voxel model");
+
+        assert_eq!(&glb[0..4], &MAGIC.to_le_bytes());
+        assert_eq!(u32::from_le_bytes(glb[4..8].try_into().unwrap()), GLTF_VERSION);
+        let total_length = u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize;
+        assert_eq!(total_length, glb.len());
+
+        let json_length = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        assert_eq!(u32::from_le_bytes(glb[16..20].try_into().unwrap()), JSON_CHUNK_TYPE);
+        let json = std::str::from_utf8(&glb[20..20 + json_length]).expect("JSON chunk should be UTF-8");
+        assert!(json.trim_end().ends_with('}'));
+        assert!(json.contains("\"POSITION\":0"));
+        assert!(json.contains("\"COLOR_0\":2"));
+
+        let bin_header_offset = 20 + json_length;
+        let bin_length = u32::from_le_bytes(
+            glb[bin_header_offset..bin_header_offset + 4].try_into().unwrap(),
+        ) as usize;
+        assert_eq!(
+            u32::from_le_bytes(
+                glb[bin_header_offset + 4..bin_header_offset + 8].try_into().unwrap()
+            ),
+            CHUNK_TYPE_BIN
+        );
+        assert_eq!(bin_header_offset + 8 + bin_length, glb.len());
+    }
+}
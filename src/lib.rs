@@ -0,0 +1,16 @@
+//! A parser (and, increasingly, writer) for Goxel's `.gox` voxel format.
+
+pub mod csv;
+pub mod export;
+#[cfg(feature = "gltf")]
+pub mod gltf;
+pub mod mesh;
+pub mod obj;
+pub mod parser;
+pub mod ply;
+pub mod png;
+#[cfg(feature = "gzip")]
+pub mod schematic;
+pub mod vox;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,226 @@
+//! Greedy meshing: merging coplanar, same-color voxel faces into larger
+//! quads so flat regions of a model don't explode into one quad per voxel.
+//!
+//! This is the standard "greedy meshing" algorithm (as popularized by
+//! Mikola Lysenko's `mikolalysenko/mikolalysenko.github.com` writeups):
+//! for each of the 6 axis-aligned face directions, slice the model into
+//! planes perpendicular to that direction, build a 2D mask of exposed
+//! same-color faces in that plane, then greedily grow same-color
+//! rectangles across the mask.
+
+use crate::parser::Model;
+
+/// The alpha a voxel needs to occlude its neighbors' faces. Below this, a
+/// voxel is treated as see-through (glass, water, ...): it still renders
+/// its own faces, but doesn't cull the face of a neighbor behind it.
+const OPAQUE_ALPHA: u8 = 255;
+
+/// A flat triangle mesh: positions, a triangle index buffer (two triangles,
+/// six indices, per merged quad), and one color per quad.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    pub positions: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+    pub colors: Vec<[u8; 4]>,
+}
+
+impl Mesh {
+    fn push_quad(&mut self, corners: [[f32; 3]; 4], color: [u8; 4]) {
+        let base = self.positions.len() as u32;
+        self.positions.extend_from_slice(&corners);
+        self.indices
+            .extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        self.colors.push(color);
+    }
+}
+
+/// Builds a vertex with `axes[0]` set to `p`, `axes[1]` set to `u`, and
+/// `axes[2]` set to `v`, where `axes` is the `(d, u_axis, v_axis)` triple
+/// for the face direction being meshed.
+fn vertex(axes: (usize, usize, usize), p: f32, u: f32, v: f32) -> [f32; 3] {
+    let mut out = [0.0; 3];
+    out[axes.0] = p;
+    out[axes.1] = u;
+    out[axes.2] = v;
+    out
+}
+
+/// Greedily meshes the exposed faces perpendicular to axis `d` (0=x, 1=y,
+/// 2=z) facing in direction `sign` (+1 or -1), appending merged quads to
+/// `mesh`.
+fn mesh_direction(model: &Model, min: (i32, i32, i32), max: (i32, i32, i32), d: usize, sign: i32, mesh: &mut Mesh) {
+    let min_axes = [min.0, min.1, min.2];
+    let max_axes = [max.0, max.1, max.2];
+    let u_axis = (d + 1) % 3;
+    let v_axis = (d + 2) % 3;
+
+    let width = (max_axes[u_axis] - min_axes[u_axis] + 1) as usize;
+    let height = (max_axes[v_axis] - min_axes[v_axis] + 1) as usize;
+
+    let voxel_at = |axes: [i32; 3]| model.voxel_at(axes[0], axes[1], axes[2]);
+    let occludes = |axes: [i32; 3]| voxel_at(axes).is_some_and(|rgba| rgba[3] >= OPAQUE_ALPHA);
+
+    for plane in min_axes[d]..=max_axes[d] + 1 {
+        let (behind, ahead) = if sign > 0 {
+            (plane - 1, plane)
+        } else {
+            (plane, plane - 1)
+        };
+
+        let mut mask: Vec<Option<[u8; 4]>> = vec![None; width * height];
+        for (j, v) in (min_axes[v_axis]..=max_axes[v_axis]).enumerate() {
+            for (i, u) in (min_axes[u_axis]..=max_axes[u_axis]).enumerate() {
+                let mut behind_pos = [0; 3];
+                behind_pos[d] = behind;
+                behind_pos[u_axis] = u;
+                behind_pos[v_axis] = v;
+                let mut ahead_pos = behind_pos;
+                ahead_pos[d] = ahead;
+
+                let exposed_color = voxel_at(behind_pos).filter(|_| !occludes(ahead_pos));
+                mask[j * width + i] = exposed_color;
+            }
+        }
+
+        let mut visited = vec![false; width * height];
+        for j in 0..height {
+            for i in 0..width {
+                let idx = j * width + i;
+                if visited[idx] {
+                    continue;
+                }
+                let Some(color) = mask[idx] else {
+                    visited[idx] = true;
+                    continue;
+                };
+
+                let mut w = 1;
+                while i + w < width && !visited[idx + w] && mask[idx + w] == Some(color) {
+                    w += 1;
+                }
+
+                let mut h = 1;
+                'grow: while j + h < height {
+                    for k in 0..w {
+                        let row_idx = (j + h) * width + i + k;
+                        if visited[row_idx] || mask[row_idx] != Some(color) {
+                            break 'grow;
+                        }
+                    }
+                    h += 1;
+                }
+
+                for jj in 0..h {
+                    for ii in 0..w {
+                        visited[(j + jj) * width + i + ii] = true;
+                    }
+                }
+
+                let u0 = (min_axes[u_axis] + i as i32) as f32;
+                let u1 = (min_axes[u_axis] + (i + w) as i32) as f32;
+                let v0 = (min_axes[v_axis] + j as i32) as f32;
+                let v1 = (min_axes[v_axis] + (j + h) as i32) as f32;
+                let p = plane as f32;
+                let axes = (d, u_axis, v_axis);
+
+                // u×v = +d by construction (axes are in cyclic x,y,z order),
+                // so this winding faces +d; reverse it for the -d direction.
+                let corners = if sign > 0 {
+                    [
+                        vertex(axes, p, u0, v0),
+                        vertex(axes, p, u1, v0),
+                        vertex(axes, p, u1, v1),
+                        vertex(axes, p, u0, v1),
+                    ]
+                } else {
+                    [
+                        vertex(axes, p, u0, v0),
+                        vertex(axes, p, u0, v1),
+                        vertex(axes, p, u1, v1),
+                        vertex(axes, p, u1, v0),
+                    ]
+                };
+                mesh.push_quad(corners, color);
+            }
+        }
+    }
+}
+
+/// Greedily meshes every exposed face of `model`, merging adjacent coplanar
+/// faces of the same color into larger quads. Returns an empty [`Mesh`] for
+/// an empty model.
+pub fn greedy_mesh(model: &Model) -> Mesh {
+    let mut mesh = Mesh::default();
+    let Some(bbox) = model.bounding_box() else {
+        return mesh;
+    };
+
+    for d in 0..3 {
+        for sign in [1, -1] {
+            mesh_direction(model, bbox.min, bbox.max, d, sign, &mut mesh);
+        }
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn greedy_mesh_of_an_empty_model_is_empty() {
+        let model = Model::default();
+        let mesh = greedy_mesh(&model);
+        assert!(mesh.positions.is_empty());
+        assert!(mesh.indices.is_empty());
+    }
+
+    #[test]
+    fn solid_cube_produces_six_merged_quads() {
+        let mut model = Model::default();
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    model.extend([((x, y, z), [200, 100, 50, 255])]);
+                }
+            }
+        }
+
+        let mesh = greedy_mesh(&model);
+        assert_eq!(mesh.colors.len(), 6);
+        assert_eq!(mesh.positions.len(), 6 * 4);
+        assert_eq!(mesh.indices.len(), 6 * 6);
+    }
+
+    #[test]
+    fn differently_colored_neighbors_dont_merge_across_the_boundary() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let mesh = greedy_mesh(&model);
+        // The shared x=1 face between the two voxels is interior (culled).
+        // Top/bottom (z) and front/back (y) each see two differently
+        // colored 1x1 cells that can't merge, giving 2 quads apiece (8
+        // total); the two remaining x faces (the outer ends) give 1 quad
+        // apiece.
+        assert_eq!(mesh.colors.len(), 2 + 2 + 2 + 2 + 1 + 1);
+    }
+
+    #[test]
+    fn a_transparent_neighbor_does_not_cull_the_shared_face() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 0, 255, 128]),
+        ]);
+
+        let mesh = greedy_mesh(&model);
+        // Both voxels keep all six faces (the +x face of voxel 0 and the -x
+        // face of voxel 1 included), since the transparent neighbor doesn't
+        // occlude: 2 quads per axis direction, 6 directions, 12 total.
+        assert_eq!(mesh.colors.len(), 12);
+    }
+}
@@ -0,0 +1,232 @@
+//! An exporter to Wavefront `.obj` (plus a companion `.mtl`) cube meshes.
+
+use crate::parser::Model;
+use indexmap::IndexMap;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ObjError {
+    #[error("model has no voxels to export")]
+    Empty,
+}
+
+/// Corners of a unit cube, indexed by the bit pattern of (x, y, z).
+const CORNERS: [[f32; 3]; 8] = [
+    [0.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0],
+    [1.0, 1.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [1.0, 0.0, 1.0],
+    [1.0, 1.0, 1.0],
+    [0.0, 1.0, 1.0],
+];
+
+/// One entry per cube face: the offset to the neighboring voxel that face
+/// touches, and the corner indices of the quad in CCW winding as seen from
+/// outside the cube (so Blender and friends don't see it as backface-culled).
+const FACES: [((i32, i32, i32), [usize; 4]); 6] = [
+    ((-1, 0, 0), [0, 4, 7, 3]),
+    ((1, 0, 0), [1, 2, 6, 5]),
+    ((0, -1, 0), [0, 1, 5, 4]),
+    ((0, 1, 0), [3, 7, 6, 2]),
+    ((0, 0, -1), [0, 3, 2, 1]),
+    ((0, 0, 1), [4, 5, 6, 7]),
+];
+
+fn offset_corner(corner: [f32; 3], x: i32, y: i32, z: i32) -> [f32; 3] {
+    [corner[0] + x as f32, corner[1] + y as f32, corner[2] + z as f32]
+}
+
+/// The alpha a voxel needs to occlude its neighbors' faces; see
+/// [`crate::mesh::OPAQUE_ALPHA`] for the mesh exporter's equivalent. Below
+/// this, a voxel is see-through (glass, water, ...) and doesn't cull the
+/// face of a neighbor behind it, even though it still gets its own faces.
+const OPAQUE_ALPHA: u8 = 255;
+
+/// Exports `model` as a Wavefront `.obj` mesh plus its companion `.mtl`.
+/// Each occupied voxel becomes a unit cube; faces shared between two
+/// occupied voxels are culled, and the remaining faces are grouped by
+/// color into materials referenced with `usemtl`. Vertex and face indices
+/// in the `.obj` are 1-based, per the OBJ spec.
+///
+/// If `include_normals` is set, one `vn` line per axis-aligned direction
+/// (`±X`/`±Y`/`±Z`) is emitted and each face references the one matching
+/// its [`FACES`] entry, so importers that don't recompute normals
+/// themselves still get correct flat shading. Leave it unset for pipelines
+/// that recompute normals anyway, to keep the `.obj` smaller.
+pub fn export(model: &Model, include_normals: bool) -> Result<(String, String), ObjError> {
+    if model.is_empty() {
+        return Err(ObjError::Empty);
+    }
+
+    let mut materials: IndexMap<[u8; 4], usize> = IndexMap::new();
+    let mut vertices: Vec<[f32; 3]> = Vec::new();
+    let mut faces: Vec<(usize, [usize; 4], usize)> = Vec::new();
+
+    for ([x, y, z], rgba) in model.iter() {
+        for (face_index, (offset, corners)) in FACES.iter().enumerate() {
+            let neighbor = (x + offset.0, y + offset.1, z + offset.2);
+            let occluded = model
+                .voxel_at(neighbor.0, neighbor.1, neighbor.2)
+                .is_some_and(|rgba| rgba[3] >= OPAQUE_ALPHA);
+            if occluded {
+                continue;
+            }
+
+            let material = match materials.get(&rgba) {
+                Some(&index) => index,
+                None => {
+                    let index = materials.len();
+                    materials.insert(rgba, index);
+                    index
+                }
+            };
+
+            let mut indices = [0usize; 4];
+            for (i, &corner) in corners.iter().enumerate() {
+                vertices.push(offset_corner(CORNERS[corner], x, y, z));
+                indices[i] = vertices.len(); // 1-based, since we just pushed it
+            }
+            faces.push((material, indices, face_index));
+        }
+    }
+
+    let mut obj = String::from("mtllib model.mtl\n");
+    for v in &vertices {
+        obj.push_str(&format!("v {} {} {}\n", v[0], v[1], v[2]));
+    }
+    if include_normals {
+        for (offset, _) in &FACES {
+            obj.push_str(&format!("vn {} {} {}\n", offset.0, offset.1, offset.2));
+        }
+    }
+    for material_index in 0..materials.len() {
+        obj.push_str(&format!("usemtl material{material_index}\n"));
+        for (face_material, indices, face_index) in &faces {
+            if *face_material != material_index {
+                continue;
+            }
+            if include_normals {
+                let vn = face_index + 1; // 1-based, in FACES order
+                obj.push_str(&format!(
+                    "f {}//{vn} {}//{vn} {}//{vn} {}//{vn}\n",
+                    indices[0], indices[1], indices[2], indices[3]
+                ));
+            } else {
+                obj.push_str(&format!(
+                    "f {} {} {} {}\n",
+                    indices[0], indices[1], indices[2], indices[3]
+                ));
+            }
+        }
+    }
+
+    let mut mtl = String::new();
+    for (&rgba, &index) in &materials {
+        mtl.push_str(&format!(
+            "newmtl material{index}\nKd {:.6} {:.6} {:.6}\nd {:.6}\n",
+            rgba[0] as f32 / 255.0,
+            rgba[1] as f32 / 255.0,
+            rgba[2] as f32 / 255.0,
+            rgba[3] as f32 / 255.0,
+        ));
+    }
+
+    Ok((obj, mtl))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn export_rejects_an_empty_model() {
+        let model = Model::default();
+        assert!(matches!(export(&model, false), Err(ObjError::Empty)));
+    }
+
+    #[test]
+    fn export_culls_internal_faces() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+        ]);
+
+        let (obj, _) = export(&model, false).expect("should export a two-voxel model");
+        let face_count = obj.lines().filter(|l| l.starts_with("f ")).count();
+
+        // Two unit cubes sharing a face have 12 faces total, minus the two
+        // that touch each other: 10 remain.
+        assert_eq!(face_count, 10);
+    }
+
+    #[test]
+    fn export_does_not_cull_faces_behind_a_transparent_neighbor() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 0, 255, 128]),
+        ]);
+
+        let (obj, _) = export(&model, false).expect("should export a two-voxel model");
+        let face_count = obj.lines().filter(|l| l.starts_with("f ")).count();
+
+        // The transparent neighbor doesn't occlude, so both cubes keep all
+        // six faces: 12 total, none culled.
+        assert_eq!(face_count, 12);
+    }
+
+    #[test]
+    fn export_emits_one_material_per_distinct_color() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((5, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let (_, mtl) = export(&model, false).expect("should export a two-color model");
+        let material_count = mtl.lines().filter(|l| l.starts_with("newmtl")).count();
+        assert_eq!(material_count, 2);
+    }
+
+    #[test]
+    fn exported_obj_uses_one_based_indices() {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        let (obj, _) = export(&model, false).expect("should export a single-voxel model");
+        assert!(!obj.lines().any(|l| l.starts_with("f 0")));
+    }
+
+    #[test]
+    fn export_with_normals_gives_an_isolated_voxel_six_faces_and_normals() {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        let (obj, _) = export(&model, true).expect("should export a single-voxel model");
+
+        let normals: Vec<[i32; 3]> = obj
+            .lines()
+            .filter(|l| l.starts_with("vn "))
+            .map(|l| {
+                let parts: Vec<i32> = l
+                    .split_whitespace()
+                    .skip(1)
+                    .map(|n| n.parse().unwrap())
+                    .collect();
+                [parts[0], parts[1], parts[2]]
+            })
+            .collect();
+        let mut expected: Vec<[i32; 3]> =
+            FACES.iter().map(|(offset, _)| [offset.0, offset.1, offset.2]).collect();
+        let mut normals_sorted = normals.clone();
+        normals_sorted.sort();
+        expected.sort();
+        assert_eq!(normals_sorted, expected);
+
+        let face_lines: Vec<&str> = obj.lines().filter(|l| l.starts_with("f ")).collect();
+        assert_eq!(face_lines.len(), 6);
+        assert!(face_lines.iter().all(|l| l.contains("//")));
+    }
+}
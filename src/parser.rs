@@ -3,22 +3,65 @@
 //! Based on the spec at https://github.com/guillaumechereau/goxel/blob/master/src/formats/gox.c#L27
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    combinator::{map, verify},
-    multi::{fold_many1, length_count, length_data, many0},
+    bytes::complete::{tag, take},
+    combinator::{complete, consumed, map, map_parser, verify},
+    multi::{length_count, length_data, many0},
     number::complete::{le_i32, le_u32},
-    sequence::{preceded, terminated, tuple},
+    sequence::{preceded, tuple},
     IResult,
 };
-use std::collections::HashMap;
+use crate::png;
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::path::Path;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Goxel {
     version: i32,
     chunks: Vec<Chunk>,
 }
 
+impl Default for Goxel {
+    /// An empty file at version 2, the version [`ModelBuilder::build`]
+    /// also targets. Equivalent to `Goxel::new(2)`.
+    fn default() -> Self {
+        Goxel::new(2)
+    }
+}
+
+/// The borrowing counterpart to [`Goxel`], produced by [`parse_borrowed`].
 #[derive(Debug)]
+pub struct GoxelRef<'a> {
+    version: i32,
+    chunks: Vec<ChunkRef<'a>>,
+}
+
+impl<'a> GoxelRef<'a> {
+    /// The file format version this `.gox` was written with.
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// The chunks that make up this `.gox` file, in on-disk order.
+    pub fn chunks(&self) -> &[ChunkRef<'a>] {
+        &self.chunks
+    }
+
+    /// Copies every borrowed chunk into an owned [`Goxel`].
+    pub fn to_owned_goxel(&self) -> Goxel {
+        Goxel {
+            version: self.version,
+            chunks: self.chunks.iter().map(ChunkRef::to_owned_chunk).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     index: i32,
     x: i32,
@@ -26,143 +69,9735 @@ pub struct Block {
     z: i32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Chunk {
     Img {
-        dict: HashMap<String, Vec<u8>>,
+        dict: Dict,
     },
     Prev {
+        #[cfg_attr(feature = "serde", serde(with = "base64_bytes"))]
         data: Vec<u8>,
     },
     Bl16 {
+        #[cfg_attr(feature = "serde", serde(with = "base64_bytes"))]
         data: Vec<u8>,
     },
     Layr {
         blocks: Vec<Block>,
-        dict: HashMap<String, Vec<u8>>,
+        dict: Dict,
     },
     Camr {
-        dict: HashMap<String, Vec<u8>>,
+        dict: Dict,
     },
     Ligh {
-        dict: HashMap<String, Vec<u8>>,
+        dict: Dict,
+    },
+    Mate {
+        dict: Dict,
+    },
+    /// Goxel's authored swatch palette, distinct from any palette derived
+    /// from the model's voxels. Not every file has one.
+    Pale {
+        colors: Vec<[u8; 4]>,
+        dict: Dict,
+    },
+    /// A chunk type this parser doesn't know how to interpret, preserved
+    /// verbatim so files using newer or third-party chunk types still parse.
+    Unknown {
+        tag: [u8; 4],
+        #[cfg_attr(feature = "serde", serde(with = "base64_bytes"))]
+        data: Vec<u8>,
     },
 }
 
-fn entry(input: &[u8]) -> IResult<&[u8], (String, Vec<u8>)> {
-    map(
-        tuple((
-            length_data(verify(le_u32, |&n| n != 0)),
-            length_data(le_u32),
-        )),
-        |(key, value)| (String::from_utf8_lossy(key).to_string(), value.to_vec()),
-    )(input)
+/// A chunk's type discriminant, without its payload. Returned by
+/// [`Chunk::kind`] and [`Goxel::chunk_kinds`] for callers that just want to
+/// know what a file contains without decoding every dict and block list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkKind {
+    Img,
+    Prev,
+    Bl16,
+    Layr,
+    Camr,
+    Ligh,
+    Mate,
+    Pale,
+    /// A chunk type this parser doesn't know how to interpret, carrying its
+    /// raw 4-byte tag since there's no named variant for it.
+    Unknown([u8; 4]),
 }
 
-fn dict(input: &[u8]) -> IResult<&[u8], HashMap<String, Vec<u8>>> {
-    fold_many1(entry, HashMap::new, |mut map, (key, value)| {
-        map.insert(key, value);
-        map
-    })(input)
+impl fmt::Display for ChunkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag: &[u8; 4] = match self {
+            ChunkKind::Img => b"IMG ",
+            ChunkKind::Prev => b"PREV",
+            ChunkKind::Bl16 => b"BL16",
+            ChunkKind::Layr => b"LAYR",
+            ChunkKind::Camr => b"CAMR",
+            ChunkKind::Ligh => b"LIGH",
+            ChunkKind::Mate => b"MATE",
+            ChunkKind::Pale => b"PALE",
+            ChunkKind::Unknown(tag) => tag,
+        };
+        write!(f, "{}", String::from_utf8_lossy(tag))
+    }
 }
 
-fn chunk_common<'a, F: 'a>(
-    name: &'a str,
-    parser: F,
-) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], Chunk>
-where
-    F: FnMut(&'a [u8]) -> IResult<&'a [u8], Chunk>,
-{
-    terminated(
-        preceded(tag(name), parser), // TODO: Collect length buffer so callers don't have to, map_parser maybe?
-        le_u32,                      // TODO: Handle CRC?
-    )
+/// The borrowing counterpart to [`Chunk`]: `PREV`/`BL16`/`Unknown` bodies
+/// and dict values are slices into the original input rather than owned
+/// copies, so parsing a large file full of `BL16` blobs doesn't have to
+/// copy them just to read them.
+#[derive(Debug)]
+pub enum ChunkRef<'a> {
+    Img {
+        dict: DictRef<'a>,
+    },
+    Prev {
+        data: &'a [u8],
+    },
+    Bl16 {
+        data: &'a [u8],
+    },
+    Layr {
+        blocks: Vec<Block>,
+        dict: DictRef<'a>,
+    },
+    Camr {
+        dict: DictRef<'a>,
+    },
+    Ligh {
+        dict: DictRef<'a>,
+    },
+    Mate {
+        dict: DictRef<'a>,
+    },
+    Pale {
+        colors: Vec<[u8; 4]>,
+        dict: DictRef<'a>,
+    },
+    Unknown {
+        tag: [u8; 4],
+        data: &'a [u8],
+    },
 }
 
-fn img(input: &[u8]) -> IResult<&[u8], Chunk> {
-    chunk_common(
-        "IMG ",
-        map(preceded(le_u32, dict), |dict| Chunk::Img { dict }),
-    )(input)
+impl<'a> ChunkRef<'a> {
+    /// Copies this chunk's borrowed data into an owned [`Chunk`].
+    pub fn to_owned_chunk(&self) -> Chunk {
+        match self {
+            ChunkRef::Img { dict } => Chunk::Img {
+                dict: dict.to_owned_dict(),
+            },
+            ChunkRef::Prev { data } => Chunk::Prev {
+                data: data.to_vec(),
+            },
+            ChunkRef::Bl16 { data } => Chunk::Bl16 {
+                data: data.to_vec(),
+            },
+            ChunkRef::Layr { blocks, dict } => Chunk::Layr {
+                blocks: blocks
+                    .iter()
+                    .map(|b| Block {
+                        index: b.index,
+                        x: b.x,
+                        y: b.y,
+                        z: b.z,
+                    })
+                    .collect(),
+                dict: dict.to_owned_dict(),
+            },
+            ChunkRef::Camr { dict } => Chunk::Camr {
+                dict: dict.to_owned_dict(),
+            },
+            ChunkRef::Ligh { dict } => Chunk::Ligh {
+                dict: dict.to_owned_dict(),
+            },
+            ChunkRef::Mate { dict } => Chunk::Mate {
+                dict: dict.to_owned_dict(),
+            },
+            ChunkRef::Pale { colors, dict } => Chunk::Pale {
+                colors: colors.clone(),
+                dict: dict.to_owned_dict(),
+            },
+            ChunkRef::Unknown { tag, data } => Chunk::Unknown {
+                tag: *tag,
+                data: data.to_vec(),
+            },
+        }
+    }
 }
 
-fn prev(input: &[u8]) -> IResult<&[u8], Chunk> {
-    chunk_common(
-        "PREV",
-        map(length_data(le_u32), |data: &[u8]| Chunk::Prev {
-            data: data.to_vec(),
-        }),
-    )(input)
+/// A chunk's key/value metadata, preserving the order its entries were
+/// written in on disk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dict(IndexMap<String, Vec<u8>>);
+
+impl Dict {
+    fn new() -> Self {
+        Dict(IndexMap::new())
+    }
+
+    fn insert(&mut self, key: String, value: Vec<u8>) {
+        self.0.insert(key, value);
+    }
+
+    /// Looks up `key` and interprets its value as a UTF-8 string.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        std::str::from_utf8(self.0.get(key)?).ok()
+    }
+
+    /// Looks up `key` and interprets its value as a little-endian `i32`.
+    /// `Ok(None)` means `key` isn't present; a present value of the wrong
+    /// length is a [`GoxError::DictValueLength`] rather than being treated
+    /// the same as missing.
+    pub fn get_i32(&self, key: &str) -> Result<Option<i32>, GoxError> {
+        dict_get_fixed(self.0.get(key).map(Vec::as_slice), key, 4, read_i32)
+    }
+
+    /// Looks up `key` and interprets its value as a little-endian `f32`;
+    /// see [`Dict::get_i32`] for how a wrong-length value is reported.
+    pub fn get_f32(&self, key: &str) -> Result<Option<f32>, GoxError> {
+        dict_get_fixed(self.0.get(key).map(Vec::as_slice), key, 4, read_f32)
+    }
+
+    /// Looks up `key` and interprets its value as three little-endian
+    /// `f32`s; see [`Dict::get_i32`] for how a wrong-length value is
+    /// reported.
+    pub fn get_vec3(&self, key: &str) -> Result<Option<[f32; 3]>, GoxError> {
+        dict_get_fixed(self.0.get(key).map(Vec::as_slice), key, 12, read_vec3)
+    }
+
+    /// Iterates this dict's entries in the order they were parsed from
+    /// disk. The writer relies on this matching the order goxel itself
+    /// writes keys in, so re-serializing a parsed file round-trips
+    /// byte-identically.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
+    }
+
+    /// The combined size of every key and value this dict owns, used by
+    /// [`parse_with_options`] to track cumulative allocation against
+    /// [`ParseOptions::max_alloc`].
+    fn heap_size(&self) -> usize {
+        self.0.iter().map(|(k, v)| k.len() + v.len()).sum()
+    }
+
+    /// Looks up an `IMG` dict entry by its known key instead of a raw
+    /// string, catching typos at compile time. Raw string access (`get_*`)
+    /// still works for forward compatibility with keys this crate doesn't
+    /// know about yet.
+    pub fn get_img(&self, key: ImgKey) -> Option<&[u8]> {
+        self.0.get(key.as_str()).map(Vec::as_slice)
+    }
+
+    /// Looks up a `CAMR` dict entry by its known key; see [`Dict::get_img`].
+    pub fn get_camr(&self, key: CamrKey) -> Option<&[u8]> {
+        self.0.get(key.as_str()).map(Vec::as_slice)
+    }
+
+    /// Looks up a `LAYR` dict entry by its known key; see [`Dict::get_img`].
+    pub fn get_layr(&self, key: LayrKey) -> Option<&[u8]> {
+        self.0.get(key.as_str()).map(Vec::as_slice)
+    }
+
+    /// Looks up a `LIGH` dict entry by its known key; see [`Dict::get_img`].
+    pub fn get_ligh(&self, key: LighKey) -> Option<&[u8]> {
+        self.0.get(key.as_str()).map(Vec::as_slice)
+    }
+
+    /// Looks up a `MATE` dict entry by its known key; see [`Dict::get_img`].
+    pub fn get_mate(&self, key: MateKey) -> Option<&[u8]> {
+        self.0.get(key.as_str()).map(Vec::as_slice)
+    }
 }
 
-fn bl16(input: &[u8]) -> IResult<&[u8], Chunk> {
-    chunk_common(
-        "BL16",
-        map(length_data(le_u32), |data: &[u8]| Chunk::Bl16 {
-            data: data.to_vec(),
-        }),
-    )(input)
+impl std::ops::Deref for Dict {
+    type Target = IndexMap<String, Vec<u8>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
 
-fn block(input: &[u8]) -> IResult<&[u8], Block> {
-    map(
-        tuple((le_i32, le_i32, le_i32, le_i32, le_i32)),
-        |(index, x, y, z, _)| Block { index, x, y, z },
-    )(input)
+impl<'a> IntoIterator for &'a Dict {
+    type Item = (&'a String, &'a Vec<u8>);
+    type IntoIter = indexmap::map::Iter<'a, String, Vec<u8>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
-fn layr(input: &[u8]) -> IResult<&[u8], Chunk> {
-    chunk_common(
-        "LAYR",
-        map(
-            preceded(le_u32, tuple((length_count(le_u32, block), dict))),
-            |(blocks, dict)| Chunk::Layr { blocks, dict },
-        ),
-    )(input)
+impl From<IndexMap<String, Vec<u8>>> for Dict {
+    fn from(map: IndexMap<String, Vec<u8>>) -> Self {
+        Dict(map)
+    }
 }
 
-fn camr(input: &[u8]) -> IResult<&[u8], Chunk> {
-    chunk_common(
-        "CAMR",
-        map(preceded(le_u32, dict), |dict| Chunk::Camr { dict }),
-    )(input)
+/// Serializes `Vec<u8>` fields as a base64 string instead of an array of
+/// numbers, so JSON dumps of a parsed `.gox` (dict values, `PREV`/`BL16`
+/// payloads) stay compact and readable. Used via `#[serde(with = "...")]`.
+#[cfg(feature = "serde")]
+mod base64_bytes {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)
+    }
 }
 
-fn ligh(input: &[u8]) -> IResult<&[u8], Chunk> {
-    chunk_common(
-        "LIGH",
-        map(preceded(le_u32, dict), |dict| Chunk::Ligh { dict }),
-    )(input)
+/// Dict values need the same base64 treatment as other `Vec<u8>` payloads,
+/// but `Dict` wraps an `IndexMap` rather than deriving `Serialize`
+/// directly, so it goes through this ordered `(key, value)` stand-in.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DictEntry {
+    key: String,
+    #[serde(with = "base64_bytes")]
+    value: Vec<u8>,
 }
 
-fn chunk(input: &[u8]) -> IResult<&[u8], Chunk> {
-    alt((img, prev, bl16, layr, camr, ligh))(input)
+#[cfg(feature = "serde")]
+impl serde::Serialize for Dict {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entries: Vec<DictEntry> = self
+            .0
+            .iter()
+            .map(|(key, value)| DictEntry {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        serde::Serialize::serialize(&entries, serializer)
+    }
 }
 
-pub fn parse(input: &[u8]) -> IResult<&[u8], Goxel> {
-    map(
-        preceded(tag("GOX "), tuple((le_i32, many0(chunk)))),
-        |(version, chunks)| Goxel { version, chunks },
-    )(input)
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Dict {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Vec::<DictEntry>::deserialize(deserializer)?;
+        let mut map = IndexMap::new();
+        for entry in entries {
+            map.insert(entry.key, entry.value);
+        }
+        Ok(Dict(map))
+    }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// The borrowing counterpart to [`Dict`]: values are slices into the
+/// original input rather than owned copies.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DictRef<'a>(IndexMap<String, &'a [u8]>);
 
-    #[test]
-    fn img_should_parse() {
-        let input: &[u8] = &[
-            // Chunk Header
-            b'I', b'M', b'G', b' ', // Type
-            0x9, 0x0, 0x0, 0x0, // Size
-            // Dict
-            0x1, 0x0, 0x0, 0x0,  // Key Length
-            0x41, // Key Data
-            0x0, 0x0, 0x0, 0x0, // End Dict
-            0x0, 0x0, 0x0, 0x0, // CRC
+impl<'a> DictRef<'a> {
+    fn new() -> Self {
+        DictRef(IndexMap::new())
+    }
+
+    fn insert(&mut self, key: String, value: &'a [u8]) {
+        self.0.insert(key, value);
+    }
+
+    /// Looks up `key` and interprets its value as a UTF-8 string.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        std::str::from_utf8(self.0.get(key)?).ok()
+    }
+
+    /// Looks up `key` and interprets its value as a little-endian `i32`;
+    /// see [`Dict::get_i32`] for how a wrong-length value is reported.
+    pub fn get_i32(&self, key: &str) -> Result<Option<i32>, GoxError> {
+        dict_get_fixed(self.0.get(key).copied(), key, 4, read_i32)
+    }
+
+    /// Looks up `key` and interprets its value as a little-endian `f32`;
+    /// see [`Dict::get_i32`] for how a wrong-length value is reported.
+    pub fn get_f32(&self, key: &str) -> Result<Option<f32>, GoxError> {
+        dict_get_fixed(self.0.get(key).copied(), key, 4, read_f32)
+    }
+
+    /// Looks up `key` and interprets its value as three little-endian
+    /// `f32`s; see [`Dict::get_i32`] for how a wrong-length value is
+    /// reported.
+    pub fn get_vec3(&self, key: &str) -> Result<Option<[f32; 3]>, GoxError> {
+        dict_get_fixed(self.0.get(key).copied(), key, 12, read_vec3)
+    }
+
+    /// Iterates this dict's entries in the order they were parsed from
+    /// disk; see [`Dict::iter`] for why the order matters.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.0.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+
+    /// Copies this dict's borrowed values into an owned [`Dict`].
+    pub fn to_owned_dict(&self) -> Dict {
+        Dict::from(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_vec()))
+                .collect::<IndexMap<_, _>>(),
+        )
+    }
+}
+
+impl<'a> std::ops::Deref for DictRef<'a> {
+    type Target = IndexMap<String, &'a [u8]>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b DictRef<'a> {
+    type Item = (&'b String, &'b &'a [u8]);
+    type IntoIter = indexmap::map::Iter<'b, String, &'a [u8]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a> From<IndexMap<String, &'a [u8]>> for DictRef<'a> {
+    fn from(map: IndexMap<String, &'a [u8]>) -> Self {
+        DictRef(map)
+    }
+}
+
+/// Errors returned by [`parse`] and [`parse_verified`], each carrying the
+/// byte offset within the input where the problem was found.
+#[derive(Debug, thiserror::Error)]
+pub enum GoxError {
+    #[error("not a .gox file: expected the \"GOX \" magic header, found {found:02x?}")]
+    BadMagic { found: [u8; 4] },
+
+    #[error("unsupported .gox version {0}; this crate understands versions 1 and 2")]
+    UnsupportedVersion(i32),
+
+    #[error("CRC-32 mismatch at offset {offset}: expected {expected:#010x}, found {found:#010x}")]
+    CrcMismatch {
+        expected: u32,
+        found: u32,
+        offset: usize,
+    },
+
+    #[error("chunk at offset {offset} is truncated")]
+    TruncatedChunk { offset: usize },
+
+    /// Returned instead of [`GoxError::TruncatedChunk`] when a chunk's own
+    /// length field claims more bytes than are left in the input, which is
+    /// a sign of a corrupt or hostile file rather than an ordinarily
+    /// truncated one.
+    #[error(
+        "{chunk:?} chunk declares a length of {declared} bytes, but only {available} remain"
+    )]
+    ChunkLengthOverrun {
+        chunk: [u8; 4],
+        declared: u32,
+        available: usize,
+    },
+
+    #[error("failed to decode chunk dict at offset {offset}")]
+    DictDecode { offset: usize },
+
+    /// Only returned by [`parse_strict`]/[`parse_strict_verified`], which
+    /// reject an invalid UTF-8 dict key instead of replacing it with
+    /// `U+FFFD` the way [`parse`]/[`parse_verified`] do.
+    #[error("dict key at offset {offset} is not valid UTF-8")]
+    InvalidKeyUtf8 { offset: usize },
+
+    /// Only returned by [`Goxel::validate`], which checks every block's
+    /// index up front instead of discovering a dangling one mid-assembly.
+    #[error("layer {layer}'s block references nonexistent BL16 chunk {index}")]
+    DanglingBlock { layer: usize, index: i32 },
+
+    /// Only returned by [`Goxel::validate`], for a file with no `IMG`
+    /// chunk. [`parse`] and friends stay lenient about this; `validate` is
+    /// for tooling that wants to reject malformed files up front.
+    #[error("file has no IMG chunk")]
+    MissingImage,
+
+    /// Only returned by [`Goxel::validate`], for a file with more than one
+    /// `IMG` chunk; see [`GoxError::MissingImage`].
+    #[error("file has {count} IMG chunks, expected exactly one")]
+    MultipleImages { count: usize },
+
+    /// Only returned by [`parse_checked`], which treats a header-only file
+    /// with no chunks as an error instead of a valid empty [`Goxel`].
+    #[error("file has a valid \"GOX \" header but contains no chunks")]
+    NoChunks,
+
+    /// Only returned by [`parse_checked`], when bytes remain after the last
+    /// chunk that don't form another chunk and aren't a trailing run of
+    /// zero padding. `many0`-style chunk parsing otherwise stops quietly at
+    /// the first byte sequence it can't parse, which would let a
+    /// truncated-then-garbage file "succeed" with the garbage silently
+    /// dropped.
+    #[error("{len} unparsed byte(s) remain at offset {offset} after the last chunk")]
+    TrailingBytes { offset: usize, len: usize },
+
+    /// Only returned by [`parse_with_options`], once the cumulative size of
+    /// every chunk's owned payload would exceed
+    /// [`ParseOptions::max_alloc`].
+    #[error("parsing this file would allocate more than the {limit}-byte limit")]
+    LimitExceeded { limit: usize },
+
+    /// Returned by [`Dict::get_i32`]/[`Dict::get_f32`]/[`Dict::get_vec3`]
+    /// (and their [`DictRef`] counterparts) when `key` is present but its
+    /// value isn't `expected` bytes long, so a malformed value can't be
+    /// mistaken for an absent key.
+    #[error("dict key {key:?} has a {got}-byte value, expected {expected}")]
+    DictValueLength {
+        key: String,
+        expected: usize,
+        got: usize,
+    },
+
+    /// Only returned under [`DuplicateKeyPolicy::Error`], when a dict has
+    /// the same key more than once. A well-formed Goxel file never does
+    /// this; under the default [`DuplicateKeyPolicy::KeepLast`] it's
+    /// silently tolerated the way it always has been.
+    #[error("dict key {key:?} appears more than once")]
+    DuplicateDictKey { key: String },
+
+    /// Only returned by [`Goxel::export_layer`], when `selector` doesn't
+    /// match any `LAYR` chunk.
+    #[error("no layer matches {selector:?}")]
+    UnknownLayer { selector: LayerSelector },
+
+    /// Only returned by [`crate::export::ObjExporter`], wrapping a failure
+    /// from [`crate::obj::export`] itself.
+    #[error("OBJ export failed: {0}")]
+    Obj(#[from] crate::obj::ObjError),
+
+    /// Only returned by [`crate::export::PlyExporter`], wrapping a failure
+    /// from [`crate::ply::export`] itself.
+    #[error("PLY export failed: {0}")]
+    Ply(#[from] crate::ply::PlyError),
+
+    /// Only returned by [`crate::export::VoxExporter`], wrapping a failure
+    /// from [`crate::vox::export`] itself.
+    #[error(".vox export failed: {0}")]
+    VoxExport(#[from] crate::vox::VoxError),
+
+    #[error("failed to read .gox stream: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("failed to decode PREV chunk preview: {0}")]
+    Preview(#[from] image::ImageError),
+
+    /// Only returned by [`Goxel::thumbnail`], when it falls back to
+    /// rendering the model (because there's no `PREV` chunk) and decoding
+    /// the model's voxels fails.
+    #[error("failed to decode voxels for thumbnail fallback: {0}")]
+    Voxel(#[from] VoxelError),
+
+    /// Only returned by [`from_gz`]/[`Goxel::from_gz`], gated behind the
+    /// `gzip` feature.
+    #[cfg(feature = "gzip")]
+    #[error("failed to decompress gzip input: {0}")]
+    Decompress(io::Error),
+}
+
+fn read_f32(bytes: &[u8]) -> Option<f32> {
+    Some(f32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i32(bytes: &[u8]) -> Option<i32> {
+    Some(i32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_bool(bytes: &[u8]) -> Option<bool> {
+    Some(read_i32(bytes)? != 0)
+}
+
+/// Shared by [`Dict`]'s and [`DictRef`]'s `get_i32`/`get_f32`/`get_vec3`:
+/// `Ok(None)` means `key` was never in the dict, while a present value of
+/// the wrong length is a [`GoxError::DictValueLength`], not `Ok(None)` —
+/// the two cases mean very different things to a caller.
+fn dict_get_fixed<T>(
+    value: Option<&[u8]>,
+    key: &str,
+    expected: usize,
+    read: impl Fn(&[u8]) -> Option<T>,
+) -> Result<Option<T>, GoxError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    if value.len() != expected {
+        return Err(GoxError::DictValueLength {
+            key: key.to_string(),
+            expected,
+            got: value.len(),
+        });
+    }
+    Ok(read(value))
+}
+
+fn read_vec3(bytes: &[u8]) -> Option<[f32; 3]> {
+    if bytes.len() != 12 {
+        return None;
+    }
+    let mut vec = [0f32; 3];
+    for (dst, src) in vec.iter_mut().zip(bytes.chunks_exact(4)) {
+        *dst = read_f32(src)?;
+    }
+    Some(vec)
+}
+
+fn read_vec4(bytes: &[u8]) -> Option<[f32; 4]> {
+    if bytes.len() != 16 {
+        return None;
+    }
+    let mut vec = [0f32; 4];
+    for (dst, src) in vec.iter_mut().zip(bytes.chunks_exact(4)) {
+        *dst = read_f32(src)?;
+    }
+    Some(vec)
+}
+
+fn read_mat4(bytes: &[u8]) -> Option<[f32; 16]> {
+    if bytes.len() != 64 {
+        return None;
+    }
+    let mut mat = [0f32; 16];
+    for (dst, src) in mat.iter_mut().zip(bytes.chunks_exact(4)) {
+        *dst = read_f32(src)?;
+    }
+    Some(mat)
+}
+
+/// The identity transform, stored the same column-major way Goxel stores
+/// a layer's `mat` entry.
+const IDENTITY_MAT4: [f32; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// Transforms a point by a column-major 4×4 matrix, treating it as the
+/// homogeneous point `(x, y, z, 1)`.
+fn apply_mat4(mat: &[f32; 16], x: f32, y: f32, z: f32) -> (f32, f32, f32) {
+    (
+        mat[0] * x + mat[4] * y + mat[8] * z + mat[12],
+        mat[1] * x + mat[5] * y + mat[9] * z + mat[13],
+        mat[2] * x + mat[6] * y + mat[10] * z + mat[14],
+    )
+}
+
+/// The known keys of a `CAMR` chunk's dict, for use with [`Dict::get_camr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CamrKey {
+    Mat,
+    Dist,
+    Ortho,
+    Name,
+}
+
+impl CamrKey {
+    fn as_str(self) -> &'static str {
+        match self {
+            CamrKey::Mat => "mat",
+            CamrKey::Dist => "dist",
+            CamrKey::Ortho => "ortho",
+            CamrKey::Name => "name",
+        }
+    }
+}
+
+/// A decoded `CAMR` dict: the camera's transform and projection.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraView {
+    pub mat: [f32; 16],
+    pub dist: f32,
+    pub ortho: bool,
+    pub name: Option<String>,
+}
+
+impl CameraView {
+    /// This camera's transform as nested column arrays: `matrix()[c]` is
+    /// column `c`, matching how Goxel itself stores `mat` (column-major).
+    /// Available without any feature; see [`CameraView::to_glam_mat4`] and
+    /// [`CameraView::to_nalgebra_matrix4`] for typed matrix conversions.
+    pub fn matrix(&self) -> [[f32; 4]; 4] {
+        let m = &self.mat;
+        [
+            [m[0], m[1], m[2], m[3]],
+            [m[4], m[5], m[6], m[7]],
+            [m[8], m[9], m[10], m[11]],
+            [m[12], m[13], m[14], m[15]],
+        ]
+    }
+
+    /// Converts this camera's transform into a [`glam::Mat4`], respecting
+    /// Goxel's column-major layout. Only available with the `glam`
+    /// feature.
+    #[cfg(feature = "glam")]
+    pub fn to_glam_mat4(&self) -> glam::Mat4 {
+        glam::Mat4::from_cols_array(&self.mat)
+    }
+
+    /// Converts this camera's transform into a [`nalgebra::Matrix4`],
+    /// respecting Goxel's column-major layout. Only available with the
+    /// `nalgebra` feature.
+    #[cfg(feature = "nalgebra")]
+    pub fn to_nalgebra_matrix4(&self) -> nalgebra::Matrix4<f32> {
+        nalgebra::Matrix4::from_column_slice(&self.mat)
+    }
+
+    /// Decomposes this camera's transform into translation, rotation (as
+    /// an `[x, y, z, w]` quaternion), and per-axis scale, assuming the
+    /// matrix has no shear. Saves callers from hand-rolling this math
+    /// themselves just to position a view.
+    pub fn decompose(&self) -> ([f32; 3], [f32; 4], [f32; 3]) {
+        let m = &self.mat;
+        let translation = [m[12], m[13], m[14]];
+
+        let column = |c: usize| [m[c * 4], m[c * 4 + 1], m[c * 4 + 2]];
+        let length = |v: [f32; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        let normalize = |v: [f32; 3], len: f32| [v[0] / len, v[1] / len, v[2] / len];
+
+        let (c0, c1, c2) = (column(0), column(1), column(2));
+        let scale = [length(c0), length(c1), length(c2)];
+        let (r0, r1, r2) = (
+            normalize(c0, scale[0]),
+            normalize(c1, scale[1]),
+            normalize(c2, scale[2]),
+        );
+
+        // Rows of the pure rotation matrix, built from the normalized
+        // basis columns.
+        let rows = [
+            [r0[0], r1[0], r2[0]],
+            [r0[1], r1[1], r2[1]],
+            [r0[2], r1[2], r2[2]],
         ];
+        (translation, quat_from_rotation_rows(rows), scale)
+    }
+}
+
+/// Converts a 3×3 rotation matrix (given as rows) into an `[x, y, z, w]`
+/// quaternion, via the standard trace-based construction (Shepperd's
+/// method) that picks the numerically stable branch for the matrix at
+/// hand.
+fn quat_from_rotation_rows(r: [[f32; 3]; 3]) -> [f32; 4] {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            (r[2][1] - r[1][2]) / s,
+            (r[0][2] - r[2][0]) / s,
+            (r[1][0] - r[0][1]) / s,
+            0.25 * s,
+        ]
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+        [
+            0.25 * s,
+            (r[0][1] + r[1][0]) / s,
+            (r[0][2] + r[2][0]) / s,
+            (r[2][1] - r[1][2]) / s,
+        ]
+    } else if r[1][1] > r[2][2] {
+        let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+        [
+            (r[0][1] + r[1][0]) / s,
+            0.25 * s,
+            (r[1][2] + r[2][1]) / s,
+            (r[0][2] - r[2][0]) / s,
+        ]
+    } else {
+        let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+        [
+            (r[0][2] + r[2][0]) / s,
+            (r[1][2] + r[2][1]) / s,
+            0.25 * s,
+            (r[1][0] - r[0][1]) / s,
+        ]
+    }
+}
+
+/// The known keys of a `LAYR` chunk's dict, for use with [`Dict::get_layr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayrKey {
+    Name,
+    Mat,
+    Visible,
+    BaseId,
+    Material,
+    Id,
+    Shape,
+    Mode,
+}
+
+impl LayrKey {
+    fn as_str(self) -> &'static str {
+        match self {
+            LayrKey::Name => "name",
+            LayrKey::Mat => "mat",
+            LayrKey::Visible => "visible",
+            LayrKey::BaseId => "base_id",
+            LayrKey::Material => "material",
+            LayrKey::Id => "id",
+            LayrKey::Shape => "shape",
+            LayrKey::Mode => "mode",
+        }
+    }
+}
+
+/// How a layer's voxels are composited onto whatever's beneath them at a
+/// shared coordinate, decoded from the `LAYR` dict's `mode` entry by
+/// [`Chunk::as_layer`] and applied by [`Goxel::flatten`]. Defaults to
+/// `Normal` when the key is absent or holds a value this crate doesn't
+/// recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    /// The later layer wins outright; this is also what [`Goxel::flatten`]
+    /// did before it knew about blend modes at all.
+    Normal,
+    /// Each channel of the later layer is added to the one beneath it,
+    /// saturating at 255.
+    Add,
+    /// Each channel of the later layer is multiplied with the one beneath
+    /// it, both scaled to 0.0..=1.0.
+    Mul,
+}
+
+impl BlendMode {
+    fn from_i32(mode: i32) -> BlendMode {
+        match mode {
+            1 => BlendMode::Add,
+            2 => BlendMode::Mul,
+            _ => BlendMode::Normal,
+        }
+    }
+
+    /// Composites `above` (the later layer) over `below`, both already
+    /// decoded RGBA colors at the same voxel coordinate.
+    fn composite(self, below: [u8; 4], above: [u8; 4]) -> [u8; 4] {
+        match self {
+            BlendMode::Normal => above,
+            BlendMode::Add => std::array::from_fn(|i| below[i].saturating_add(above[i])),
+            BlendMode::Mul => {
+                std::array::from_fn(|i| ((below[i] as u16 * above[i] as u16) / 255) as u8)
+            }
+        }
+    }
+}
+
+/// Whether a `LAYR` chunk holds ordinary voxel blocks or is a newer
+/// procedural/signed-distance-field layer described entirely by its dict.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerKind {
+    Blocks,
+    /// `name` is the dict's `shape` value (e.g. `"sphere"`, `"cube"`); its
+    /// other parameters stay reachable via [`LayerView`]'s underlying dict.
+    Shape { name: String },
+}
+
+/// A decoded `LAYR` dict: the layer's name, transform and flags.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerView {
+    pub name: String,
+    pub mat: [f32; 16],
+    pub visible: bool,
+    pub base_id: i32,
+    pub material: i32,
+    pub id: i32,
+    pub kind: LayerKind,
+    pub blend_mode: BlendMode,
+}
+
+/// The known keys of a `LIGH` chunk's dict, for use with [`Dict::get_ligh`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LighKey {
+    Pitch,
+    Yaw,
+    Intensity,
+    Fixed,
+    Ambient,
+}
+
+impl LighKey {
+    fn as_str(self) -> &'static str {
+        match self {
+            LighKey::Pitch => "pitch",
+            LighKey::Yaw => "yaw",
+            LighKey::Intensity => "intensity",
+            LighKey::Fixed => "fixed",
+            LighKey::Ambient => "ambient",
+        }
+    }
+}
+
+/// A decoded `LIGH` dict: the scene light's direction and strength.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LightView {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub intensity: f32,
+    /// Whether the light is fixed relative to the camera rather than the
+    /// scene. Defaults to `false`, Goxel's own default, when absent.
+    pub fixed: bool,
+    /// The scene's ambient light intensity. Defaults to `0.0` when absent.
+    pub ambient: f32,
+}
+
+/// The known keys of a `MATE` chunk's dict, for use with [`Dict::get_mate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MateKey {
+    Name,
+    Color,
+    Metallic,
+    Roughness,
+}
+
+impl MateKey {
+    fn as_str(self) -> &'static str {
+        match self {
+            MateKey::Name => "name",
+            MateKey::Color => "color",
+            MateKey::Metallic => "metallic",
+            MateKey::Roughness => "roughness",
+        }
+    }
+}
+
+/// A decoded `MATE` dict: a material's color and shading parameters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaterialView {
+    pub name: Option<String>,
+    pub color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+}
+
+/// The known keys of an `IMG` chunk's dict, for use with [`Dict::get_img`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImgKey {
+    BoundingBox,
+}
+
+impl ImgKey {
+    fn as_str(self) -> &'static str {
+        match self {
+            ImgKey::BoundingBox => "box",
+        }
+    }
+}
+
+/// A decoded `IMG` dict: the image's bounding box transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageView {
+    pub bounding_box: [f32; 16],
+}
+
+impl ImageView {
+    /// Decodes `bounding_box`'s column-major mat4 into world-space min/max
+    /// corners. Goxel stores the box as a scale-and-translate transform:
+    /// the first three columns' diagonal entries are half-extents along
+    /// x/y/z, and the fourth column is the center. Returns `None` if the
+    /// half-extents are all zero, meaning no real box was authored.
+    pub fn world_box(&self) -> Option<([f32; 3], [f32; 3])> {
+        let m = &self.bounding_box;
+        let half_extents = [m[0], m[5], m[10]];
+        if half_extents == [0.0, 0.0, 0.0] {
+            return None;
+        }
+        let center = [m[12], m[13], m[14]];
+        let min = std::array::from_fn(|i| center[i] - half_extents[i]);
+        let max = std::array::from_fn(|i| center[i] + half_extents[i]);
+        Some((min, max))
+    }
+}
+
+/// Everything [`Goxel::scene`] pulls together: every typed chunk view,
+/// paired with its decoded voxels where that makes sense, so a consumer
+/// doesn't have to rummage through [`Goxel::chunks`] matching variants
+/// themselves. `image` and `light` are `None` when the file has no `IMG`
+/// or `LIGH` chunk (or its dict doesn't decode); `cameras` and `materials`
+/// are simply empty with no `CAMR`/`MATE` chunks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub image: Option<ImageView>,
+    pub cameras: Vec<CameraView>,
+    pub light: Option<LightView>,
+    pub layers: Vec<(LayerView, Model)>,
+    pub materials: Vec<MaterialView>,
+}
+
+impl Chunk {
+    /// This chunk's type discriminant, without decoding its payload.
+    pub fn kind(&self) -> ChunkKind {
+        match self {
+            Chunk::Img { .. } => ChunkKind::Img,
+            Chunk::Prev { .. } => ChunkKind::Prev,
+            Chunk::Bl16 { .. } => ChunkKind::Bl16,
+            Chunk::Layr { .. } => ChunkKind::Layr,
+            Chunk::Camr { .. } => ChunkKind::Camr,
+            Chunk::Ligh { .. } => ChunkKind::Ligh,
+            Chunk::Mate { .. } => ChunkKind::Mate,
+            Chunk::Pale { .. } => ChunkKind::Pale,
+            Chunk::Unknown { tag, .. } => ChunkKind::Unknown(*tag),
+        }
+    }
+
+    /// This chunk's raw 4-byte on-disk tag, e.g. `b"IMG "` or `b"BL16"`, even
+    /// for a known variant. Lets generic code (logging, a hex dump) handle
+    /// every chunk uniformly by tag instead of matching on [`ChunkKind`].
+    pub fn tag(&self) -> [u8; 4] {
+        match self {
+            Chunk::Img { .. } => *b"IMG ",
+            Chunk::Prev { .. } => *b"PREV",
+            Chunk::Bl16 { .. } => *b"BL16",
+            Chunk::Layr { .. } => *b"LAYR",
+            Chunk::Camr { .. } => *b"CAMR",
+            Chunk::Ligh { .. } => *b"LIGH",
+            Chunk::Mate { .. } => *b"MATE",
+            Chunk::Pale { .. } => *b"PALE",
+            Chunk::Unknown { tag, .. } => *tag,
+        }
+    }
+
+    /// The combined size of this chunk's owned payload: dict keys/values,
+    /// the block list, or raw chunk data, depending on variant. Used by
+    /// [`parse_with_options`] to track cumulative allocation against
+    /// [`ParseOptions::max_alloc`].
+    fn heap_size(&self) -> usize {
+        match self {
+            Chunk::Img { dict } | Chunk::Camr { dict } | Chunk::Ligh { dict } | Chunk::Mate { dict } => {
+                dict.heap_size()
+            }
+            Chunk::Prev { data } | Chunk::Bl16 { data } | Chunk::Unknown { data, .. } => data.len(),
+            Chunk::Layr { blocks, dict } => {
+                blocks.len() * std::mem::size_of::<Block>() + dict.heap_size()
+            }
+            Chunk::Pale { colors, dict } => {
+                colors.len() * std::mem::size_of::<[u8; 4]>() + dict.heap_size()
+            }
+        }
+    }
+
+    /// Decodes this chunk's dict as a camera view, if it's a `CAMR` chunk
+    /// with a well-formed `mat` and `dist`.
+    pub fn as_camera(&self) -> Option<CameraView> {
+        let Chunk::Camr { dict } = self else {
+            return None;
+        };
+        Some(CameraView {
+            mat: read_mat4(dict.get_camr(CamrKey::Mat)?)?,
+            dist: read_f32(dict.get_camr(CamrKey::Dist)?)?,
+            ortho: dict
+                .get_camr(CamrKey::Ortho)
+                .and_then(read_bool)
+                .unwrap_or(false),
+            name: dict
+                .get_camr(CamrKey::Name)
+                .and_then(|v| std::str::from_utf8(v).ok())
+                .map(str::to_string),
+        })
+    }
+
+    /// Decodes this chunk's dict as a layer view, if it's a `LAYR` chunk
+    /// with a well-formed `name` and `mat`.
+    pub fn as_layer(&self) -> Option<LayerView> {
+        let Chunk::Layr { dict, blocks } = self else {
+            return None;
+        };
+        let kind = match dict.get_layr(LayrKey::Shape) {
+            Some(shape) if blocks.is_empty() => LayerKind::Shape {
+                name: std::str::from_utf8(shape).ok()?.to_string(),
+            },
+            _ => LayerKind::Blocks,
+        };
+        Some(LayerView {
+            name: std::str::from_utf8(dict.get_layr(LayrKey::Name)?).ok()?.to_string(),
+            mat: read_mat4(dict.get_layr(LayrKey::Mat)?)?,
+            visible: dict
+                .get_layr(LayrKey::Visible)
+                .and_then(read_bool)
+                .unwrap_or(true),
+            base_id: dict
+                .get_layr(LayrKey::BaseId)
+                .and_then(read_i32)
+                .unwrap_or(-1),
+            material: dict
+                .get_layr(LayrKey::Material)
+                .and_then(read_i32)
+                .unwrap_or(-1),
+            id: dict
+                .get_layr(LayrKey::Id)
+                .and_then(read_i32)
+                .unwrap_or(-1),
+            kind,
+            blend_mode: BlendMode::from_i32(
+                dict.get_layr(LayrKey::Mode).and_then(read_i32).unwrap_or(0),
+            ),
+        })
+    }
+
+    /// Decodes this chunk's dict as a light view, if it's a `LIGH` chunk
+    /// with well-formed `pitch`, `yaw` and `intensity` values.
+    pub fn as_light(&self) -> Option<LightView> {
+        let Chunk::Ligh { dict } = self else {
+            return None;
+        };
+        Some(LightView {
+            pitch: read_f32(dict.get_ligh(LighKey::Pitch)?)?,
+            yaw: read_f32(dict.get_ligh(LighKey::Yaw)?)?,
+            intensity: read_f32(dict.get_ligh(LighKey::Intensity)?)?,
+            fixed: dict
+                .get_ligh(LighKey::Fixed)
+                .and_then(read_bool)
+                .unwrap_or(false),
+            ambient: dict
+                .get_ligh(LighKey::Ambient)
+                .and_then(read_f32)
+                .unwrap_or(0.0),
+        })
+    }
+
+    /// Decodes this chunk's dict as an image view, if it's an `IMG` chunk
+    /// with a well-formed `box` bounding box matrix.
+    pub fn as_image(&self) -> Option<ImageView> {
+        let Chunk::Img { dict } = self else {
+            return None;
+        };
+        Some(ImageView {
+            bounding_box: read_mat4(dict.get_img(ImgKey::BoundingBox)?)?,
+        })
+    }
+
+    /// Collects every string-decodable entry of this chunk's dict, keyed by
+    /// dict key, skipping binary-only values like `box`'s matrix that
+    /// aren't meaningfully a string. Returns an empty map for anything but
+    /// an `IMG` chunk. Goxel stashes authoring metadata here (e.g. its own
+    /// app version), so this is a convenient way for a catalog or importer
+    /// to surface "made with goxel vX" without hand-decoding the dict.
+    pub fn image_metadata(&self) -> HashMap<String, String> {
+        let Chunk::Img { dict } = self else {
+            return HashMap::new();
+        };
+        dict.iter()
+            .filter_map(|(key, value)| {
+                let value = std::str::from_utf8(value).ok()?;
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
+    /// Decodes this chunk's dict as a material view, if it's a `MATE` chunk
+    /// with a well-formed `color`.
+    pub fn as_material(&self) -> Option<MaterialView> {
+        let Chunk::Mate { dict } = self else {
+            return None;
+        };
+        Some(MaterialView {
+            name: dict
+                .get_mate(MateKey::Name)
+                .and_then(|v| std::str::from_utf8(v).ok())
+                .map(str::to_string),
+            color: read_vec4(dict.get_mate(MateKey::Color)?)?,
+            metallic: dict
+                .get_mate(MateKey::Metallic)
+                .and_then(read_f32)
+                .unwrap_or(0.0),
+            roughness: dict
+                .get_mate(MateKey::Roughness)
+                .and_then(read_f32)
+                .unwrap_or(0.0),
+        })
+    }
+
+    /// Decodes this chunk's PNG payload into its 16×16×16 voxel grid, if
+    /// it's a `BL16` chunk.
+    pub fn as_voxels(&self) -> Option<Result<Voxels, VoxelError>> {
+        let Chunk::Bl16 { data } = self else {
+            return None;
+        };
+        Some(decode_bl16(data))
+    }
+}
+
+/// A decoded 16×16×16 block of voxels, indexed `[x][y][z]`. Each voxel is an
+/// RGBA color; an alpha of `0` means the voxel is empty.
+pub type Voxels = [[[[u8; 4]; 16]; 16]; 16];
+
+/// Splits a world-space voxel coordinate into the origin (in world units)
+/// of the 16×16×16 block it falls in, and its `0..16` local index within
+/// that block. Uses floor division (via `div_euclid`/`rem_euclid`), not
+/// truncation toward zero, so negative coordinates land in the right
+/// block instead of off by one — e.g. `-1` is local index `15` of the
+/// block at `-16`, not local index `-1` of the block at `0`.
+pub fn world_to_block(pos: [i32; 3]) -> ([i32; 3], [usize; 3]) {
+    let origin = pos.map(|c| c.div_euclid(16) * 16);
+    let local = std::array::from_fn(|i| pos[i].rem_euclid(16) as usize);
+    (origin, local)
+}
+
+/// The inverse of [`world_to_block`]: recovers the world-space coordinate
+/// of the voxel at `local` (each component `0..16`) within the block
+/// whose origin is `block_origin`.
+pub fn block_to_world(block_origin: [i32; 3], local: [usize; 3]) -> [i32; 3] {
+    std::array::from_fn(|i| block_origin[i] + local[i] as i32)
+}
+
+#[derive(Debug)]
+pub enum VoxelError {
+    /// A `Block` referenced a `BL16` chunk index that doesn't exist.
+    MissingBl16 { index: i32 },
+    /// The referenced `BL16` chunk's PNG payload couldn't be decoded.
+    Png(png::PngError),
+    /// A `BL16` chunk's image wasn't the 64×64 size a voxel block expects.
+    UnexpectedImageSize { width: u32, height: u32 },
+    /// A block's offset plus its local voxel coordinate overflowed `i32`.
+    /// Only extreme, almost certainly corrupt or hostile `Block` offsets
+    /// trigger this; a well-formed file never comes close.
+    CoordinateOverflow { block_x: i32, block_y: i32, block_z: i32 },
+    /// A specific `BL16` chunk failed to decode. `index` counts only `BL16`
+    /// chunks, in file order, same as [`Block::index`] and
+    /// [`DecodedBlock::index`], so a caller can tell which chunk in a
+    /// large file is corrupt instead of just that decoding failed
+    /// somewhere.
+    BlockDecode { index: usize, source: Box<VoxelError> },
+}
+
+impl fmt::Display for VoxelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoxelError::MissingBl16 { index } => {
+                write!(f, "block references missing BL16 chunk #{}", index)
+            }
+            VoxelError::Png(err) => write!(f, "failed to decode BL16 image: {}", err),
+            VoxelError::UnexpectedImageSize { width, height } => {
+                write!(f, "BL16 image is {}x{}, expected 64x64", width, height)
+            }
+            VoxelError::CoordinateOverflow { block_x, block_y, block_z } => write!(
+                f,
+                "block offset ({block_x}, {block_y}, {block_z}) overflows i32 when combined with its local voxel coordinate"
+            ),
+            VoxelError::BlockDecode { index, source } => {
+                write!(f, "BL16 chunk #{index} failed to decode: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VoxelError {}
+
+impl From<png::PngError> for VoxelError {
+    fn from(err: png::PngError) -> Self {
+        VoxelError::Png(err)
+    }
+}
+
+/// The tile arrangement a `BL16` chunk's 64×64 PNG packs its sixteen
+/// 16×16 slices into. Goxel itself always writes [`Bl16Layout::RowMajor`]
+/// (also this type's `Default`); the other variant exists for decoding
+/// files from third-party tools that pack the same slices differently.
+/// See [`decode_bl16_with_layout`] and [`Goxel::detect_bl16_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bl16Layout {
+    /// The z-th slice lives at tile `(z % 4, z / 4)` in the 4×4 grid of
+    /// 16×16 tiles — the layout current goxel writes.
+    RowMajor,
+    /// The z-th slice lives at tile `(z / 4, z % 4)`: the same 4×4 grid of
+    /// tiles, transposed.
+    ColumnMajor,
+}
+
+impl Default for Bl16Layout {
+    fn default() -> Self {
+        Bl16Layout::RowMajor
+    }
+}
+
+impl Bl16Layout {
+    /// The tile coordinates, in 16×16-tile units, slice `z` (0..16) lives
+    /// at under this layout.
+    fn tile(self, z: usize) -> (usize, usize) {
+        match self {
+            Bl16Layout::RowMajor => (z % 4, z / 4),
+            Bl16Layout::ColumnMajor => (z / 4, z % 4),
+        }
+    }
+}
+
+/// Decodes a `BL16` chunk's PNG payload into its 16×16×16 voxel grid,
+/// assuming the layout current goxel writes. See
+/// [`decode_bl16_with_layout`] to decode a file using a different tile
+/// arrangement.
+pub fn decode_bl16(data: &[u8]) -> Result<Voxels, VoxelError> {
+    decode_bl16_with_layout(data, Bl16Layout::default())
+}
+
+/// Like [`decode_bl16`], but with an explicit [`Bl16Layout`] for files
+/// whose `BL16` chunks were packed by a tool that doesn't match current
+/// goxel's tile arrangement.
+pub fn decode_bl16_with_layout(data: &[u8], layout: Bl16Layout) -> Result<Voxels, VoxelError> {
+    let image = png::decode(data)?;
+    if image.width != 64 || image.height != 64 {
+        return Err(VoxelError::UnexpectedImageSize {
+            width: image.width,
+            height: image.height,
+        });
+    }
+    Ok(voxels_from_image(&image, layout))
+}
+
+/// Maps a decoded 64×64 RGBA image into a 16×16×16 voxel grid, according
+/// to `layout`'s tile arrangement.
+fn voxels_from_image(image: &png::Image, layout: Bl16Layout) -> Voxels {
+    let mut voxels: Voxels = [[[[0u8; 4]; 16]; 16]; 16];
+    for (x, plane) in voxels.iter_mut().enumerate() {
+        for (y, column) in plane.iter_mut().enumerate() {
+            for (z, voxel) in column.iter_mut().enumerate() {
+                let (tile_x, tile_y) = layout.tile(z);
+                let px = tile_x * 16 + x;
+                let py = tile_y * 16 + y;
+                let i = (py * image.width as usize + px) * 4;
+                voxel.copy_from_slice(&image.rgba[i..i + 4]);
+            }
+        }
+    }
+    voxels
+}
+
+impl Block {
+    /// Decodes this block's voxel grid by looking up the `BL16` chunk it
+    /// references (`index` counts only the `BL16` chunks, in file order),
+    /// assuming the layout current goxel writes. See
+    /// [`Block::voxels_with_layout`] for files that use a different tile
+    /// arrangement.
+    pub fn voxels(&self, goxel: &Goxel) -> Result<Voxels, VoxelError> {
+        self.voxels_with_layout(goxel, Bl16Layout::default())
+    }
+
+    /// Like [`Block::voxels`], but decodes the referenced `BL16` chunk
+    /// under an explicit [`Bl16Layout`].
+    pub fn voxels_with_layout(&self, goxel: &Goxel, layout: Bl16Layout) -> Result<Voxels, VoxelError> {
+        let data = goxel
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Bl16 { data } => Some(data),
+                _ => None,
+            })
+            .nth(self.index as usize)
+            .ok_or(VoxelError::MissingBl16 { index: self.index })?;
+        decode_bl16_with_layout(data, layout).map_err(|source| VoxelError::BlockDecode {
+            index: self.index as usize,
+            source: Box::new(source),
+        })
+    }
+}
+
+/// A decoded `BL16` chunk's voxel grid, paired with `index` (counting only
+/// `BL16` chunks, in file order) so callers can match it back to the
+/// `Block`s that reference it — see [`Block::voxels`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodedBlock {
+    pub index: usize,
+    pub voxels: Voxels,
+}
+
+impl Goxel {
+    /// Decodes every `BL16` chunk's voxel grid serially, in file order.
+    pub fn decode_blocks(&self) -> Result<Vec<DecodedBlock>, VoxelError> {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Bl16 { data } => Some(data),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(index, data)| {
+                let voxels = decode_bl16(data).map_err(|source| VoxelError::BlockDecode {
+                    index,
+                    source: Box::new(source),
+                })?;
+                Ok(DecodedBlock { index, voxels })
+            })
+            .collect()
+    }
+
+    /// Like [`Goxel::decode_blocks`], but decodes every `BL16` chunk in
+    /// parallel with rayon's `par_iter`. Only available with the `rayon`
+    /// feature; the serial path remains the default so no-feature builds
+    /// don't pull rayon in. Results match the serial decode exactly,
+    /// just not necessarily in the same order.
+    #[cfg(feature = "rayon")]
+    pub fn decode_blocks_par(&self) -> Result<Vec<DecodedBlock>, VoxelError> {
+        use rayon::prelude::*;
+
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Bl16 { data } => Some(data),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, data)| {
+                let voxels = decode_bl16(data).map_err(|source| VoxelError::BlockDecode {
+                    index,
+                    source: Box::new(source),
+                })?;
+                Ok(DecodedBlock { index, voxels })
+            })
+            .collect()
+    }
+
+    /// Pairs every `LAYR` block reference with its decoded 16³ voxel cube,
+    /// across every layer in file order, without assembling one combined
+    /// [`Model`] the way [`Goxel::model`] does. `Block::index` identifies
+    /// which `BL16` chunk a block decodes (see [`Block::voxels`]);
+    /// `block.x`/`y`/`z` are the block's own offset, in block units (16
+    /// voxels each), within its layer's world space. Useful for streaming
+    /// through a large model block by block instead of holding every voxel
+    /// in memory at once.
+    ///
+    /// Yields a `Result` alongside each block rather than failing the whole
+    /// iterator, so one dangling or malformed block doesn't stop a caller
+    /// from processing the rest.
+    pub fn blocks(&self) -> impl Iterator<Item = (&Block, Result<DecodedBlock, VoxelError>)> + '_ {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some(blocks),
+                _ => None,
+            })
+            .flatten()
+            .map(move |block| {
+                let decoded = block.voxels(self).map(|voxels| DecodedBlock {
+                    index: block.index as usize,
+                    voxels,
+                });
+                (block, decoded)
+            })
+    }
+
+    /// A best-effort guess at which [`Bl16Layout`] this file's `BL16`
+    /// chunks were packed with. Currently always reports
+    /// [`Bl16Layout::default`] (the layout goxel itself writes): there's no
+    /// concrete evidence of any real `.gox` producer writing
+    /// [`Bl16Layout::ColumnMajor`], so treating a missing `"goxel"` `IMG`
+    /// dict key (see [`Goxel::image_metadata`]) as a signal for the
+    /// transposed layout would misdecode the far more likely case of a
+    /// standard-layout file that just lost its authoring metadata. Callers
+    /// with definite knowledge that a file's tiles are transposed should
+    /// pass an explicit [`Bl16Layout`] to
+    /// [`Block::voxels_with_layout`]/[`decode_bl16_with_layout`] instead of
+    /// relying on this.
+    pub fn detect_bl16_layout(&self) -> Bl16Layout {
+        Bl16Layout::default()
+    }
+}
+
+/// A sparse, world-space voxel map for a single layer, keyed by integer
+/// `(x, y, z)` coordinate.
+pub type LayerVoxels = HashMap<(i32, i32, i32), [u8; 4]>;
+
+/// The largest factor [`Model::upsample`] will honor; a `factor³` blowup
+/// past this would risk an unreasonably large allocation for a single call.
+pub const MAX_UPSAMPLE_FACTOR: u32 = 8;
+
+/// A sparse, world-space voxel map assembled from every `LAYR` chunk in a
+/// file, keyed by integer `(x, y, z)` coordinate. Alongside each voxel's
+/// color, tracks which layer's material index (if any) placed it — see
+/// [`Model::voxel_material`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Model {
+    voxels: HashMap<(i32, i32, i32), [u8; 4]>,
+    materials: HashMap<(i32, i32, i32), usize>,
+}
+
+impl Model {
+    fn new() -> Self {
+        Model::default()
+    }
+
+    /// Iterates over every occupied voxel exactly once, in no particular
+    /// order, yielding its position and color.
+    pub fn iter(&self) -> impl Iterator<Item = ([i32; 3], [u8; 4])> + '_ {
+        self.voxels.iter().map(|(&(x, y, z), &rgba)| ([x, y, z], rgba))
+    }
+
+    /// The number of occupied voxels in this model.
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    /// Whether this model has no occupied voxels.
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Looks up the voxel at world-space coordinate `(x, y, z)`, returning
+    /// `None` if it's empty or outside the model.
+    pub fn voxel_at(&self, x: i32, y: i32, z: i32) -> Option<[u8; 4]> {
+        self.voxels.get(&(x, y, z)).copied()
+    }
+
+    /// Whether any occupied voxel falls within the inclusive `[min, max]`
+    /// region. For spatial culling, e.g. deciding whether a chunk of the
+    /// world needs rendering at all, without scanning the whole model.
+    /// Like [`Model::voxels_in_box`], this probes the region's coordinates
+    /// rather than the model's voxels, so it's efficient when the region
+    /// is small relative to the model.
+    pub fn any_in_box(&self, min: [i32; 3], max: [i32; 3]) -> bool {
+        self.voxels_in_box(min, max).next().is_some()
+    }
+
+    /// Iterates the occupied voxels within the inclusive `[min, max]`
+    /// region, for streaming a model to a renderer chunk by chunk.
+    /// Iterates the region's coordinates and probes the voxel map, which
+    /// is efficient when the region is small relative to the model but
+    /// scales with the region's volume regardless of how sparse it is.
+    pub fn voxels_in_box(
+        &self,
+        min: [i32; 3],
+        max: [i32; 3],
+    ) -> impl Iterator<Item = ([i32; 3], [u8; 4])> + '_ {
+        let [min_x, min_y, min_z] = min;
+        let [max_x, max_y, max_z] = max;
+        (min_x..=max_x).flat_map(move |x| {
+            (min_y..=max_y).flat_map(move |y| {
+                (min_z..=max_z).filter_map(move |z| {
+                    self.voxel_at(x, y, z).map(|rgba| ([x, y, z], rgba))
+                })
+            })
+        })
+    }
+
+    /// Looks up the material index of the layer that placed the voxel at
+    /// world-space coordinate `(x, y, z)`, built by [`Goxel::model`] from
+    /// each `LAYR` chunk's `material` dict entry. Returns `None` if the
+    /// voxel is empty, was placed by a layer with no `material` entry, or
+    /// the model wasn't assembled with material tracking (e.g. one built
+    /// by [`ModelBuilder`]). When layers overlap at the same coordinate,
+    /// this reports the winning (later) layer's material, matching
+    /// [`Model::voxel_at`]'s color.
+    pub fn voxel_material(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        self.materials.get(&(x, y, z)).copied()
+    }
+
+    /// Whether each of the 6 face-adjacent neighbors of `pos` is occupied,
+    /// in `[-x, +x, -y, +y, -z, +z]` order. Doesn't report on `pos` itself.
+    /// The primitive for ambient-occlusion-style shading in a custom
+    /// exporter, without re-implementing the occupancy lookups; see
+    /// [`Model::neighbors26`] for the full 3×3×3 neighborhood.
+    pub fn neighbors(&self, pos: [i32; 3]) -> [bool; 6] {
+        let [x, y, z] = pos;
+        [
+            self.voxel_at(x - 1, y, z).is_some(),
+            self.voxel_at(x + 1, y, z).is_some(),
+            self.voxel_at(x, y - 1, z).is_some(),
+            self.voxel_at(x, y + 1, z).is_some(),
+            self.voxel_at(x, y, z - 1).is_some(),
+            self.voxel_at(x, y, z + 1).is_some(),
+        ]
+    }
+
+    /// Whether each of the 26 neighbors of `pos` in a 3×3×3 neighborhood is
+    /// occupied, in row-major `(dx, dy, dz)` order with `dx` slowest and
+    /// `dz` fastest, skipping `(0, 0, 0)` (`pos` itself). See
+    /// [`Model::neighbors`] for just the 6 face-adjacent ones.
+    pub fn neighbors26(&self, pos: [i32; 3]) -> [bool; 26] {
+        let [x, y, z] = pos;
+        let mut out = [false; 26];
+        let mut i = 0;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if dx == 0 && dy == 0 && dz == 0 {
+                        continue;
+                    }
+                    out[i] = self.voxel_at(x + dx, y + dy, z + dz).is_some();
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+
+    /// Exports this model as a Wavefront `.obj` mesh plus its companion
+    /// `.mtl`, culling faces shared between two occupied voxels and
+    /// grouping the rest by color into materials. Set `include_normals` to
+    /// also emit `vn` lines for importers that don't recompute normals
+    /// themselves. See [`crate::obj`].
+    pub fn to_obj(&self, include_normals: bool) -> Result<(String, String), crate::obj::ObjError> {
+        crate::obj::export(self, include_normals)
+    }
+
+    /// Greedily meshes this model's exposed faces, merging adjacent
+    /// coplanar same-color faces into larger quads. See [`crate::mesh`].
+    pub fn greedy_mesh(&self) -> crate::mesh::Mesh {
+        crate::mesh::greedy_mesh(self)
+    }
+
+    /// Exports this model as a Stanford `.ply` point cloud, one vertex per
+    /// occupied voxel. See [`crate::ply`].
+    pub fn to_ply(&self, format: crate::ply::PlyFormat) -> Result<Vec<u8>, crate::ply::PlyError> {
+        crate::ply::export(self, format)
+    }
+
+    /// Exports this model as CSV text, one row per occupied voxel. See
+    /// [`crate::csv`].
+    pub fn to_csv(&self) -> String {
+        crate::csv::to_string(self)
+    }
+
+    /// Like [`Model::to_csv`], but streams rows straight to `w` instead of
+    /// building a `String` up front. See [`crate::csv::write`].
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        crate::csv::write(self, w)
+    }
+
+    /// Exports this model as a gzip-compressed Sponge `.schem` file, mapping
+    /// each voxel's color to a block name via `palette`. Only available
+    /// with the `gzip` feature. See [`crate::schematic`].
+    #[cfg(feature = "gzip")]
+    pub fn to_schematic(
+        &self,
+        palette: &[([u8; 4], &str)],
+    ) -> Result<Vec<u8>, crate::schematic::SchematicError> {
+        crate::schematic::export(self, palette)
+    }
+
+    /// Exports this model as a self-contained binary glTF (`.glb`) file,
+    /// embedding its greedy mesh geometry and vertex colors in one buffer.
+    /// Only available with the `gltf` feature. See [`crate::gltf`].
+    #[cfg(feature = "gltf")]
+    pub fn to_gltf(&self) -> Result<Vec<u8>, crate::gltf::GltfError> {
+        crate::gltf::export(self)
+    }
+
+    /// The distinct, non-transparent RGBA colors used in this model, sorted
+    /// for a deterministic order.
+    pub fn palette(&self) -> Vec<[u8; 4]> {
+        self.palette_with_counts()
+            .into_iter()
+            .map(|(rgba, _)| rgba)
+            .collect()
+    }
+
+    /// Like [`Model::palette`], but pairs each color with how many voxels
+    /// use it.
+    pub fn palette_with_counts(&self) -> Vec<([u8; 4], usize)> {
+        let mut counts: HashMap<[u8; 4], usize> = HashMap::new();
+        for &rgba in self.voxels.values() {
+            if rgba[3] == 0 {
+                continue;
+            }
+            *counts.entry(rgba).or_insert(0) += 1;
+        }
+        let mut palette: Vec<([u8; 4], usize)> = counts.into_iter().collect();
+        palette.sort_unstable_by_key(|&(rgba, _)| rgba);
+        palette
+    }
+
+    /// The model's most frequent non-transparent color, for a one-color
+    /// swatch in a catalog UI. Ties are broken by the lowest RGBA value,
+    /// matching [`Model::palette_with_counts`]'s sort order.
+    pub fn dominant_color(&self) -> Option<[u8; 4]> {
+        self.palette_with_counts()
+            .into_iter()
+            .fold(None, |best, (rgba, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((rgba, count)),
+            })
+            .map(|(rgba, _)| rgba)
+    }
+
+    /// Reduces this model's palette to at most `max_colors` entries via
+    /// median-cut quantization, remapping every voxel to its nearest
+    /// palette color. With `dither`, perturbs each voxel's color by a small
+    /// position-derived offset before picking its nearest color, breaking
+    /// up banding in smooth gradients at the cost of a slightly noisier
+    /// result. Returns the quantized model alongside its palette, sorted
+    /// for a deterministic order. Useful for squeezing a richly-colored
+    /// model under an indexed format's color cap, like `.vox`'s 256 colors,
+    /// before exporting.
+    pub fn quantize(&self, max_colors: usize, dither: bool) -> (Model, Vec<[u8; 4]>) {
+        if self.voxels.is_empty() {
+            return (Model::new(), Vec::new());
+        }
+        let max_colors = max_colors.max(1);
+
+        let mut counts: HashMap<[u8; 4], usize> = HashMap::new();
+        for &rgba in self.voxels.values() {
+            *counts.entry(rgba).or_insert(0) += 1;
+        }
+
+        let mut buckets: Vec<QuantizeBucket> = vec![counts.into_iter().collect()];
+        while buckets.len() < max_colors {
+            let split = buckets
+                .iter()
+                .enumerate()
+                .filter(|(_, bucket)| bucket.len() > 1)
+                .map(|(index, bucket)| (index, channel_range(bucket)))
+                .max_by_key(|&(_, (_, range))| range);
+
+            let Some((index, (channel, _))) = split else {
+                break;
+            };
+            let bucket = buckets.swap_remove(index);
+            let (low, high) = split_bucket(bucket, channel);
+            buckets.push(low);
+            buckets.push(high);
+        }
+
+        let mut palette: Vec<[u8; 4]> = buckets.iter().map(bucket_average).collect();
+        palette.sort_unstable();
+        palette.dedup();
+
+        let mut quantized = Model::new();
+        for (&pos, &rgba) in &self.voxels {
+            let sample = if dither {
+                let mut noisy = rgba;
+                for (channel, value) in noisy.iter_mut().enumerate() {
+                    let offset = dither_offset(pos.0, pos.1, pos.2, channel as u8);
+                    *value = (*value as i32 + offset).clamp(0, 255) as u8;
+                }
+                noisy
+            } else {
+                rgba
+            };
+
+            quantized.voxels.insert(pos, nearest_color(sample, &palette));
+            if let Some(&material) = self.materials.get(&pos) {
+                quantized.materials.insert(pos, material);
+            }
+        }
+
+        (quantized, palette)
+    }
+
+    /// Tallies how many voxels use each color, after quantizing every
+    /// channel (including alpha) down to `bits_per_channel` bits. At 8 bits
+    /// this is an exact per-color count; fewer bits merges visually-similar
+    /// colors into the same bucket, which is useful for spotting a model's
+    /// dominant colors or feeding a palette-suggestion tool. Returns a map
+    /// rather than a sorted vec since callers typically want to sort by
+    /// count, not by color; see [`Model::palette_with_counts`] for that.
+    pub fn histogram(&self, bits_per_channel: u8) -> HashMap<[u8; 4], usize> {
+        let mut counts: HashMap<[u8; 4], usize> = HashMap::new();
+        for &rgba in self.voxels.values() {
+            let bucket = rgba.map(|channel| quantize_channel(channel, bits_per_channel));
+            *counts.entry(bucket).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Removes every voxel whose alpha is exactly `0`. Goxel lets a voxel
+    /// slot in a block carry alpha `0` (an "erased" voxel that still
+    /// occupies the slot) rather than truly removing it; [`Goxel::model`]
+    /// and [`Goxel::model_with_options`] already exclude these by default
+    /// via [`ModelOptions::alpha_threshold`] (which defaults to `1`) while
+    /// assembling a [`Model`] from chunks, so this is only needed to clean
+    /// up a [`Model`] built some other way, e.g. via [`ModelBuilder`] or
+    /// after [`Model::remap_colors`] maps some voxels to alpha `0`.
+    pub fn drop_transparent(&mut self) {
+        let transparent: Vec<(i32, i32, i32)> = self
+            .voxels
+            .iter()
+            .filter(|&(_, rgba)| rgba[3] == 0)
+            .map(|(&pos, _)| pos)
+            .collect();
+        for pos in transparent {
+            self.voxels.remove(&pos);
+            self.materials.remove(&pos);
+        }
+    }
+
+    /// Applies `f` to every voxel's color in place, for palette swaps or
+    /// recoloring (e.g. a character's shirt for a theme variant) without
+    /// re-authoring the model. Mapping a voxel to alpha `0` doesn't remove
+    /// it here; call [`Model::drop_transparent`] afterward if that's what
+    /// you want.
+    pub fn remap_colors(&mut self, f: impl Fn([u8; 4]) -> [u8; 4]) {
+        for rgba in self.voxels.values_mut() {
+            *rgba = f(*rgba);
+        }
+    }
+
+    /// Replaces every voxel colored exactly `from` with `to`; a thin
+    /// convenience over [`Model::remap_colors`] for the common single-color
+    /// swap case.
+    pub fn replace_color(&mut self, from: [u8; 4], to: [u8; 4]) {
+        self.remap_colors(|rgba| if rgba == from { to } else { rgba });
+    }
+
+    /// A deterministic content hash of this model's voxels, independent of
+    /// the file's original chunk/block layout: sorts the voxel set by
+    /// `(x, y, z, r, g, b, a)` before hashing so two models with the same
+    /// voxels hash equal even if they were assembled from differently
+    /// arranged `BL16` blocks. Doesn't hash [`Model`]'s material
+    /// assignments. Useful for content-addressed caching that wants to
+    /// skip reprocessing a model it's already seen.
+    pub fn content_hash(&self) -> u64 {
+        let mut voxels: Vec<((i32, i32, i32), [u8; 4])> =
+            self.voxels.iter().map(|(&pos, &rgba)| (pos, rgba)).collect();
+        voxels.sort_unstable();
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        voxels.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Run-length encodes this model's voxels, collapsing contiguous
+    /// same-color runs along the X axis into one [`RleRun`] each. Runs are
+    /// ordered by `(y, z)` then by starting `x`, so two models with the
+    /// same voxels always produce the same runs regardless of
+    /// [`HashMap`] iteration order. A lightweight alternative to a `BL16`
+    /// PNG block for transmitting a model over the wire. To scan a
+    /// different axis, [`Model::rotate90`] first.
+    pub fn to_rle(&self) -> Vec<RleRun> {
+        let mut rows: RleRows = HashMap::new();
+        for (&(x, y, z), &rgba) in &self.voxels {
+            rows.entry((y, z)).or_default().push((x, rgba));
+        }
+
+        let mut rows: Vec<_> = rows.into_iter().collect();
+        rows.sort_unstable_by_key(|&(yz, _)| yz);
+
+        let mut runs = Vec::new();
+        for ((y, z), mut xs) in rows {
+            xs.sort_unstable_by_key(|&(x, _)| x);
+
+            let mut xs = xs.into_iter();
+            let Some((mut start, mut color)) = xs.next() else {
+                continue;
+            };
+            let mut length = 1;
+            let mut prev = start;
+
+            for (x, rgba) in xs {
+                if x == prev + 1 && rgba == color {
+                    length += 1;
+                } else {
+                    runs.push(RleRun { start: (start, y, z), length, color });
+                    start = x;
+                    color = rgba;
+                    length = 1;
+                }
+                prev = x;
+            }
+            runs.push(RleRun { start: (start, y, z), length, color });
+        }
+        runs
+    }
+
+    /// Rebuilds a model from runs produced by [`Model::to_rle`]. Doesn't
+    /// restore per-voxel materials, since [`RleRun`] doesn't carry them.
+    pub fn from_rle(runs: &[RleRun]) -> Model {
+        let mut model = Model::new();
+        for run in runs {
+            let (x, y, z) = run.start;
+            model.extend((0..run.length).map(|i| ((x + i as i32, y, z), run.color)));
+        }
+        model
+    }
+}
+
+/// One run of contiguous, same-color voxels along the X axis, produced by
+/// [`Model::to_rle`] and consumed by [`Model::from_rle`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RleRun {
+    pub start: (i32, i32, i32),
+    pub length: u32,
+    pub color: [u8; 4],
+}
+
+/// [`Model::to_rle`]'s working set: each `(y, z)` row's voxels, as
+/// `(x, color)` pairs awaiting a sort and a scan for runs.
+type RleRows = HashMap<(i32, i32), Vec<(i32, [u8; 4])>>;
+
+/// One median-cut bucket built by [`Model::quantize`]: distinct colors
+/// paired with how many voxels use each, awaiting a split or averaging
+/// into a final palette entry.
+type QuantizeBucket = Vec<([u8; 4], usize)>;
+
+/// For each of a quantize bucket's four RGBA channels, the spread between
+/// its lowest and highest value; paired with the widest such channel.
+fn channel_range(bucket: &QuantizeBucket) -> (usize, u32) {
+    (0..4)
+        .map(|channel| {
+            let min = bucket.iter().map(|&(c, _)| c[channel]).min().unwrap();
+            let max = bucket.iter().map(|&(c, _)| c[channel]).max().unwrap();
+            (channel, (max - min) as u32)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap()
+}
+
+/// Splits a quantize bucket in two along `channel`, at the midpoint of its
+/// colors sorted by that channel's value.
+fn split_bucket(mut bucket: QuantizeBucket, channel: usize) -> (QuantizeBucket, QuantizeBucket) {
+    bucket.sort_unstable_by_key(|&(c, _)| c[channel]);
+    let high = bucket.split_off(bucket.len() / 2);
+    (bucket, high)
+}
+
+/// The count-weighted average color of a quantize bucket, used as its
+/// palette entry.
+fn bucket_average(bucket: &QuantizeBucket) -> [u8; 4] {
+    let total: u64 = bucket.iter().map(|&(_, count)| count as u64).sum();
+    let mut sums = [0u64; 4];
+    for &(color, count) in bucket {
+        for (channel, sum) in sums.iter_mut().enumerate() {
+            *sum += color[channel] as u64 * count as u64;
+        }
+    }
+    sums.map(|sum| (sum / total) as u8)
+}
+
+/// The palette entry closest to `color` in squared Euclidean RGBA distance.
+fn nearest_color(color: [u8; 4], palette: &[[u8; 4]]) -> [u8; 4] {
+    *palette
+        .iter()
+        .min_by_key(|&&candidate| color_distance_sq(color, candidate))
+        .expect("palette is never empty when quantizing a non-empty model")
+}
+
+/// Rounds `value` down to a multiple of `2^(8 - bits_per_channel)`, i.e.
+/// keeps only the top `bits_per_channel` bits of the byte and zeroes the
+/// rest. Clamped to `1..=8` so a caller passing 0 or a value above 8 still
+/// gets a sane bucket instead of a shift overflow. Used by
+/// [`Model::histogram`] to merge near-colors into the same bucket.
+fn quantize_channel(value: u8, bits_per_channel: u8) -> u8 {
+    let shift = 8 - bits_per_channel.clamp(1, 8);
+    (value >> shift) << shift
+}
+
+fn color_distance_sq(a: [u8; 4], b: [u8; 4]) -> u32 {
+    (0..4)
+        .map(|channel| {
+            let delta = a[channel] as i32 - b[channel] as i32;
+            (delta * delta) as u32
+        })
+        .sum()
+}
+
+/// A small, deterministic pseudo-random offset for `channel` at voxel
+/// position `(x, y, z)`, in the range `-8..=8`. Used by [`Model::quantize`]
+/// to dither colors before quantizing, so repeated runs stay reproducible.
+fn dither_offset(x: i32, y: i32, z: i32, channel: u8) -> i32 {
+    let mut h = (x as u32 as u64)
+        .wrapping_mul(73_856_093)
+        .wrapping_add((y as u32 as u64).wrapping_mul(19_349_663))
+        .wrapping_add((z as u32 as u64).wrapping_mul(83_492_791))
+        .wrapping_add(channel as u64);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x2545_F491_4F6C_DD1D);
+    h ^= h >> 29;
+    (h % 17) as i32 - 8
+}
+
+impl std::ops::Deref for Model {
+    type Target = HashMap<(i32, i32, i32), [u8; 4]>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.voxels
+    }
+}
+
+impl Extend<((i32, i32, i32), [u8; 4])> for Model {
+    fn extend<T: IntoIterator<Item = ((i32, i32, i32), [u8; 4])>>(&mut self, iter: T) {
+        self.voxels.extend(iter);
+    }
+}
+
+/// Builds a [`Model`] from a flat iterator of `(position, color)` pairs,
+/// the simplest entry point for authoring a model from a procedural
+/// generator or another format's voxel data, e.g.
+/// `let model: Model = voxels.into_iter().collect();`. Later voxels at the
+/// same position overwrite earlier ones, same as [`Model::extend`].
+impl FromIterator<((i32, i32, i32), [u8; 4])> for Model {
+    fn from_iter<T: IntoIterator<Item = ((i32, i32, i32), [u8; 4])>>(iter: T) -> Self {
+        let mut model = Model::new();
+        model.extend(iter);
+        model
+    }
+}
+
+/// The axis-aligned bounding box of a model's occupied voxels, as inclusive
+/// min/max world-space coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: (i32, i32, i32),
+    pub max: (i32, i32, i32),
+}
+
+/// The axis [`Model::render_ortho`] (and [`Model::front_view`]) projects
+/// along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewDir {
+    /// Looks down the `y` axis; nearer (smaller `y`) voxels occlude
+    /// farther ones.
+    Front,
+    /// Looks down the `z` axis from above; nearer (larger `z`) voxels
+    /// occlude farther ones.
+    Top,
+    /// Looks down the `x` axis; nearer (smaller `x`) voxels occlude
+    /// farther ones.
+    Side,
+}
+
+impl Model {
+    /// Computes the axis-aligned bounding box of this model's occupied
+    /// voxels, or `None` if the model is empty.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut coords = self.voxels.keys();
+        let &first = coords.next()?;
+        let mut bbox = BoundingBox {
+            min: first,
+            max: first,
+        };
+        for &(x, y, z) in coords {
+            bbox.min = (bbox.min.0.min(x), bbox.min.1.min(y), bbox.min.2.min(z));
+            bbox.max = (bbox.max.0.max(x), bbox.max.1.max(y), bbox.max.2.max(z));
+        }
+        Some(bbox)
+    }
+
+    /// A bounding sphere enclosing every occupied voxel: the bounding
+    /// box's center, and a radius reaching its farthest voxel. This isn't
+    /// the minimal enclosing sphere (which would need to consider voxels
+    /// off the box's diagonal too), just a simple, cheap-to-compute one
+    /// that's good enough for frustum and LOD culling. `None` for an empty
+    /// model.
+    pub fn bounding_sphere(&self) -> Option<([f32; 3], f32)> {
+        let bbox = self.bounding_box()?;
+        // Cast each coordinate to f64 before summing, same as
+        // `centroid`/`alpha_weighted_centroid` below: an untrusted file's
+        // block offsets can land min/max near opposite ends of i32's range,
+        // and `i32 + i32` would overflow before the cast ever runs.
+        let center = [
+            ((bbox.min.0 as f64 + bbox.max.0 as f64) / 2.0) as f32,
+            ((bbox.min.1 as f64 + bbox.max.1 as f64) / 2.0) as f32,
+            ((bbox.min.2 as f64 + bbox.max.2 as f64) / 2.0) as f32,
+        ];
+        let radius = self
+            .voxels
+            .keys()
+            .map(|&(x, y, z)| {
+                let d = [x as f32 - center[0], y as f32 - center[1], z as f32 - center[2]];
+                (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+            })
+            .fold(0f32, f32::max);
+        Some((center, radius))
+    }
+
+    /// The average position of every occupied voxel, weighted equally
+    /// regardless of color or alpha. `None` for an empty model. Useful for
+    /// auto-placing a pivot or camera target when importing into another
+    /// engine.
+    pub fn centroid(&self) -> Option<[f32; 3]> {
+        if self.voxels.is_empty() {
+            return None;
+        }
+        let mut sum = [0f64; 3];
+        for &(x, y, z) in self.voxels.keys() {
+            sum[0] += x as f64;
+            sum[1] += y as f64;
+            sum[2] += z as f64;
+        }
+        let count = self.voxels.len() as f64;
+        Some(sum.map(|s| (s / count) as f32))
+    }
+
+    /// Like [`Model::centroid`], but weights each voxel's position by its
+    /// alpha, so mostly-transparent voxels pull the result toward them less
+    /// than opaque ones. `None` if every voxel is fully transparent (alpha
+    /// `0`), same as for an empty model.
+    pub fn alpha_weighted_centroid(&self) -> Option<[f32; 3]> {
+        let mut sum = [0f64; 3];
+        let mut weight = 0f64;
+        for (&(x, y, z), &[_, _, _, a]) in &self.voxels {
+            let a = a as f64;
+            sum[0] += x as f64 * a;
+            sum[1] += y as f64 * a;
+            sum[2] += z as f64 * a;
+            weight += a;
+        }
+        if weight == 0.0 {
+            return None;
+        }
+        Some(sum.map(|s| (s / weight) as f32))
+    }
+
+    /// Crops this model to its bounding box, shifting every voxel so the
+    /// minimum corner lands at the origin. Returns the cropped model
+    /// paired with the offset that was subtracted; add it back to every
+    /// voxel to restore the original position. An empty model crops to
+    /// itself with a zero offset.
+    pub fn cropped(&self) -> (Model, (i32, i32, i32)) {
+        let Some(bbox) = self.bounding_box() else {
+            return (Model::new(), (0, 0, 0));
+        };
+
+        let mut cropped = Model::new();
+        cropped.extend(self.iter().map(|([x, y, z], rgba)| {
+            (
+                (x - bbox.min.0, y - bbox.min.1, z - bbox.min.2),
+                rgba,
+            )
+        }));
+        (cropped, bbox.min)
+    }
+
+    /// Shifts every voxel by `offset`, preserving colors. The building
+    /// block for placing multiple models into a shared scene or
+    /// re-centering one before export; see [`Model::cropped`] for undoing a
+    /// shift back to the origin. An empty model translates to itself.
+    pub fn translated(&self, offset: [i32; 3]) -> Model {
+        let mut translated = Model::new();
+        translated.extend(
+            self.iter()
+                .map(|([x, y, z], rgba)| ((x + offset[0], y + offset[1], z + offset[2]), rgba)),
+        );
+        translated
+    }
+
+    /// Mirrors every voxel across the model's bounding-box center along
+    /// `axis`, preserving colors. The center is the midpoint of the
+    /// bounding box, so this works for both even- and odd-width models
+    /// without gaps or collisions. An empty model mirrors to itself.
+    pub fn mirror(&self, axis: Axis) -> Model {
+        let Some(bbox) = self.bounding_box() else {
+            return Model::new();
+        };
+
+        let mut mirrored = Model::new();
+        mirrored.extend(self.iter().map(|([x, y, z], rgba)| {
+            let pos = match axis {
+                Axis::X => (bbox.min.0 + bbox.max.0 - x, y, z),
+                Axis::Y => (x, bbox.min.1 + bbox.max.1 - y, z),
+                Axis::Z => (x, y, bbox.min.2 + bbox.max.2 - z),
+            };
+            (pos, rgba)
+        }));
+        mirrored
+    }
+
+    /// Rotates every voxel by `quarter_turns` 90° turns around `axis`
+    /// (normalized modulo 4), recentering after each turn so the model's
+    /// bounding box doesn't drift. This is the only kind of rotation that's
+    /// lossless on an integer voxel grid, since anything else requires
+    /// resampling. Preserves colors; an empty model rotates to itself.
+    pub fn rotate90(&self, axis: Axis, quarter_turns: u8) -> Model {
+        let mut current = self.clone();
+        for _ in 0..quarter_turns % 4 {
+            let Some(bbox) = current.bounding_box() else {
+                return current;
+            };
+
+            let mut raw = Model::new();
+            raw.extend(
+                current
+                    .iter()
+                    .map(|([x, y, z], rgba)| (rotate90_once(axis, x, y, z), rgba)),
+            );
+            let raw_min = raw.bounding_box().expect("non-empty, checked above").min;
+            let offset = (
+                bbox.min.0 - raw_min.0,
+                bbox.min.1 - raw_min.1,
+                bbox.min.2 - raw_min.2,
+            );
+
+            let mut next = Model::new();
+            next.extend(raw.iter().map(|([x, y, z], rgba)| {
+                ((x + offset.0, y + offset.1, z + offset.2), rgba)
+            }));
+            current = next;
+        }
+        current
+    }
+
+    /// Halves this model's resolution along every axis, grouping voxels
+    /// into 2×2×2 cells and emitting one voxel per non-empty cell. A cell's
+    /// color is the average of its occupied voxels; its alpha reflects how
+    /// much of the cell is covered (occupied voxel count out of 8), rather
+    /// than averaging alpha itself. Useful for thumbnails or LOD meshes.
+    pub fn downsample_half(&self) -> Model {
+        let mut cells: HashMap<(i32, i32, i32), Vec<[u8; 4]>> = HashMap::new();
+        for ([x, y, z], rgba) in self.iter() {
+            if rgba[3] == 0 {
+                continue;
+            }
+            let cell = (x.div_euclid(2), y.div_euclid(2), z.div_euclid(2));
+            cells.entry(cell).or_default().push(rgba);
+        }
+
+        let mut downsampled = Model::new();
+        downsampled.extend(cells.into_iter().map(|(cell, voxels)| {
+            let count = voxels.len() as u32;
+            let mut sum = [0u32; 3];
+            for voxel in &voxels {
+                for (channel, total) in sum.iter_mut().enumerate() {
+                    *total += voxel[channel] as u32;
+                }
+            }
+            let rgba = [
+                (sum[0] / count) as u8,
+                (sum[1] / count) as u8,
+                (sum[2] / count) as u8,
+                (count as f32 / 8.0 * 255.0).round() as u8,
+            ];
+            (cell, rgba)
+        }));
+        downsampled
+    }
+
+    /// Replaces each voxel with a `factor`³ block of the same color,
+    /// scaling this model's resolution up to match a finer one before
+    /// [`Model::merge`]ing them. `factor` is clamped to
+    /// [`MAX_UPSAMPLE_FACTOR`] so a runaway value can't blow up into an
+    /// unbounded allocation; a factor of `0` or `1` leaves the model
+    /// unchanged (aside from a clone). An empty model upsamples to itself.
+    pub fn upsample(&self, factor: u32) -> Model {
+        let factor = factor.clamp(1, MAX_UPSAMPLE_FACTOR) as i32;
+        if factor == 1 {
+            return self.clone();
+        }
+
+        let mut upsampled = Model::new();
+        for ([x, y, z], rgba) in self.iter() {
+            let (bx, by, bz) = (x * factor, y * factor, z * factor);
+            upsampled.extend((0..factor).flat_map(|dx| {
+                (0..factor).flat_map(move |dy| {
+                    (0..factor).map(move |dz| ((bx + dx, by + dy, bz + dz), rgba))
+                })
+            }));
+        }
+        upsampled
+    }
+
+    /// Builds a dense RGBA volume from this model's occupied voxels, sized
+    /// to its bounding box and zero-filled (fully transparent) everywhere
+    /// else. The array's axes are `[depth, height, width, 4]` (z, y, x,
+    /// channel); pair it with the returned minimum corner to map an array
+    /// index `[dz, dy, dx]` back to the world coordinate
+    /// `(dx + min.0, dy + min.1, dz + min.2)`. An empty model produces a
+    /// zero-sized array with a zero offset. Only available with the
+    /// `ndarray` feature, for interop with the scientific Rust ecosystem.
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> (ndarray::Array4<u8>, (i32, i32, i32)) {
+        let Some(bbox) = self.bounding_box() else {
+            return (ndarray::Array4::zeros((0, 0, 0, 4)), (0, 0, 0));
+        };
+
+        let width = (bbox.max.0 - bbox.min.0 + 1) as usize;
+        let height = (bbox.max.1 - bbox.min.1 + 1) as usize;
+        let depth = (bbox.max.2 - bbox.min.2 + 1) as usize;
+
+        let mut array = ndarray::Array4::zeros((depth, height, width, 4));
+        for ([x, y, z], rgba) in self.iter() {
+            let dx = (x - bbox.min.0) as usize;
+            let dy = (y - bbox.min.1) as usize;
+            let dz = (z - bbox.min.2) as usize;
+            for (channel, &value) in rgba.iter().enumerate() {
+                array[[dz, dy, dx, channel]] = value;
+            }
+        }
+        (array, bbox.min)
+    }
+
+    /// Renders the voxels at a single `z` plane as a flat RGBA image, sized
+    /// to the model's overall XY bounding box (not just this slice's own
+    /// extent) so slices at different `z` line up pixel-for-pixel, e.g. for
+    /// a contact-sheet of a model's cross-sections. A cell with no voxel at
+    /// that `z` is fully transparent. An empty model produces a zero-sized
+    /// image.
+    pub fn slice_z(&self, z: i32) -> image::RgbaImage {
+        let Some(bbox) = self.bounding_box() else {
+            return image::RgbaImage::new(0, 0);
+        };
+        let width = (bbox.max.0 - bbox.min.0 + 1) as u32;
+        let height = (bbox.max.1 - bbox.min.1 + 1) as u32;
+        let mut slice = image::RgbaImage::new(width, height);
+        for y in bbox.min.1..=bbox.max.1 {
+            for x in bbox.min.0..=bbox.max.0 {
+                if let Some(rgba) = self.voxel_at(x, y, z) {
+                    slice.put_pixel((x - bbox.min.0) as u32, (y - bbox.min.1) as u32, image::Rgba(rgba));
+                }
+            }
+        }
+        slice
+    }
+
+    /// Renders every `z` layer across the model's bounds as its own
+    /// [`Model::slice_z`] image, in ascending `z` order, for 2D tooling or
+    /// texture baking — e.g. saving the result as `layer_000.png`,
+    /// `layer_001.png`, and so on for slice-based 3D printing. An empty
+    /// model produces an empty stack.
+    pub fn to_png_stack(&self) -> Vec<image::RgbaImage> {
+        let Some(bbox) = self.bounding_box() else {
+            return Vec::new();
+        };
+        (bbox.min.2..=bbox.max.2).map(|z| self.slice_z(z)).collect()
+    }
+
+    /// Like [`Model::slice_z`], but slices along `x`, producing a
+    /// `width × height` image of the model's YZ extent (image `x` maps to
+    /// world `y`, image `y` maps to world `z`).
+    pub fn slice_x(&self, x: i32) -> image::RgbaImage {
+        let Some(bbox) = self.bounding_box() else {
+            return image::RgbaImage::new(0, 0);
+        };
+        let width = (bbox.max.1 - bbox.min.1 + 1) as u32;
+        let height = (bbox.max.2 - bbox.min.2 + 1) as u32;
+        let mut slice = image::RgbaImage::new(width, height);
+        for z in bbox.min.2..=bbox.max.2 {
+            for y in bbox.min.1..=bbox.max.1 {
+                if let Some(rgba) = self.voxel_at(x, y, z) {
+                    slice.put_pixel((y - bbox.min.1) as u32, (z - bbox.min.2) as u32, image::Rgba(rgba));
+                }
+            }
+        }
+        slice
+    }
+
+    /// Like [`Model::slice_z`], but slices along `y`, producing a
+    /// `width × height` image of the model's XZ extent (image `x` maps to
+    /// world `x`, image `y` maps to world `z`).
+    pub fn slice_y(&self, y: i32) -> image::RgbaImage {
+        let Some(bbox) = self.bounding_box() else {
+            return image::RgbaImage::new(0, 0);
+        };
+        let width = (bbox.max.0 - bbox.min.0 + 1) as u32;
+        let height = (bbox.max.2 - bbox.min.2 + 1) as u32;
+        let mut slice = image::RgbaImage::new(width, height);
+        for z in bbox.min.2..=bbox.max.2 {
+            for x in bbox.min.0..=bbox.max.0 {
+                if let Some(rgba) = self.voxel_at(x, y, z) {
+                    slice.put_pixel((x - bbox.min.0) as u32, (z - bbox.min.2) as u32, image::Rgba(rgba));
+                }
+            }
+        }
+        slice
+    }
+
+    /// Renders the voxels at a single `z` plane as a character grid, `#`
+    /// for an occupied cell and a space for an empty one, one line per row
+    /// of `y` within the model's overall XY bounding box (not just this
+    /// slice's own extent), same as [`Model::slice_z`]. Handy for printing
+    /// a quick look at a model from a test failure or a bug report without
+    /// reaching for an image viewer. An empty model produces an empty
+    /// string.
+    pub fn ascii_slice(&self, z: i32) -> String {
+        let Some(bbox) = self.bounding_box() else {
+            return String::new();
+        };
+        let mut out = String::new();
+        for y in bbox.min.1..=bbox.max.1 {
+            if y > bbox.min.1 {
+                out.push('\n');
+            }
+            for x in bbox.min.0..=bbox.max.0 {
+                out.push(if self.voxel_at(x, y, z).is_some() { '#' } else { ' ' });
+            }
+        }
+        out
+    }
+
+    /// Renders a flat orthographic front view of the model by looking down
+    /// the `y` axis: for each `(x, z)` column, the color of the closest
+    /// (smallest `y`) voxel wins, as if the rest of the column were behind
+    /// it. Used by [`Goxel::thumbnail`] as a fallback render for files with
+    /// no `PREV` chunk to decode. An empty model produces a zero-sized
+    /// image, same as [`Model::slice_z`]. See [`Model::render_ortho`] for a
+    /// version that also looks from the top or side and scales the result
+    /// to a fixed size.
+    pub fn front_view(&self) -> image::RgbaImage {
+        let Some(bbox) = self.bounding_box() else {
+            return image::RgbaImage::new(0, 0);
+        };
+        Self::project_nearest(&bbox, ViewDir::Front, |x, y, z| self.voxel_at(x, y, z))
+    }
+
+    /// Depth-sorted projection shared by [`Model::front_view`] and
+    /// [`Model::render_ortho`]: collapses the model along `view`'s axis,
+    /// keeping the color of whichever voxel in each column is nearest the
+    /// camera, at the native resolution of `bbox`'s extent on the other two
+    /// axes.
+    fn project_nearest(
+        bbox: &BoundingBox,
+        view: ViewDir,
+        voxel_at: impl Fn(i32, i32, i32) -> Option<[u8; 4]>,
+    ) -> image::RgbaImage {
+        match view {
+            ViewDir::Front => {
+                let width = (bbox.max.0 - bbox.min.0 + 1) as u32;
+                let height = (bbox.max.2 - bbox.min.2 + 1) as u32;
+                let mut out = image::RgbaImage::new(width, height);
+                for x in bbox.min.0..=bbox.max.0 {
+                    for z in bbox.min.2..=bbox.max.2 {
+                        for y in bbox.min.1..=bbox.max.1 {
+                            if let Some(rgba) = voxel_at(x, y, z) {
+                                out.put_pixel((x - bbox.min.0) as u32, (z - bbox.min.2) as u32, image::Rgba(rgba));
+                                break;
+                            }
+                        }
+                    }
+                }
+                out
+            }
+            ViewDir::Top => {
+                let width = (bbox.max.0 - bbox.min.0 + 1) as u32;
+                let height = (bbox.max.1 - bbox.min.1 + 1) as u32;
+                let mut out = image::RgbaImage::new(width, height);
+                for x in bbox.min.0..=bbox.max.0 {
+                    for y in bbox.min.1..=bbox.max.1 {
+                        for z in (bbox.min.2..=bbox.max.2).rev() {
+                            if let Some(rgba) = voxel_at(x, y, z) {
+                                out.put_pixel((x - bbox.min.0) as u32, (y - bbox.min.1) as u32, image::Rgba(rgba));
+                                break;
+                            }
+                        }
+                    }
+                }
+                out
+            }
+            ViewDir::Side => {
+                let width = (bbox.max.1 - bbox.min.1 + 1) as u32;
+                let height = (bbox.max.2 - bbox.min.2 + 1) as u32;
+                let mut out = image::RgbaImage::new(width, height);
+                for y in bbox.min.1..=bbox.max.1 {
+                    for z in bbox.min.2..=bbox.max.2 {
+                        for x in bbox.min.0..=bbox.max.0 {
+                            if let Some(rgba) = voxel_at(x, y, z) {
+                                out.put_pixel((y - bbox.min.1) as u32, (z - bbox.min.2) as u32, image::Rgba(rgba));
+                                break;
+                            }
+                        }
+                    }
+                }
+                out
+            }
+        }
+    }
+
+    /// Renders a flat, depth-sorted orthographic projection of the model
+    /// looking down `view`'s axis, scaled to a `size`×`size` image. Nearer
+    /// voxels occlude farther ones along the view axis (see
+    /// [`Model::project_nearest`]), so the result looks like a simple
+    /// render rather than a single cross-section. Useful as a thumbnail for
+    /// files with no embedded `PREV`, or for documentation screenshots. An
+    /// empty model produces a blank `size`×`size` image.
+    pub fn render_ortho(&self, view: ViewDir, size: u32) -> image::RgbaImage {
+        let Some(bbox) = self.bounding_box() else {
+            return image::RgbaImage::new(size, size);
+        };
+        let raw = Self::project_nearest(&bbox, view, |x, y, z| self.voxel_at(x, y, z));
+        image::imageops::resize(&raw, size, size, image::imageops::FilterType::Nearest)
+    }
+
+    /// Builds a dense, 1-voxel-padded [`BlockMeshVoxel`] grid from this
+    /// model's occupied voxels and runs `block-mesh`'s greedy quad meshing
+    /// over it, returning the filled [`block_mesh::GreedyQuadsBuffer`]
+    /// alongside the grid's shape and the world-space minimum corner needed
+    /// to map a quad's coordinates back to world space. The padding gives
+    /// `greedy_quads`'s 3x3x3 neighborhood room to see the model's outer
+    /// faces as exposed. Only available with the `block-mesh` feature, for
+    /// production-grade meshing (proper face merging and normals) in place
+    /// of [`Model::greedy_mesh`]'s hand-rolled algorithm. An empty model
+    /// returns an empty buffer over a zero-sized grid.
+    #[cfg(feature = "block-mesh")]
+    pub fn to_block_mesh_buffer(
+        &self,
+    ) -> (
+        block_mesh::GreedyQuadsBuffer,
+        block_mesh::ndshape::RuntimeShape<u32, 3>,
+        (i32, i32, i32),
+    ) {
+        use block_mesh::ndshape::Shape;
+
+        let Some(bbox) = self.bounding_box() else {
+            let shape = block_mesh::ndshape::RuntimeShape::<u32, 3>::new([0, 0, 0]);
+            return (block_mesh::GreedyQuadsBuffer::new(0), shape, (0, 0, 0));
+        };
+
+        let width = (bbox.max.0 - bbox.min.0 + 1) as u32 + 2;
+        let height = (bbox.max.1 - bbox.min.1 + 1) as u32 + 2;
+        let depth = (bbox.max.2 - bbox.min.2 + 1) as u32 + 2;
+        let shape = block_mesh::ndshape::RuntimeShape::<u32, 3>::new([width, height, depth]);
+
+        let mut voxels = vec![BlockMeshVoxel([0, 0, 0, 0]); shape.size() as usize];
+        for ([x, y, z], rgba) in self.iter() {
+            let px = (x - bbox.min.0) as u32 + 1;
+            let py = (y - bbox.min.1) as u32 + 1;
+            let pz = (z - bbox.min.2) as u32 + 1;
+            voxels[shape.linearize([px, py, pz]) as usize] = BlockMeshVoxel(rgba);
+        }
+
+        let mut buffer = block_mesh::GreedyQuadsBuffer::new(voxels.len());
+        block_mesh::greedy_quads(
+            &voxels,
+            &shape,
+            [0, 0, 0],
+            [width - 1, height - 1, depth - 1],
+            &block_mesh::RIGHT_HANDED_Y_UP_CONFIG.faces,
+            &mut buffer,
+        );
+
+        let offset = (bbox.min.0 - 1, bbox.min.1 - 1, bbox.min.2 - 1);
+        (buffer, shape, offset)
+    }
+
+    /// Converts every voxel's color from 8-bit sRGB to linear, applying the
+    /// sRGB transfer function per RGB channel and leaving alpha linear.
+    /// Useful for handing this model's colors to a renderer that expects
+    /// linear light rather than display-ready sRGB. See [`LinearModel::to_srgb`]
+    /// for the inverse.
+    pub fn to_linear(&self) -> LinearModel {
+        let mut voxels = HashMap::with_capacity(self.voxels.len());
+        for (&pos, &[r, g, b, a]) in &self.voxels {
+            voxels.insert(
+                pos,
+                [
+                    srgb_to_linear(r),
+                    srgb_to_linear(g),
+                    srgb_to_linear(b),
+                    a as f32 / 255.0,
+                ],
+            );
+        }
+        LinearModel { voxels }
+    }
+
+    /// Builds a dense, bit-packed occupancy grid over this model's
+    /// bounding box: one bit per voxel instead of storing colors, for fast
+    /// collision/occlusion queries or interior-voxel culling during
+    /// meshing. Returns the grid alongside the bounding box's minimum
+    /// corner; subtract it from a world coordinate to get the local
+    /// coordinate [`Occupancy::get`] expects. An empty model produces an
+    /// empty grid with a zero offset.
+    pub fn occupancy(&self) -> (Occupancy, [i32; 3]) {
+        let Some(bbox) = self.bounding_box() else {
+            return (
+                Occupancy {
+                    bits: Vec::new(),
+                    width: 0,
+                    height: 0,
+                    depth: 0,
+                },
+                [0, 0, 0],
+            );
+        };
+
+        let width = bbox.max.0 - bbox.min.0 + 1;
+        let height = bbox.max.1 - bbox.min.1 + 1;
+        let depth = bbox.max.2 - bbox.min.2 + 1;
+        let bit_count = (width * height * depth) as usize;
+
+        let mut occupancy = Occupancy {
+            bits: vec![0u64; bit_count.div_ceil(64)],
+            width,
+            height,
+            depth,
+        };
+        for ([x, y, z], rgba) in self.iter() {
+            if rgba[3] == 0 {
+                continue;
+            }
+            occupancy.set(x - bbox.min.0, y - bbox.min.1, z - bbox.min.2);
+        }
+        (occupancy, [bbox.min.0, bbox.min.1, bbox.min.2])
+    }
+
+    /// Drops voxels that are fully enclosed by occupied neighbors on all
+    /// six faces, since they can never be seen. A voxel at the model's
+    /// boundary always counts as exposed on the faces that have no
+    /// neighbor at all. Useful for shrinking solid models before meshing
+    /// or `.vox` export.
+    pub fn shell_only(&self) -> Model {
+        const NEIGHBOR_OFFSETS: [(i32, i32, i32); 6] = [
+            (1, 0, 0),
+            (-1, 0, 0),
+            (0, 1, 0),
+            (0, -1, 0),
+            (0, 0, 1),
+            (0, 0, -1),
+        ];
+
+        let mut shell = Model::new();
+        shell.extend(
+            self.iter()
+                .filter(|&([x, y, z], _)| {
+                    NEIGHBOR_OFFSETS
+                        .iter()
+                        .any(|(dx, dy, dz)| self.voxel_at(x + dx, y + dy, z + dz).is_none())
+                })
+                .map(|([x, y, z], rgba)| ((x, y, z), rgba)),
+        );
+        shell
+    }
+
+    /// The number of occupied voxels, i.e. unit cubes. An alias for
+    /// [`Model::len`] under the name 3D-printing cost estimates tend to use
+    /// it by; pairs with [`Model::surface_area`].
+    pub fn volume(&self) -> usize {
+        self.len()
+    }
+
+    /// The number of exposed voxel faces: each occupied voxel contributes
+    /// one face for every side with no occupied neighbor, the same check
+    /// [`Model::shell_only`] uses to decide which voxels are visible at
+    /// all. Pairs with [`Model::volume`] for 3D-printing material and cost
+    /// estimates.
+    pub fn surface_area(&self) -> usize {
+        const NEIGHBOR_OFFSETS: [(i32, i32, i32); 6] = [
+            (1, 0, 0),
+            (-1, 0, 0),
+            (0, 1, 0),
+            (0, -1, 0),
+            (0, 0, 1),
+            (0, 0, -1),
+        ];
+
+        self.iter()
+            .map(|([x, y, z], _)| {
+                NEIGHBOR_OFFSETS
+                    .iter()
+                    .filter(|&&(dx, dy, dz)| self.voxel_at(x + dx, y + dy, z + dz).is_none())
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Splits this model into its connected components: groups of occupied
+    /// voxels reachable from one another through `connectivity`-adjacent
+    /// neighbors, found by flood-filling from an arbitrary unvisited voxel
+    /// until none remain. Useful for separating distinct objects authored
+    /// in a single file, or counting distinct parts. Returns one `Model`
+    /// per component, in no particular order; an empty model yields no
+    /// components.
+    pub fn components(&self, connectivity: Connectivity) -> Vec<Model> {
+        let offsets = connectivity.offsets();
+        let mut unvisited: HashSet<(i32, i32, i32)> = self.voxels.keys().copied().collect();
+        let mut components = Vec::new();
+
+        while let Some(&start) = unvisited.iter().next() {
+            unvisited.remove(&start);
+            let mut component = Model::new();
+            component.place(self, start);
+
+            let mut stack = vec![start];
+            while let Some((x, y, z)) = stack.pop() {
+                for (dx, dy, dz) in &offsets {
+                    let neighbor = (x + dx, y + dy, z + dz);
+                    if unvisited.remove(&neighbor) {
+                        component.place(self, neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Copies the voxel (and material, if any) at `pos` from `source` into
+    /// `self`. A small helper for [`Model::components`], which builds each
+    /// component up voxel by voxel as it's discovered.
+    fn place(&mut self, source: &Model, pos: (i32, i32, i32)) {
+        self.voxels.insert(pos, source.voxels[&pos]);
+        if let Some(&material) = source.materials.get(&pos) {
+            self.materials.insert(pos, material);
+        }
+    }
+
+    /// Copies every voxel (and material, if any) from `other` into `self`,
+    /// using `conflict` to decide what happens when both models already
+    /// occupy the same coordinate. With [`MergePolicy::Error`], this checks
+    /// for a conflict before touching `self`, so a failed merge leaves it
+    /// unchanged. Paired with [`Model::translated`], this is the building
+    /// block for laying out multiple imported models in a shared coordinate
+    /// space before exporting.
+    pub fn merge(&mut self, other: &Model, conflict: MergePolicy) -> Result<(), MergeConflict> {
+        if conflict == MergePolicy::Error
+            && let Some(&position) = other.voxels.keys().find(|pos| self.voxels.contains_key(pos))
+        {
+            return Err(MergeConflict { position });
+        }
+
+        for (&pos, &rgba) in &other.voxels {
+            if conflict == MergePolicy::KeepSelf && self.voxels.contains_key(&pos) {
+                continue;
+            }
+            self.voxels.insert(pos, rgba);
+            match other.materials.get(&pos) {
+                Some(&material) => {
+                    self.materials.insert(pos, material);
+                }
+                None => {
+                    self.materials.remove(&pos);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares this model against `other` and reports which voxels were
+    /// added, removed, or recolored, based on the reconstructed voxel sets
+    /// rather than raw chunk bytes — so cosmetic file differences (chunk
+    /// order, unrelated metadata) never show up. Useful for diffing two
+    /// `.gox` files for version control or regression testing.
+    pub fn diff(&self, other: &Model) -> ModelDiff {
+        let mut diff = ModelDiff::default();
+        for ([x, y, z], rgba) in self.iter() {
+            match other.voxel_at(x, y, z) {
+                None => diff.removed.push(((x, y, z), rgba)),
+                Some(new_rgba) if new_rgba != rgba => diff.changed.push(ChangedVoxel {
+                    position: (x, y, z),
+                    old: rgba,
+                    new: new_rgba,
+                }),
+                Some(_) => {}
+            }
+        }
+        for ([x, y, z], rgba) in other.iter() {
+            if self.voxel_at(x, y, z).is_none() {
+                diff.added.push(((x, y, z), rgba));
+            }
+        }
+        diff
+    }
+}
+
+/// How [`Model::merge`] should resolve two models both occupying the same
+/// voxel coordinate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep this model's voxel, discarding the incoming one.
+    KeepSelf,
+    /// Overwrite this model's voxel with the incoming one.
+    TakeOther,
+    /// Fail instead of silently picking a winner.
+    Error,
+}
+
+/// Returned by [`Model::merge`] under [`MergePolicy::Error`] when both
+/// models occupy `position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("both models claim voxel {position:?}")]
+pub struct MergeConflict {
+    pub position: (i32, i32, i32),
+}
+
+/// One voxel's color before and after, as reported by [`Model::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedVoxel {
+    pub position: (i32, i32, i32),
+    pub old: [u8; 4],
+    pub new: [u8; 4],
+}
+
+/// The result of comparing two [`Model`]s with [`Model::diff`]: voxels
+/// present only in the new model, voxels present only in the old model,
+/// and voxels present in both but with a different color.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelDiff {
+    pub added: Vec<((i32, i32, i32), [u8; 4])>,
+    pub removed: Vec<((i32, i32, i32), [u8; 4])>,
+    pub changed: Vec<ChangedVoxel>,
+}
+
+/// Wraps a [`Model`] voxel's RGBA color so it can implement `block-mesh`'s
+/// [`block_mesh::Voxel`]/[`block_mesh::MergeVoxel`] traits without running
+/// into the orphan rule (`[u8; 4]` is a foreign type). Transparent (alpha
+/// `0`) voxels are empty; any other color is opaque and merges with
+/// neighboring voxels of the same color into one quad. Built by
+/// [`Model::to_block_mesh_buffer`]; only available with the `block-mesh`
+/// feature.
+#[cfg(feature = "block-mesh")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockMeshVoxel(pub [u8; 4]);
+
+#[cfg(feature = "block-mesh")]
+impl block_mesh::Voxel for BlockMeshVoxel {
+    fn get_visibility(&self) -> block_mesh::VoxelVisibility {
+        if self.0[3] == 0 {
+            block_mesh::VoxelVisibility::Empty
+        } else {
+            block_mesh::VoxelVisibility::Opaque
+        }
+    }
+}
+
+#[cfg(feature = "block-mesh")]
+impl block_mesh::MergeVoxel for BlockMeshVoxel {
+    type MergeValue = [u8; 4];
+
+    fn merge_value(&self) -> Self::MergeValue {
+        self.0
+    }
+}
+
+/// A dense, bit-packed occupancy grid built by [`Model::occupancy`]. Uses
+/// one bit per voxel rather than a full RGBA volume, for queries that only
+/// care whether a voxel is present, not its color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occupancy {
+    bits: Vec<u64>,
+    width: i32,
+    height: i32,
+    depth: i32,
+}
+
+impl Occupancy {
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        if x < 0 || y < 0 || z < 0 || x >= self.width || y >= self.height || z >= self.depth {
+            return None;
+        }
+        Some((z * self.height * self.width + y * self.width + x) as usize)
+    }
+
+    fn set(&mut self, x: i32, y: i32, z: i32) {
+        let index = self.index(x, y, z).expect("caller passes in-bounds local coordinates");
+        self.bits[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Reports whether the voxel at local coordinate `(x, y, z)` is
+    /// occupied. Coordinates are relative to the grid's own origin, as
+    /// returned alongside it by [`Model::occupancy`], not world
+    /// coordinates; out-of-range coordinates are always unoccupied.
+    pub fn get(&self, x: i32, y: i32, z: i32) -> bool {
+        match self.index(x, y, z) {
+            Some(index) => self.bits[index / 64] & (1 << (index % 64)) != 0,
+            None => false,
+        }
+    }
+}
+
+/// A sparse, world-space voxel map with linear-light colors, built by
+/// [`Model::to_linear`]. Keyed the same way as [`Model`], but stores each
+/// voxel's color as `[f32; 4]` RGBA instead of 8-bit sRGB.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinearModel {
+    voxels: HashMap<(i32, i32, i32), [f32; 4]>,
+}
+
+impl LinearModel {
+    /// Iterates over every occupied voxel exactly once, in no particular
+    /// order, yielding its position and linear RGBA color.
+    pub fn iter(&self) -> impl Iterator<Item = ([i32; 3], [f32; 4])> + '_ {
+        self.voxels.iter().map(|(&(x, y, z), &rgba)| ([x, y, z], rgba))
+    }
+
+    /// Converts every voxel's color back to 8-bit sRGB, applying the
+    /// inverse sRGB transfer function per RGB channel and leaving alpha
+    /// linear. The inverse of [`Model::to_linear`].
+    pub fn to_srgb(&self) -> Model {
+        let mut model = Model::new();
+        model.voxels.reserve(self.voxels.len());
+        for (&pos, &[r, g, b, a]) in &self.voxels {
+            model.voxels.insert(
+                pos,
+                [
+                    linear_to_srgb(r),
+                    linear_to_srgb(g),
+                    linear_to_srgb(b),
+                    (a * 255.0).round() as u8,
+                ],
+            );
+        }
+        model
+    }
+}
+
+/// Converts a single 8-bit sRGB channel value to linear light, applying the
+/// standard sRGB transfer function (a linear segment near black, a gamma
+/// curve elsewhere).
+pub fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a single linear-light channel value back to 8-bit sRGB, the
+/// inverse of [`srgb_to_linear`].
+pub fn linear_to_srgb(value: f32) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let encoded = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+/// Rotates a single point 90° counterclockwise (right-hand rule) around
+/// `axis`, leaving the coordinate along `axis` unchanged.
+fn rotate90_once(axis: Axis, x: i32, y: i32, z: i32) -> (i32, i32, i32) {
+    match axis {
+        Axis::X => (x, -z, y),
+        Axis::Y => (z, y, -x),
+        Axis::Z => (-y, x, z),
+    }
+}
+
+/// An axis a [`Model`] can be mirrored or rotated along, via
+/// [`Model::mirror`] and [`Model::rotate90`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// How two voxels count as adjacent for [`Model::components`]: sharing a
+/// face (`Six`) or sharing at least a corner (`TwentySix`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Six,
+    TwentySix,
+}
+
+impl Connectivity {
+    fn offsets(self) -> Vec<(i32, i32, i32)> {
+        match self {
+            Connectivity::Six => vec![
+                (1, 0, 0),
+                (-1, 0, 0),
+                (0, 1, 0),
+                (0, -1, 0),
+                (0, 0, 1),
+                (0, 0, -1),
+            ],
+            Connectivity::TwentySix => (-1..=1)
+                .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+                .filter(|&offset| offset != (0, 0, 0))
+                .collect(),
+        }
+    }
+}
+
+/// Builds a [`Goxel`] from scratch by placing voxels directly, rather than
+/// parsing an existing file. This is the inverse of the parse path: voxels
+/// accumulate into the current layer; [`ModelBuilder::add_layer`] seals it
+/// off under a name and starts a fresh one, and [`ModelBuilder::build`]
+/// buckets every layer's voxels into 16×16×16 blocks, encodes each one as
+/// a `BL16` PNG, and assembles the `LAYR` chunks that reference them.
+#[derive(Debug, Clone, Default)]
+pub struct ModelBuilder {
+    current: Model,
+    layers: Vec<(String, Model)>,
+}
+
+impl ModelBuilder {
+    /// Starts an empty builder.
+    pub fn new() -> Self {
+        ModelBuilder::default()
+    }
+
+    /// Sets (or overwrites) the voxel at `(x, y, z)` in the current layer.
+    pub fn set_voxel(&mut self, x: i32, y: i32, z: i32, rgba: [u8; 4]) -> &mut Self {
+        self.current.voxels.insert((x, y, z), rgba);
+        self
+    }
+
+    /// Removes the voxel at `(x, y, z)` from the current layer, if any.
+    pub fn clear_voxel(&mut self, x: i32, y: i32, z: i32) -> &mut Self {
+        self.current.voxels.remove(&(x, y, z));
+        self.current.materials.remove(&(x, y, z));
+        self
+    }
+
+    /// Seals the voxels placed since the last `add_layer` (or since the
+    /// builder was created) into a layer named `name`, and starts a fresh,
+    /// empty one for whatever comes next.
+    pub fn add_layer(&mut self, name: impl Into<String>) -> &mut Self {
+        let sealed = std::mem::take(&mut self.current);
+        self.layers.push((name.into(), sealed));
+        self
+    }
+
+    /// Assembles the sealed layers into a [`Goxel`] ready for
+    /// [`Goxel::write`]. Any voxels placed but not yet sealed with
+    /// [`ModelBuilder::add_layer`] are included as one final, unnamed
+    /// layer. The result starts with a minimal `IMG` chunk, so
+    /// [`Goxel::validate`] accepts a freshly built file without the caller
+    /// having to add one by hand.
+    pub fn build(&self) -> Goxel {
+        let mut layers = self.layers.clone();
+        if !self.current.is_empty() {
+            layers.push((String::new(), self.current.clone()));
+        }
+
+        let mut chunks = vec![Chunk::Img { dict: authoring_img_dict() }];
+        for (name, model) in &layers {
+            let blocks = blocks_for_layer(model, &mut chunks);
+            chunks.push(Chunk::Layr {
+                blocks,
+                dict: layer_dict(name),
+            });
+        }
+
+        Goxel { version: 2, chunks }
+    }
+}
+
+/// Buckets `model`'s voxels into 16×16×16 blocks, appending a freshly
+/// encoded [`Chunk::Bl16`] for each one to `chunks`, and returns the
+/// [`Block`] placements a [`Chunk::Layr`] needs to reference them.
+/// `Block::index` counts `BL16` chunks globally, so this counts in from
+/// however many `chunks` already holds.
+///
+/// Buckets into a plain `HashMap` (since [`Model::iter`] is itself
+/// unordered, there'd be nothing to gain from an `IndexMap` here), then
+/// sorts the origins before emitting chunks, so two builds of the same
+/// model always produce byte-identical `BL16`/`Block` ordering regardless
+/// of `HashMap`'s randomized iteration order.
+fn blocks_for_layer(model: &Model, chunks: &mut Vec<Chunk>) -> Vec<Block> {
+    let mut by_origin: HashMap<(i32, i32, i32), Voxels> = HashMap::new();
+    for ([x, y, z], rgba) in model.iter() {
+        let (origin, [lx, ly, lz]) = world_to_block([x, y, z]);
+        let origin = (origin[0], origin[1], origin[2]);
+        let voxels = by_origin
+            .entry(origin)
+            .or_insert_with(|| [[[[0u8; 4]; 16]; 16]; 16]);
+        voxels[lx][ly][lz] = rgba;
+    }
+
+    let mut origins: Vec<(i32, i32, i32)> = by_origin.keys().copied().collect();
+    origins.sort_unstable();
+
+    let bl16_count = chunks.iter().filter(|c| matches!(c, Chunk::Bl16 { .. })).count();
+    origins
+        .into_iter()
+        .enumerate()
+        .map(|(i, (x, y, z))| {
+            chunks.push(Chunk::Bl16 { data: encode_bl16(&by_origin[&(x, y, z)]) });
+            Block { index: (bl16_count + i) as i32, x, y, z }
+        })
+        .collect()
+}
+
+/// The inverse of [`voxels_from_image`]: packs a 16×16×16 voxel grid into
+/// the 64×64 RGBA image layout a `BL16` chunk stores, and PNG-encodes it.
+fn encode_bl16(voxels: &Voxels) -> Vec<u8> {
+    let mut rgba = vec![0u8; 64 * 64 * 4];
+    for (x, plane) in voxels.iter().enumerate() {
+        for (y, column) in plane.iter().enumerate() {
+            for (z, voxel) in column.iter().enumerate() {
+                let px = (z % 4) * 16 + x;
+                let py = (z / 4) * 16 + y;
+                let i = (py * 64 + px) * 4;
+                rgba[i..i + 4].copy_from_slice(voxel);
+            }
+        }
+    }
+    png::encode(&png::Image { width: 64, height: 64, rgba })
+}
+
+/// A minimal `LAYR` dict: just enough for [`Chunk::as_layer`] to decode it
+/// back (a name and an identity `mat`).
+fn layer_dict(name: &str) -> Dict {
+    let mut map = IndexMap::new();
+    map.insert("name".to_string(), name.as_bytes().to_vec());
+    map.insert("mat".to_string(), encode_mat4(&IDENTITY_MAT4));
+    Dict::from(map)
+}
+
+/// A minimal `IMG` dict for files [`ModelBuilder::build`] assembles from
+/// scratch, satisfying [`Goxel::validate`]'s one-`IMG`-chunk requirement.
+/// Records this crate under the `"software"` key (the same key real goxel
+/// uses for free-text tool identification) rather than `"goxel"`/`"version"`
+/// — those are what [`Goxel::authoring_version`] reads as *goxel's own* app
+/// version, and a builder-assembled file wasn't authored by goxel at all.
+fn authoring_img_dict() -> Dict {
+    let mut map = IndexMap::new();
+    map.insert(
+        "software".to_string(),
+        format!("gox-rs {}", env!("CARGO_PKG_VERSION")).into_bytes(),
+    );
+    Dict::from(map)
+}
+
+/// The inverse of [`read_mat4`]: flattens a column-major 4×4 matrix into
+/// the 16 little-endian `f32`s a dict value stores.
+fn encode_mat4(mat: &[f32; 16]) -> Vec<u8> {
+    mat.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Chunk-header-level statistics about a `.gox` file, built by
+/// [`Goxel::stats`] without decoding any `BL16` PNG payload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub version: i32,
+    pub layer_count: usize,
+    pub camera_count: usize,
+    pub light_count: usize,
+    pub material_count: usize,
+    pub block_count: usize,
+    pub has_preview: bool,
+    /// The union of every block's 16×16×16 grid extent, in world
+    /// coordinates. This is an upper bound on the model's true bounding
+    /// box, since it doesn't know which cells in a block are actually
+    /// painted — call [`Goxel::model`]`.bounding_box()` for the exact one.
+    pub block_bounding_box: Option<BoundingBox>,
+    /// An upper bound on the number of occupied voxels: every block
+    /// contributes its full 16×16×16 = 4096 cells, whether or not they're
+    /// actually painted. Call [`Goxel::voxel_count`] for the exact count.
+    pub max_voxel_count: usize,
+}
+
+/// Picks a single layer for [`Goxel::export_layer`], by its position among
+/// `LAYR` chunks or by its decoded name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerSelector {
+    /// The `LAYR` chunk at this position, counting only `LAYR` chunks, in
+    /// file order, like [`Goxel::set_layer_visible`].
+    ByIndex(usize),
+    /// The first `LAYR` chunk whose decoded [`LayerView::name`] matches,
+    /// like [`Goxel::layer_by_name`].
+    ByName(String),
+}
+
+/// Tuning knobs for [`Goxel::model_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelOptions {
+    /// A voxel's alpha must be at least this to be treated as occupied.
+    /// Defaults to `1`, matching [`Goxel::model`]'s behavior of keeping any
+    /// non-fully-transparent voxel. Raising it filters out faint,
+    /// mostly-transparent voxels, e.g. anti-aliased edges left over from an
+    /// imported model.
+    pub alpha_threshold: u8,
+}
+
+impl Default for ModelOptions {
+    fn default() -> Self {
+        ModelOptions { alpha_threshold: 1 }
+    }
+}
+
+impl Goxel {
+    /// Starts an empty `.gox` file with no chunks, for building one from
+    /// scratch instead of parsing an existing file. Push chunks directly
+    /// (see [`Chunk`]) or build a model with [`ModelBuilder`] and splice
+    /// its chunks in, then [`Goxel::write`] the result. `version` should
+    /// be `1` or `2`, the only versions [`parse`] and friends understand
+    /// on the way back in.
+    pub fn new(version: i32) -> Goxel {
+        Goxel {
+            version,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// The file format version this `.gox` was written with.
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// The chunks that make up this `.gox` file, in on-disk order.
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+
+    /// Whether this `.gox` has no chunks at all: a valid header and version
+    /// followed by nothing. See [`parse_checked`] for a parse path that
+    /// treats this as an error rather than a valid, if unusual, file.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Each chunk's type discriminant, in on-disk order, without decoding
+    /// any dict or block list. Useful for quickly understanding what a
+    /// file contains or routing logic without matching on every variant.
+    pub fn chunk_kinds(&self) -> Vec<ChunkKind> {
+        self.chunks.iter().map(Chunk::kind).collect()
+    }
+
+    /// The first chunk of the given `kind`, in file order, or `None` if
+    /// there isn't one. Saves writing a `chunks().iter().find(...)` with a
+    /// manual `match` every time a caller wants "the IMG chunk" or similar.
+    pub fn first(&self, kind: ChunkKind) -> Option<&Chunk> {
+        self.chunks.iter().find(|chunk| chunk.kind() == kind)
+    }
+
+    /// Every chunk of the given `kind`, in file order; see [`Goxel::first`].
+    pub fn all(&self, kind: ChunkKind) -> impl Iterator<Item = &Chunk> {
+        self.chunks.iter().filter(move |chunk| chunk.kind() == kind)
+    }
+
+    /// Decodes every `CAMR` chunk's dict as a camera view, in file order.
+    /// Goxel files can hold several named views; see
+    /// [`Goxel::camera_by_name`] to fetch one by name.
+    pub fn cameras(&self) -> Vec<CameraView> {
+        self.chunks.iter().filter_map(Chunk::as_camera).collect()
+    }
+
+    /// Finds the first `CAMR` chunk whose decoded [`CameraView::name`]
+    /// matches `name`. If two cameras share a name (Goxel doesn't enforce
+    /// uniqueness), this returns whichever comes first in chunk order.
+    pub fn camera_by_name(&self, name: &str) -> Option<CameraView> {
+        self.cameras().into_iter().find(|view| view.name.as_deref() == Some(name))
+    }
+
+    /// Decodes every `MATE` chunk's dict as a material view, in file order.
+    pub fn materials(&self) -> Vec<MaterialView> {
+        self.chunks.iter().filter_map(Chunk::as_material).collect()
+    }
+
+    /// The authored swatch palette from this file's `PALE` chunk, if it has
+    /// one. This is distinct from a palette derived from the model's
+    /// voxels (see [`Model::palette_with_counts`]): it's whatever fixed set
+    /// of colors the user saved, independent of which are actually used.
+    /// `None` if the file has no `PALE` chunk.
+    pub fn file_palette(&self) -> Option<Vec<[u8; 4]>> {
+        self.chunks.iter().find_map(|chunk| match chunk {
+            Chunk::Pale { colors, .. } => Some(colors.clone()),
+            _ => None,
+        })
+    }
+
+    /// The first `IMG` chunk's string-decodable metadata; see
+    /// [`Chunk::image_metadata`]. Empty if the file has no `IMG` chunk.
+    pub fn image_metadata(&self) -> HashMap<String, String> {
+        self.first(ChunkKind::Img)
+            .map(Chunk::image_metadata)
+            .unwrap_or_default()
+    }
+
+    /// The authoring app version recorded in the `IMG` dict's `"goxel"`
+    /// key (or, on older files, `"version"`) — distinct from the binary
+    /// format's integer [`Goxel::version`]. Useful for compatibility
+    /// diagnostics like "this file was made with an old goxel". `None` if
+    /// neither key is present.
+    pub fn authoring_version(&self) -> Option<String> {
+        let metadata = self.image_metadata();
+        metadata.get("goxel").or_else(|| metadata.get("version")).cloned()
+    }
+
+    /// Reconstructs each `LAYR` chunk as a sparse, world-space voxel map
+    /// keyed by integer coordinate, skipping empty (alpha `0`) voxels.
+    pub fn layers(&self) -> Result<Vec<LayerVoxels>, VoxelError> {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some(blocks),
+                _ => None,
+            })
+            .map(|blocks| decode_layer(blocks, self, 1))
+            .collect()
+    }
+
+    /// Lists, per `LAYR` chunk in chunk order, the `BL16` indices that
+    /// layer's [`Block`]s reference. Two layers sharing a block report that
+    /// block's index in both of their lists; a `BL16` index that never
+    /// appears in any of these lists is unused by any layer. Useful for
+    /// tooling that wants to analyze block sharing or spot dead `BL16`
+    /// chunks without decoding any actual voxel data.
+    pub fn layer_block_indices(&self) -> Vec<Vec<i32>> {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => {
+                    Some(blocks.iter().map(|block| block.index).collect())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Finds the first `LAYR` chunk whose decoded [`LayerView::name`]
+    /// matches `name`, paired with its raw blocks. If two layers share a
+    /// name (Goxel doesn't enforce uniqueness), this returns whichever
+    /// comes first in chunk order. Convenient for pipelines that operate
+    /// on a specific conventionally-named layer, e.g. `"collision"`.
+    pub fn layer_by_name(&self, name: &str) -> Option<(LayerView, &[Block])> {
+        self.chunks.iter().find_map(|chunk| {
+            let Chunk::Layr { blocks, .. } = chunk else {
+                return None;
+            };
+            let view = chunk.as_layer()?;
+            (view.name == name).then_some((view, blocks.as_slice()))
+        })
+    }
+
+    /// Pairs each `LAYR` chunk's decoded [`LayerView`] with the axis-aligned
+    /// bounding box of that layer's own voxels, for UI like a layer panel
+    /// that wants per-layer extents without building the merged [`Model`].
+    /// A layer with no occupied voxels reports `None` for its bounds. A
+    /// `LAYR` chunk whose dict doesn't decode as a [`LayerView`] (missing
+    /// `name` or `mat`) is skipped.
+    pub fn layer_bounds(&self) -> Result<Vec<(LayerView, Option<BoundingBox>)>, VoxelError> {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some((chunk.as_layer()?, blocks)),
+                _ => None,
+            })
+            .map(|(view, blocks)| {
+                let mut model = Model::new();
+                model.extend(decode_layer(blocks, self, 1)?);
+                Ok((view, model.bounding_box()))
+            })
+            .collect()
+    }
+
+    /// Reconstructs the full model by merging every layer's voxels into a
+    /// single sparse, world-space map. Later layers overwrite earlier ones
+    /// at the same coordinate, mirroring Goxel's top-down layer stacking;
+    /// when two layers claim the same voxel, [`Model::voxel_material`]
+    /// reports the winning (later) layer's material, same as the color.
+    /// Equivalent to [`Goxel::model_with_options`] with the default
+    /// [`ModelOptions`].
+    pub fn model(&self) -> Result<Model, VoxelError> {
+        self.model_with_options(ModelOptions::default())
+    }
+
+    /// Like [`Goxel::model`], but applies `options` while assembling
+    /// voxels. Currently that's just [`ModelOptions::alpha_threshold`],
+    /// which lets a caller discard faint, mostly-transparent voxels (e.g.
+    /// anti-aliased edges from an imported model) that [`Goxel::model`]'s
+    /// default of keeping anything non-fully-transparent would otherwise
+    /// include.
+    pub fn model_with_options(&self, options: ModelOptions) -> Result<Model, VoxelError> {
+        let mut model = Model::new();
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, dict } = chunk else {
+                continue;
+            };
+            let layer = decode_layer(blocks, self, options.alpha_threshold)?;
+            let material = dict.get("material").and_then(|v| read_i32(v)).filter(|&m| m >= 0);
+            for &pos in layer.keys() {
+                match material {
+                    Some(m) => model.materials.insert(pos, m as usize),
+                    None => model.materials.remove(&pos),
+                };
+            }
+            model.voxels.extend(layer);
+        }
+        Ok(model)
+    }
+
+    /// Like [`Goxel::model`], but calls `f(blocks_done, total_blocks)` after
+    /// each `LAYR` block is decoded, so a GUI loading a large file can drive
+    /// a progress bar. `total_blocks` counts every block reference across
+    /// every layer (a block placed by two layers counts twice, once per
+    /// placement) and is computed once up front, before any decoding
+    /// starts. The extra bookkeeping is per block, not per voxel, so this
+    /// costs no more than [`Goxel::model`] beyond the callback itself.
+    pub fn model_with_progress(&self, mut f: impl FnMut(usize, usize)) -> Result<Model, VoxelError> {
+        let total_blocks: usize = self
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some(blocks.len()),
+                _ => None,
+            })
+            .sum();
+
+        let mut model = Model::new();
+        let mut blocks_done = 0;
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, dict } = chunk else {
+                continue;
+            };
+            let material = dict.get("material").and_then(|v| read_i32(v)).filter(|&m| m >= 0);
+            let mut layer = LayerVoxels::new();
+            for block in blocks {
+                let grid = block.voxels(self)?;
+                insert_block(&mut layer, block, grid, 1)?;
+                blocks_done += 1;
+                f(blocks_done, total_blocks);
+            }
+            for &pos in layer.keys() {
+                match material {
+                    Some(m) => model.materials.insert(pos, m as usize),
+                    None => model.materials.remove(&pos),
+                };
+            }
+            model.voxels.extend(layer);
+        }
+        Ok(model)
+    }
+
+    /// Flips a `LAYR` chunk's `visible` flag, the setting [`Goxel::flatten`]
+    /// and [`Goxel::frames`] (with `honor_visibility` set) check before
+    /// including a layer's voxels. `index` counts only `LAYR` chunks, in
+    /// file order, like [`DecodedBlock::index`] counts only `BL16` chunks.
+    /// Does nothing if `index` is out of range.
+    pub fn set_layer_visible(&mut self, index: usize, visible: bool) {
+        let Some(dict) = self
+            .chunks
+            .iter_mut()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { dict, .. } => Some(dict),
+                _ => None,
+            })
+            .nth(index)
+        else {
+            return;
+        };
+        dict.insert("visible".to_string(), (visible as i32).to_le_bytes().to_vec());
+    }
+
+    /// Renames a `LAYR` chunk by overwriting its dict's `name` entry.
+    /// `index` counts only `LAYR` chunks, in file order, like
+    /// [`Goxel::set_layer_visible`]. [`Goxel::write`] re-encodes the dict
+    /// from scratch, so the new value's length is handled automatically —
+    /// there's no fixed-width field to patch around. Does nothing if
+    /// `index` is out of range.
+    pub fn set_layer_name(&mut self, index: usize, name: &str) {
+        let Some(dict) = self
+            .chunks
+            .iter_mut()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { dict, .. } => Some(dict),
+                _ => None,
+            })
+            .nth(index)
+        else {
+            return;
+        };
+        dict.insert("name".to_string(), name.as_bytes().to_vec());
+    }
+
+    /// Deletes the `index`th `LAYR` chunk (counting only `LAYR` chunks, in
+    /// file order, like [`Goxel::set_layer_visible`]), along with any
+    /// `BL16` chunk that only the removed layer's blocks referenced. Every
+    /// remaining block's `index` is remapped to match the surviving `BL16`
+    /// chunks' new positions, so the file stays internally consistent for
+    /// [`Goxel::write`]. Does nothing if `index` is out of range.
+    pub fn remove_layer(&mut self, index: usize) {
+        let Some(layer_position) = self
+            .chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| matches!(chunk, Chunk::Layr { .. }))
+            .nth(index)
+            .map(|(position, _)| position)
+        else {
+            return;
+        };
+
+        // `Block::index` counts only `BL16` chunks, in file order, so the
+        // `k`th entry here is both that chunk's absolute position and the
+        // `Block::index` value that refers to it.
+        let bl16_positions: Vec<usize> = self
+            .chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| matches!(chunk, Chunk::Bl16 { .. }))
+            .map(|(position, _)| position)
+            .collect();
+
+        let Chunk::Layr { blocks: removed_blocks, .. } = &self.chunks[layer_position] else {
+            unreachable!("layer_position always points at a Layr chunk");
+        };
+        let removed_indices: HashSet<i32> = removed_blocks.iter().map(|block| block.index).collect();
+
+        let still_referenced: HashSet<i32> = self
+            .chunks
+            .iter()
+            .enumerate()
+            .filter(|&(position, chunk)| position != layer_position && matches!(chunk, Chunk::Layr { .. }))
+            .flat_map(|(_, chunk)| match chunk {
+                Chunk::Layr { blocks, .. } => blocks.iter().map(|block| block.index),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let orphaned_positions: HashSet<usize> = removed_indices
+            .iter()
+            .filter(|index| !still_referenced.contains(index))
+            .map(|&index| bl16_positions[index as usize])
+            .collect();
+
+        let mut new_index_by_old = HashMap::new();
+        let mut next_index = 0i32;
+        for (old_index, position) in bl16_positions.iter().enumerate() {
+            if !orphaned_positions.contains(position) {
+                new_index_by_old.insert(old_index as i32, next_index);
+                next_index += 1;
+            }
+        }
+
+        let mut position = 0usize;
+        self.chunks.retain(|_| {
+            let keep = position != layer_position && !orphaned_positions.contains(&position);
+            position += 1;
+            keep
+        });
+
+        for chunk in &mut self.chunks {
+            if let Chunk::Layr { blocks, .. } = chunk {
+                for block in blocks {
+                    if let Some(&new_index) = new_index_by_old.get(&block.index) {
+                        block.index = new_index;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Indices (counting only `BL16` chunks, in file order, same as
+    /// [`Block::index`]) of `BL16` chunks that no `LAYR` block references.
+    /// These accumulate after editing (e.g. [`Goxel::remove_layer`] only
+    /// cleans up blocks orphaned by the layer it removes, not ones that
+    /// were already dangling) or in badly-authored files, and just waste
+    /// space until pruned with [`Goxel::prune_orphaned_blocks`].
+    pub fn orphaned_blocks(&self) -> Vec<usize> {
+        let referenced: HashSet<i32> = self
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some(blocks),
+                _ => None,
+            })
+            .flatten()
+            .map(|block| block.index)
+            .collect();
+
+        self.chunks
+            .iter()
+            .filter(|chunk| matches!(chunk, Chunk::Bl16 { .. }))
+            .enumerate()
+            .filter(|(index, _)| !referenced.contains(&(*index as i32)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Removes every orphaned `BL16` chunk (see [`Goxel::orphaned_blocks`])
+    /// and remaps every remaining block's `index` to match the surviving
+    /// `BL16` chunks' new positions, so the file stays internally
+    /// consistent for [`Goxel::write`]. Does nothing if nothing is
+    /// orphaned.
+    pub fn prune_orphaned_blocks(&mut self) {
+        let orphaned: HashSet<usize> = self.orphaned_blocks().into_iter().collect();
+        if orphaned.is_empty() {
+            return;
+        }
+
+        let mut new_index_by_old: HashMap<i32, i32> = HashMap::new();
+        let mut next_index = 0i32;
+        let mut bl16_seen = 0usize;
+        for chunk in &self.chunks {
+            if matches!(chunk, Chunk::Bl16 { .. }) {
+                if !orphaned.contains(&bl16_seen) {
+                    new_index_by_old.insert(bl16_seen as i32, next_index);
+                    next_index += 1;
+                }
+                bl16_seen += 1;
+            }
+        }
+
+        let mut bl16_position = 0usize;
+        self.chunks.retain(|chunk| {
+            if !matches!(chunk, Chunk::Bl16 { .. }) {
+                return true;
+            }
+            let keep = !orphaned.contains(&bl16_position);
+            bl16_position += 1;
+            keep
+        });
+
+        for chunk in &mut self.chunks {
+            if let Chunk::Layr { blocks, .. } = chunk {
+                for block in blocks {
+                    if let Some(&new_index) = new_index_by_old.get(&block.index) {
+                        block.index = new_index;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like [`Goxel::model`], but skips layers marked invisible via their
+    /// `visible` dict entry, mirroring what Goxel itself renders, and never
+    /// decodes their blocks. Layers are composited in chunk order; at a
+    /// shared coordinate, the later (higher) layer's [`BlendMode`] decides
+    /// how its color combines with what's already there (`Normal` simply
+    /// overwrites it, the default when a layer has no `mode` entry).
+    pub fn flatten(&self) -> Result<Model, VoxelError> {
+        let mut model = Model::new();
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, dict } = chunk else {
+                continue;
+            };
+            let visible = dict.get("visible").and_then(|v| read_bool(v)).unwrap_or(true);
+            if !visible {
+                continue;
+            }
+            let mode =
+                BlendMode::from_i32(dict.get_layr(LayrKey::Mode).and_then(read_i32).unwrap_or(0));
+            for (pos, rgba) in decode_layer(blocks, self, 1)? {
+                let composited = match model.voxels.get(&pos) {
+                    Some(&below) => mode.composite(below, rgba),
+                    None => rgba,
+                };
+                model.voxels.insert(pos, composited);
+            }
+        }
+        Ok(model)
+    }
+
+    /// Reconstructs just one layer's voxels, picked by [`LayerSelector`],
+    /// instead of merging every layer the way [`Goxel::model`] does. Pairs
+    /// with the OBJ/.vox exporters so a caller can export a single layer
+    /// (e.g. `"collision"`, or one animation frame) on its own. Fails with
+    /// [`GoxError::UnknownLayer`] if `selector` doesn't match any `LAYR`
+    /// chunk.
+    pub fn export_layer(&self, selector: LayerSelector) -> Result<Model, GoxError> {
+        let blocks = match &selector {
+            LayerSelector::ByIndex(index) => self
+                .chunks
+                .iter()
+                .filter_map(|chunk| match chunk {
+                    Chunk::Layr { blocks, .. } => Some(blocks.as_slice()),
+                    _ => None,
+                })
+                .nth(*index),
+            LayerSelector::ByName(name) => self.layer_by_name(name).map(|(_, blocks)| blocks),
+        };
+        let blocks = blocks.ok_or(GoxError::UnknownLayer { selector })?;
+        let mut model = Model::new();
+        model.extend(decode_layer(blocks, self, 1)?);
+        Ok(model)
+    }
+
+    /// Reconstructs each `LAYR` chunk as its own standalone [`Model`], in
+    /// chunk order, instead of compositing them into one the way
+    /// [`Goxel::model`] does. Useful for treating the layers of a file
+    /// authored one layer per frame as an animation timeline — pair with
+    /// the `.vox`/OBJ exporters to export the sequence.
+    ///
+    /// If `honor_visibility` is set, a layer hidden via its `visible` dict
+    /// entry is skipped, same as [`Goxel::flatten`]; if not, every layer
+    /// becomes a frame regardless of visibility. Which is right depends on
+    /// the file: a hidden layer might be a work-in-progress the author
+    /// doesn't want included, or it might be an intentionally-hidden frame
+    /// in the timeline, so this doesn't guess for you.
+    pub fn frames(&self, honor_visibility: bool) -> Result<Vec<Model>, VoxelError> {
+        let mut frames = Vec::new();
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, dict } = chunk else {
+                continue;
+            };
+            if honor_visibility {
+                let visible = dict.get("visible").and_then(|v| read_bool(v)).unwrap_or(true);
+                if !visible {
+                    continue;
+                }
+            }
+
+            let layer = decode_layer(blocks, self, 1)?;
+            let material = dict.get("material").and_then(|v| read_i32(v)).filter(|&m| m >= 0);
+            let mut frame = Model::new();
+            if let Some(m) = material {
+                for &pos in layer.keys() {
+                    frame.materials.insert(pos, m as usize);
+                }
+            }
+            frame.voxels = layer;
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+
+    /// Checks that every `LAYR` chunk's blocks reference an existing `BL16`
+    /// chunk, without decoding any block's PNG payload. `layer` counts only
+    /// `LAYR` chunks, in file order, matching [`Goxel::layers`]. An
+    /// out-of-range index would otherwise surface mid-assembly as
+    /// [`VoxelError::MissingBl16`]; this catches a corrupt file up front.
+    ///
+    /// Also requires exactly one `IMG` chunk, since that's where a real
+    /// `.gox` file's application metadata lives. [`ModelBuilder::build`]
+    /// emits a minimal one for exactly this reason, so a freshly authored
+    /// file built that way still passes; a [`Goxel::new`] file with chunks
+    /// pushed by hand is expected to add its own `IMG` chunk before calling
+    /// `validate()`.
+    pub fn validate(&self) -> Result<(), GoxError> {
+        let image_count = self.all(ChunkKind::Img).count();
+        match image_count {
+            1 => {}
+            0 => return Err(GoxError::MissingImage),
+            count => return Err(GoxError::MultipleImages { count }),
+        }
+
+        let bl16_count = self
+            .chunks
+            .iter()
+            .filter(|chunk| matches!(chunk, Chunk::Bl16 { .. }))
+            .count();
+
+        let mut layer = 0;
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, .. } = chunk else {
+                continue;
+            };
+            for block in blocks {
+                if block.index < 0 || block.index as usize >= bl16_count {
+                    return Err(GoxError::DanglingBlock { layer, index: block.index });
+                }
+            }
+            layer += 1;
+        }
+        Ok(())
+    }
+
+    /// Counts non-empty (non-zero-alpha) voxels across every layer's
+    /// blocks without building a [`Model`], so large files can be sized up
+    /// without the memory cost of a full `HashMap`. Voxels placed by more
+    /// than one layer at the same coordinate are counted once per layer, so
+    /// this is a placement count, not a count of unique occupied cells —
+    /// use [`Goxel::model`]`.len()` if you need the deduplicated total.
+    pub fn voxel_count(&self) -> Result<usize, VoxelError> {
+        let mut count = 0;
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, .. } = chunk else {
+                continue;
+            };
+            for block in blocks {
+                let grid = block.voxels(self)?;
+                for plane in &grid {
+                    for column in plane {
+                        count += column.iter().filter(|rgba| rgba[3] != 0).count();
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Cheap, chunk-header-level statistics about this file. Unlike
+    /// [`Goxel::voxel_count`] and [`Goxel::model`], this never decodes a
+    /// `BL16` chunk's PNG payload, so it's fast enough to catalog
+    /// thousands of files.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            version: self.version,
+            layer_count: 0,
+            camera_count: 0,
+            light_count: 0,
+            material_count: 0,
+            block_count: 0,
+            has_preview: false,
+            block_bounding_box: None,
+            max_voxel_count: 0,
+        };
+
+        for chunk in &self.chunks {
+            match chunk {
+                Chunk::Layr { blocks, .. } => {
+                    stats.layer_count += 1;
+                    stats.block_count += blocks.len();
+                    stats.max_voxel_count += blocks.len() * 16 * 16 * 16;
+                    for block in blocks {
+                        // Saturate rather than overflow: `stats()` is meant
+                        // to run over untrusted files without ever panicking
+                        // (debug) or quietly wrapping into a bogus box
+                        // (release), the same hazard `insert_block` guards
+                        // against with `checked_add` when actually decoding.
+                        let min = (block.x, block.y, block.z);
+                        let max = (
+                            block.x.saturating_add(15),
+                            block.y.saturating_add(15),
+                            block.z.saturating_add(15),
+                        );
+                        stats.block_bounding_box = Some(match stats.block_bounding_box {
+                            None => BoundingBox { min, max },
+                            Some(bbox) => BoundingBox {
+                                min: (
+                                    bbox.min.0.min(min.0),
+                                    bbox.min.1.min(min.1),
+                                    bbox.min.2.min(min.2),
+                                ),
+                                max: (
+                                    bbox.max.0.max(max.0),
+                                    bbox.max.1.max(max.1),
+                                    bbox.max.2.max(max.2),
+                                ),
+                            },
+                        });
+                    }
+                }
+                Chunk::Camr { .. } => stats.camera_count += 1,
+                Chunk::Ligh { .. } => stats.light_count += 1,
+                Chunk::Mate { .. } => stats.material_count += 1,
+                Chunk::Prev { .. } => stats.has_preview = true,
+                _ => {}
+            }
+        }
+
+        stats
+    }
+
+    /// Like [`Goxel::model`], but applies each layer's `mat` transform (its
+    /// dict's `mat` entry) to its voxel positions before merging, rounding
+    /// transformed coordinates to the nearest integer grid cell. Layers
+    /// with no `mat` entry, or an identity one, skip the float round-trip
+    /// and are placed at their raw block offsets, same as `model`.
+    pub fn model_with_transforms(&self) -> Result<Model, VoxelError> {
+        let mut model = Model::new();
+        for chunk in &self.chunks {
+            let Chunk::Layr { blocks, dict } = chunk else {
+                continue;
+            };
+            let layer = decode_layer(blocks, self, 1)?;
+            let mat = dict.get("mat").and_then(|v| read_mat4(v)).unwrap_or(IDENTITY_MAT4);
+            if mat == IDENTITY_MAT4 {
+                model.extend(layer);
+                continue;
+            }
+            for ((x, y, z), rgba) in layer {
+                let (tx, ty, tz) = apply_mat4(&mat, x as f32, y as f32, z as f32);
+                model.extend([((tx.round() as i32, ty.round() as i32, tz.round() as i32), rgba)]);
+            }
+        }
+        Ok(model)
+    }
+
+    /// Decodes the embedded `PREV` chunk's PNG payload into an RGBA
+    /// thumbnail via the `image` crate. Returns `None` if there's no
+    /// `PREV` chunk; a malformed payload surfaces as `Some(Err(..))`.
+    pub fn preview(&self) -> Option<Result<image::RgbaImage, GoxError>> {
+        let data = self.chunks.iter().find_map(|chunk| match chunk {
+            Chunk::Prev { data } => Some(data),
+            _ => None,
+        })?;
+        Some(
+            image::load_from_memory(data)
+                .map(|img| img.to_rgba8())
+                .map_err(GoxError::from),
+        )
+    }
+
+    /// PNG-encodes `image` and stores it as the file's `PREV` thumbnail,
+    /// replacing the existing one if there is one. Otherwise a new `PREV`
+    /// chunk is inserted right after `IMG` (or at the very front, if
+    /// there's no `IMG` chunk either), matching where goxel itself writes
+    /// it. Lets an asset pipeline refresh a stale thumbnail before calling
+    /// [`Goxel::write`] again.
+    pub fn set_preview(&mut self, image: &image::RgbaImage) {
+        let mut data = Vec::new();
+        image
+            .write_to(&mut io::Cursor::new(&mut data), image::ImageFormat::Png)
+            .expect("encoding to an in-memory buffer cannot fail");
+
+        if let Some(chunk) = self.chunks.iter_mut().find(|chunk| matches!(chunk, Chunk::Prev { .. })) {
+            *chunk = Chunk::Prev { data };
+            return;
+        }
+
+        let position = self
+            .chunks
+            .iter()
+            .position(|chunk| matches!(chunk, Chunk::Img { .. }))
+            .map_or(0, |index| index + 1);
+        self.chunks.insert(position, Chunk::Prev { data });
+    }
+
+    /// Returns a thumbnail that fits within `max_dim` pixels on its longer
+    /// side, preserving aspect ratio: the embedded `PREV` chunk if there is
+    /// one, decoded and scaled down like [`Goxel::preview`]; otherwise a
+    /// scaled-down [`Model::front_view`] of the decoded voxels, so every
+    /// file produces a thumbnail even without a stored preview image.
+    pub fn thumbnail(&self, max_dim: u32) -> Option<Result<image::RgbaImage, GoxError>> {
+        let image = match self.preview() {
+            Some(Ok(image)) => image,
+            Some(Err(err)) => return Some(Err(err)),
+            None => match self.model() {
+                Ok(model) => model.front_view(),
+                Err(err) => return Some(Err(GoxError::from(err))),
+            },
+        };
+        if image.width() == 0 || image.height() == 0 {
+            return Some(Ok(image));
+        }
+        Some(Ok(image::imageops::thumbnail(&image, max_dim, max_dim)))
+    }
+
+    /// Decodes the scene's `LIGH` chunk, if any, so a renderer can
+    /// reproduce the original lighting instead of guessing. Returns `None`
+    /// if there's no `LIGH` chunk, or it's malformed.
+    pub fn light(&self) -> Option<LightView> {
+        self.chunks.iter().find_map(Chunk::as_light)
+    }
+
+    /// Ties every typed chunk view together into one [`Scene`], so a caller
+    /// doesn't have to match on [`Goxel::chunks`] variants themselves. Each
+    /// layer is paired with its own decoded [`Model`] (same as
+    /// [`Goxel::layer_bounds`], a `LAYR` chunk whose dict doesn't decode as
+    /// a [`LayerView`] is skipped rather than failing the whole scene).
+    /// Fails only if a layer's blocks themselves don't decode — see
+    /// [`VoxelError`].
+    pub fn scene(&self) -> Result<Scene, VoxelError> {
+        let layers = self
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Layr { blocks, .. } => Some((chunk.as_layer()?, blocks)),
+                _ => None,
+            })
+            .map(|(view, blocks)| {
+                let mut model = Model::new();
+                model.extend(decode_layer(blocks, self, 1)?);
+                Ok((view, model))
+            })
+            .collect::<Result<Vec<_>, VoxelError>>()?;
+
+        Ok(Scene {
+            image: self.chunks.iter().find_map(Chunk::as_image),
+            cameras: self.cameras(),
+            light: self.light(),
+            materials: self.materials(),
+            layers,
+        })
+    }
+}
+
+/// Decodes one `LAYR` chunk's blocks into a sparse, world-space voxel map,
+/// skipping voxels whose alpha falls below `alpha_threshold`.
+/// Writes one decoded block's voxel grid into `voxels` at its world-space
+/// offset, dropping any voxel whose alpha falls below `alpha_threshold`.
+/// Shared by [`decode_layer`] and [`Goxel::model_with_progress`], which
+/// decode blocks in different orders (all at once vs. one at a time with a
+/// callback in between) but otherwise place them identically.
+fn insert_block(
+    voxels: &mut LayerVoxels,
+    block: &Block,
+    grid: Voxels,
+    alpha_threshold: u8,
+) -> Result<(), VoxelError> {
+    let overflow = || VoxelError::CoordinateOverflow {
+        block_x: block.x,
+        block_y: block.y,
+        block_z: block.z,
+    };
+    for (x, plane) in grid.iter().enumerate() {
+        for (y, column) in plane.iter().enumerate() {
+            for (z, &rgba) in column.iter().enumerate() {
+                if rgba[3] < alpha_threshold {
+                    continue;
+                }
+                let coord = (
+                    block.x.checked_add(x as i32).ok_or_else(overflow)?,
+                    block.y.checked_add(y as i32).ok_or_else(overflow)?,
+                    block.z.checked_add(z as i32).ok_or_else(overflow)?,
+                );
+                voxels.insert(coord, rgba);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn decode_layer(blocks: &[Block], goxel: &Goxel, alpha_threshold: u8) -> Result<LayerVoxels, VoxelError> {
+    let mut voxels = HashMap::new();
+    for block in blocks {
+        let grid = block.voxels(goxel)?;
+        insert_block(&mut voxels, block, grid, alpha_threshold)?;
+    }
+    Ok(voxels)
+}
+
+/// Reads a little-endian `u32` length prefix, then exactly that many bytes.
+/// Wrapped in `complete` because our input is always a fully buffered
+/// slice, never a stream that might still grow — without it, a length
+/// prefix lying about claiming more bytes than remain surfaces as a
+/// confusing `Incomplete` instead of a clean parse error.
+fn length_prefixed(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    complete(length_data(le_u32))(input)
+}
+
+/// Parses one dict entry. When `strict_utf8` is set, a key that isn't valid
+/// UTF-8 fails hard with [`nom::Err::Failure`] (rather than backtracking,
+/// since the surrounding chunk tag already matched) so [`ChunkIter`] can
+/// report it as [`GoxError::InvalidKeyUtf8`] instead of silently replacing
+/// it with `U+FFFD` the way the lenient default does.
+fn entry(input: &[u8], strict_utf8: bool) -> IResult<&[u8], (String, Vec<u8>)> {
+    let (rest, key) = complete(length_data(verify(le_u32, |&n| n != 0)))(input)?;
+    if strict_utf8 && std::str::from_utf8(key).is_err() {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+    let (rest, value) = length_prefixed(rest)?;
+    Ok((rest, (String::from_utf8_lossy(key).to_string(), value.to_vec())))
+}
+
+/// Matches the zero-length key that terminates a dict's entry list, without
+/// consuming anything on failure so a caller can fall back to [`entry`].
+fn terminator(input: &[u8]) -> IResult<&[u8], ()> {
+    map(complete(verify(le_u32, |&n| n == 0)), |_| ())(input)
+}
+
+/// A zero-length key terminates the entry list (see [`entry`] and
+/// [`terminator`]), so an empty dict is just that terminator with no entries
+/// before it — goxel does write chunks with no dict entries at all. The dict
+/// body is also bounded by the chunk's own declared length (see callers,
+/// which run this through `map_parser`), and in practice some encoders omit
+/// the terminator when the last entry exactly fills that length, so running
+/// out of bytes ends the dict too. We can't express any of that with
+/// `fold_many1`/`fold_many0` directly: `fold_many0` would also treat a
+/// genuinely malformed entry (e.g. a truncated value, with bytes left over
+/// that just don't form a valid entry) as "no more entries" and silently
+/// return whatever was decoded so far instead of failing, so both stopping
+/// conditions are checked explicitly on each iteration.
+fn dict(
+    input: &[u8],
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Dict> {
+    let mut dict = Dict::new();
+    let mut rest = input;
+    loop {
+        if rest.is_empty() {
+            return Ok((rest, dict));
+        }
+        if let Ok((after_terminator, ())) = terminator(rest) {
+            return Ok((after_terminator, dict));
+        }
+        let entry_start = rest;
+        let (after_entry, (key, value)) = entry(rest, strict_utf8)?;
+        if dict.0.contains_key(&key) {
+            match duplicate_key_policy {
+                DuplicateKeyPolicy::KeepLast => {
+                    dict.insert(key, value);
+                }
+                DuplicateKeyPolicy::KeepFirst => {}
+                DuplicateKeyPolicy::Error => {
+                    return Err(nom::Err::Failure(nom::error::Error::new(
+                        entry_start,
+                        DUPLICATE_KEY_MARKER,
+                    )));
+                }
+            }
+        } else {
+            dict.insert(key, value);
+        }
+        rest = after_entry;
+    }
+}
+
+/// Computes the reflected CRC-32/ISO-HDLC checksum (polynomial `0xEDB88320`,
+/// initial value `0xFFFFFFFF`, final XOR `0xFFFFFFFF`) that Goxel stores as
+/// each chunk's trailer. Equivalent to what `crc32fast` produces. Public
+/// alias: [`crc32_gox`].
+fn crc32(bytes: &[u8]) -> u32 {
+    const POLY: u32 = 0xEDB88320;
+    let mut crc = 0xFFFFFFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLY & mask);
+        }
+    }
+    crc ^ 0xFFFFFFFF
+}
+
+/// Computes the exact CRC-32 variant (polynomial, init, reflection, xorout)
+/// that goxel's `gox.c` uses for each chunk's trailing checksum, so callers
+/// can verify (or author) a chunk's CRC independently of this crate's own
+/// parse/write path. See [`crc32`] for the parameters; this is just its
+/// public name.
+pub fn crc32_gox(data: &[u8]) -> u32 {
+    crc32(data)
+}
+
+fn chunk_common<'a, T, F>(
+    name: &'a str,
+    verify_crc: bool,
+    parser: F,
+) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], T>
+where
+    F: FnMut(&'a [u8]) -> IResult<&'a [u8], T> + 'a,
+{
+    map(
+        verify(
+            tuple((consumed(preceded(tag(name), parser)), le_u32)),
+            move |((body, _out), crc): &((&[u8], T), u32)| !verify_crc || crc32(body) == *crc,
+        ),
+        |((_, out), _crc)| out,
+    )
+}
+
+fn img(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "IMG ",
+        verify_crc,
+        map(
+            map_parser(length_prefixed, move |i| dict(i, strict_utf8, duplicate_key_policy)),
+            |dict| Chunk::Img { dict },
+        ),
+    )(input)
+}
+
+fn prev(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "PREV",
+        verify_crc,
+        map(length_prefixed, |data: &[u8]| Chunk::Prev {
+            data: data.to_vec(),
+        }),
+    )(input)
+}
+
+fn bl16(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "BL16",
+        verify_crc,
+        map(length_prefixed, |data: &[u8]| Chunk::Bl16 {
+            data: data.to_vec(),
+        }),
+    )(input)
+}
+
+fn block(input: &[u8]) -> IResult<&[u8], Block> {
+    map(
+        tuple((le_i32, le_i32, le_i32, le_i32, le_i32)),
+        |(index, x, y, z, _)| Block { index, x, y, z },
+    )(input)
+}
+
+fn layr(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "LAYR",
+        verify_crc,
+        map(
+            map_parser(
+                length_prefixed,
+                tuple((
+                    length_count(le_u32, block),
+                    move |i| dict(i, strict_utf8, duplicate_key_policy),
+                )),
+            ),
+            |(blocks, dict)| Chunk::Layr { blocks, dict },
+        ),
+    )(input)
+}
+
+fn camr(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "CAMR",
+        verify_crc,
+        map(
+            map_parser(length_prefixed, move |i| dict(i, strict_utf8, duplicate_key_policy)),
+            |dict| Chunk::Camr { dict },
+        ),
+    )(input)
+}
+
+fn ligh(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "LIGH",
+        verify_crc,
+        map(
+            map_parser(length_prefixed, move |i| dict(i, strict_utf8, duplicate_key_policy)),
+            |dict| Chunk::Ligh { dict },
+        ),
+    )(input)
+}
+
+fn mate(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "MATE",
+        verify_crc,
+        map(
+            map_parser(length_prefixed, move |i| dict(i, strict_utf8, duplicate_key_policy)),
+            |dict| Chunk::Mate { dict },
+        ),
+    )(input)
+}
+
+/// Tags this parser knows how to interpret structurally. Anything else
+/// falls through to [`unknown`], which preserves the chunk verbatim.
+const KNOWN_TAGS: [&[u8; 4]; 8] =
+    [b"IMG ", b"PREV", b"BL16", b"LAYR", b"CAMR", b"LIGH", b"MATE", b"PALE"];
+
+/// Reads one raw `[r, g, b, a]` palette entry.
+fn color4(input: &[u8]) -> IResult<&[u8], [u8; 4]> {
+    map(take(4usize), |b: &[u8]| [b[0], b[1], b[2], b[3]])(input)
+}
+
+fn pale(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    chunk_common(
+        "PALE",
+        verify_crc,
+        map(
+            map_parser(
+                length_prefixed,
+                tuple((
+                    length_count(le_u32, color4),
+                    move |i| dict(i, strict_utf8, duplicate_key_policy),
+                )),
+            ),
+            |(colors, dict)| Chunk::Pale { colors, dict },
+        ),
+    )(input)
+}
+
+fn unknown(input: &[u8], verify_crc: bool) -> IResult<&[u8], Chunk> {
+    let (rest, (body, (tag, data))) = consumed(tuple((
+        verify(take(4usize), |t: &[u8]| {
+            let tag: [u8; 4] = t.try_into().unwrap();
+            !KNOWN_TAGS.contains(&&tag)
+        }),
+        length_prefixed,
+    )))(input)?;
+    let (rest, crc) = le_u32(rest)?;
+
+    if verify_crc && crc32(body) != crc {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+
+    Ok((
+        rest,
+        Chunk::Unknown {
+            tag: tag.try_into().unwrap(),
+            data: data.to_vec(),
+        },
+    ))
+}
+
+fn chunk(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Chunk> {
+    alt((
+        |i| img(i, verify_crc, strict_utf8, duplicate_key_policy),
+        |i| prev(i, verify_crc),
+        |i| bl16(i, verify_crc),
+        |i| layr(i, verify_crc, strict_utf8, duplicate_key_policy),
+        |i| camr(i, verify_crc, strict_utf8, duplicate_key_policy),
+        |i| ligh(i, verify_crc, strict_utf8, duplicate_key_policy),
+        |i| mate(i, verify_crc, strict_utf8, duplicate_key_policy),
+        |i| pale(i, verify_crc, strict_utf8, duplicate_key_policy),
+        |i| unknown(i, verify_crc),
+    ))(input)
+}
+
+fn entry_ref(input: &[u8]) -> IResult<&[u8], (String, &[u8])> {
+    map(
+        tuple((
+            complete(length_data(verify(le_u32, |&n| n != 0))),
+            length_prefixed,
+        )),
+        |(key, value): (&[u8], &[u8])| (String::from_utf8_lossy(key).to_string(), value),
+    )(input)
+}
+
+fn dict_ref(input: &[u8]) -> IResult<&[u8], DictRef<'_>> {
+    let mut dict = DictRef::new();
+    let mut rest = input;
+    loop {
+        if rest.is_empty() {
+            return Ok((rest, dict));
+        }
+        if let Ok((after_terminator, ())) = terminator(rest) {
+            return Ok((after_terminator, dict));
+        }
+        let (after_entry, (key, value)) = entry_ref(rest)?;
+        dict.insert(key, value);
+        rest = after_entry;
+    }
+}
+
+fn img_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "IMG ",
+        verify_crc,
+        map(map_parser(length_prefixed, dict_ref), |dict| {
+            ChunkRef::Img { dict }
+        }),
+    )(input)
+}
+
+fn prev_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "PREV",
+        verify_crc,
+        map(length_prefixed, |data: &[u8]| ChunkRef::Prev { data }),
+    )(input)
+}
+
+fn bl16_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "BL16",
+        verify_crc,
+        map(length_prefixed, |data: &[u8]| ChunkRef::Bl16 { data }),
+    )(input)
+}
+
+fn layr_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "LAYR",
+        verify_crc,
+        map(
+            map_parser(
+                length_prefixed,
+                tuple((length_count(le_u32, block), dict_ref)),
+            ),
+            |(blocks, dict)| ChunkRef::Layr { blocks, dict },
+        ),
+    )(input)
+}
+
+fn camr_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "CAMR",
+        verify_crc,
+        map(map_parser(length_prefixed, dict_ref), |dict| {
+            ChunkRef::Camr { dict }
+        }),
+    )(input)
+}
+
+fn ligh_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "LIGH",
+        verify_crc,
+        map(map_parser(length_prefixed, dict_ref), |dict| {
+            ChunkRef::Ligh { dict }
+        }),
+    )(input)
+}
+
+fn mate_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "MATE",
+        verify_crc,
+        map(map_parser(length_prefixed, dict_ref), |dict| {
+            ChunkRef::Mate { dict }
+        }),
+    )(input)
+}
+
+fn pale_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    chunk_common(
+        "PALE",
+        verify_crc,
+        map(
+            map_parser(length_prefixed, tuple((length_count(le_u32, color4), dict_ref))),
+            |(colors, dict)| ChunkRef::Pale { colors, dict },
+        ),
+    )(input)
+}
+
+fn unknown_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    let (rest, (body, (tag, data))) = consumed(tuple((
+        verify(take(4usize), |t: &[u8]| {
+            let tag: [u8; 4] = t.try_into().unwrap();
+            !KNOWN_TAGS.contains(&&tag)
+        }),
+        length_prefixed,
+    )))(input)?;
+    let (rest, crc) = le_u32(rest)?;
+
+    if verify_crc && crc32(body) != crc {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+
+    Ok((
+        rest,
+        ChunkRef::Unknown {
+            tag: tag.try_into().unwrap(),
+            data,
+        },
+    ))
+}
+
+fn chunk_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], ChunkRef<'_>> {
+    alt((
+        |i| img_ref(i, verify_crc),
+        |i| prev_ref(i, verify_crc),
+        |i| bl16_ref(i, verify_crc),
+        |i| layr_ref(i, verify_crc),
+        |i| camr_ref(i, verify_crc),
+        |i| ligh_ref(i, verify_crc),
+        |i| mate_ref(i, verify_crc),
+        |i| pale_ref(i, verify_crc),
+        |i| unknown_ref(i, verify_crc),
+    ))(input)
+}
+
+fn parse_nom_ref(input: &[u8], verify_crc: bool) -> IResult<&[u8], GoxelRef<'_>> {
+    map(
+        preceded(
+            tag("GOX "),
+            tuple((le_i32, many0(|i| chunk_ref(i, verify_crc)))),
+        ),
+        |(version, chunks)| GoxelRef { version, chunks },
+    )(input)
+}
+
+/// Like [`parse_nom_ref`], but produces owned [`Chunk`]s and stops (without
+/// failing) at the first byte sequence that doesn't parse as one, the way
+/// `many0` always does. Used by [`parse_checked`], which inspects whatever
+/// `many0` left unconsumed instead of letting it pass silently.
+fn parse_nom(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> IResult<&[u8], Goxel> {
+    map(
+        preceded(
+            tag("GOX "),
+            tuple((
+                le_i32,
+                many0(move |i| chunk(i, verify_crc, strict_utf8, duplicate_key_policy)),
+            )),
+        ),
+        |(version, chunks)| Goxel { version, chunks },
+    )(input)
+}
+
+/// Figures out, after `many0` has given up at `rest`, which `GoxError`
+/// variant best explains why the remaining bytes didn't form another
+/// chunk. `offset` is computed from how much of `original` was consumed.
+fn diagnose(original: &[u8], rest: &[u8], verify_crc: bool) -> GoxError {
+    let offset = original.len() - rest.len();
+
+    let Some(tag_bytes) = rest.get(0..4) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+
+    let Some(size_bytes) = rest.get(4..8) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+    let size = u32::from_le_bytes(size_bytes.try_into().unwrap()) as usize;
+    let body_end = 8 + size;
+
+    let Some(framed) = rest.get(..body_end) else {
+        return GoxError::ChunkLengthOverrun {
+            chunk: tag_bytes.try_into().unwrap(),
+            declared: size as u32,
+            available: rest.len() - 8,
+        };
+    };
+    let Some(crc_bytes) = rest.get(body_end..body_end + 4) else {
+        return GoxError::TruncatedChunk { offset };
+    };
+
+    let stored_crc = u32::from_le_bytes(crc_bytes.try_into().unwrap());
+    if verify_crc {
+        let computed = crc32(framed);
+        if computed != stored_crc {
+            return GoxError::CrcMismatch {
+                expected: stored_crc,
+                found: computed,
+                offset,
+            };
+        }
+    }
+
+    GoxError::DictDecode { offset }
+}
+
+/// Copies up to the first 4 bytes of `input` into a fixed-size array,
+/// zero-padding if `input` is shorter, for reporting in [`GoxError::BadMagic`].
+fn magic_prefix(input: &[u8]) -> [u8; 4] {
+    let mut found = [0u8; 4];
+    let n = input.len().min(4);
+    found[..n].copy_from_slice(&input[..n]);
+    found
+}
+
+/// Checks the magic and version before any chunk is parsed. Versions 1 and
+/// 2 share the same chunk and dict layout as far as this crate decodes it,
+/// so the chunk parsers below don't need to branch on version themselves;
+/// an unrecognized version is rejected right here with
+/// [`GoxError::UnsupportedVersion`] instead of falling through to a chunk
+/// parser that would likely misinterpret its layout.
+fn check_header(input: &[u8]) -> Result<(), GoxError> {
+    if !input.starts_with(b"GOX ") {
+        return Err(GoxError::BadMagic {
+            found: magic_prefix(input),
+        });
+    }
+    if input.len() < 8 {
+        return Err(GoxError::TruncatedChunk { offset: 4 });
+    }
+    let version = i32::from_le_bytes(input[4..8].try_into().unwrap());
+    if version != 1 && version != 2 {
+        return Err(GoxError::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+/// How a dict's parser should handle a repeated key, which a well-formed
+/// Goxel file never has. Threaded alongside `strict_utf8` through
+/// [`ChunkIter`]/[`parse_with_duplicate_key_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    /// The repeated key's last value wins, silently. Matches this crate's
+    /// historical behavior (a plain `map.insert` per entry), so it's the
+    /// default.
+    #[default]
+    KeepLast,
+    /// The repeated key's first value wins; later occurrences are ignored.
+    KeepFirst,
+    /// Fails with [`GoxError::DuplicateDictKey`] instead of picking either
+    /// value, for strict tooling that wants to reject a file a duplicate
+    /// key might indicate is corrupt.
+    Error,
+}
+
+/// An nom `ErrorKind` used purely as a marker so [`ChunkIter::next`] can
+/// tell a [`GoxError::DuplicateDictKey`] failure (raised by [`dict`]) apart
+/// from an invalid-UTF-8 key failure (raised by [`entry`]), since nom's
+/// default error type doesn't carry anything richer than `(input, kind)`.
+/// Not produced by any actual nom combinator in this module.
+const DUPLICATE_KEY_MARKER: nom::error::ErrorKind = nom::error::ErrorKind::Many1;
+
+/// Re-decodes the key at the start of `input` the same way [`entry`] does,
+/// for reporting in [`GoxError::DuplicateDictKey`] once [`dict`] has
+/// raised a [`DUPLICATE_KEY_MARKER`] failure pointing at it.
+fn decode_failure_key(input: &[u8]) -> String {
+    let Ok((_, key)) = length_data::<_, _, nom::error::Error<&[u8]>, _>(le_u32)(input) else {
+        return String::new();
+    };
+    String::from_utf8_lossy(key).to_string()
+}
+
+/// Lazily parses a `.gox` byte stream one chunk at a time, rather than
+/// collecting every chunk (and every `BL16` block's pixel data) up front
+/// the way [`parse`] does. Lets a consumer scan for just the chunks it
+/// cares about and stop early, or process `BL16` blocks streaming-style
+/// without holding them all in memory at once.
+pub struct ChunkIter<'a> {
+    original: &'a [u8],
+    rest: &'a [u8],
+    version: i32,
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+    done: bool,
+}
+
+impl<'a> ChunkIter<'a> {
+    /// Validates `input`'s `.gox` magic header and version, then returns an
+    /// iterator over the chunks that follow. Each chunk's CRC-32 trailer is
+    /// checked only if `verify_crc` is set. If `strict_utf8` is set, a dict
+    /// key that isn't valid UTF-8 fails with
+    /// [`GoxError::InvalidKeyUtf8`] instead of being silently replaced with
+    /// `U+FFFD`. `duplicate_key_policy` controls what happens when a dict
+    /// has the same key twice; see [`DuplicateKeyPolicy`].
+    pub fn new(
+        input: &'a [u8],
+        verify_crc: bool,
+        strict_utf8: bool,
+        duplicate_key_policy: DuplicateKeyPolicy,
+    ) -> Result<Self, GoxError> {
+        check_header(input)?;
+        Ok(ChunkIter {
+            original: input,
+            rest: &input[8..],
+            version: i32::from_le_bytes(input[4..8].try_into().unwrap()),
+            verify_crc,
+            strict_utf8,
+            duplicate_key_policy,
+            done: false,
+        })
+    }
+
+    /// The file format version this `.gox` stream was written with.
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+}
+
+impl<'a> Iterator for ChunkIter<'a> {
+    type Item = Result<Chunk, GoxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.rest.is_empty() {
+            return None;
+        }
+        match chunk(
+            self.rest,
+            self.verify_crc,
+            self.strict_utf8,
+            self.duplicate_key_policy,
+        ) {
+            Ok((rest, chunk)) => {
+                self.rest = rest;
+                Some(Ok(chunk))
+            }
+            Err(nom::Err::Failure(err)) if err.code == DUPLICATE_KEY_MARKER => {
+                self.done = true;
+                Some(Err(GoxError::DuplicateDictKey {
+                    key: decode_failure_key(err.input),
+                }))
+            }
+            Err(nom::Err::Failure(err)) => {
+                self.done = true;
+                let offset = self.original.len() - err.input.len();
+                Some(Err(GoxError::InvalidKeyUtf8 { offset }))
+            }
+            Err(_) => {
+                self.done = true;
+                Some(Err(diagnose(self.original, self.rest, self.verify_crc)))
+            }
+        }
+    }
+}
+
+fn parse_with(
+    input: &[u8],
+    verify_crc: bool,
+    strict_utf8: bool,
+    duplicate_key_policy: DuplicateKeyPolicy,
+) -> Result<Goxel, GoxError> {
+    let mut chunks = ChunkIter::new(input, verify_crc, strict_utf8, duplicate_key_policy)?;
+    let version = chunks.version();
+    let chunks = chunks.by_ref().collect::<Result<Vec<_>, _>>()?;
+    Ok(Goxel { version, chunks })
+}
+
+/// Parses a `.gox` byte stream, ignoring each chunk's CRC-32 trailer. An
+/// invalid UTF-8 dict key is silently replaced with `U+FFFD`; see
+/// [`parse_strict`] to reject that instead.
+pub fn parse(input: &[u8]) -> Result<Goxel, GoxError> {
+    parse_with(input, false, false, DuplicateKeyPolicy::KeepLast)
+}
+
+/// Parses a `.gox` byte stream, verifying each chunk's CRC-32 trailer and
+/// failing instead of silently accepting a corrupt chunk.
+pub fn parse_verified(input: &[u8]) -> Result<Goxel, GoxError> {
+    parse_with(input, true, false, DuplicateKeyPolicy::KeepLast)
+}
+
+/// Like [`parse`], but fails with [`GoxError::InvalidKeyUtf8`] if a dict key
+/// isn't valid UTF-8, instead of silently replacing it with `U+FFFD`. Useful
+/// for catching corruption that a lossy round-trip would otherwise hide.
+pub fn parse_strict(input: &[u8]) -> Result<Goxel, GoxError> {
+    parse_with(input, false, true, DuplicateKeyPolicy::KeepLast)
+}
+
+/// Like [`parse`], but applies `policy` instead of silently keeping a
+/// repeated dict key's last value; see [`DuplicateKeyPolicy`].
+pub fn parse_with_duplicate_key_policy(
+    input: &[u8],
+    policy: DuplicateKeyPolicy,
+) -> Result<Goxel, GoxError> {
+    parse_with(input, false, false, policy)
+}
+
+/// Parses a single chunk from the front of `input`, returning it alongside
+/// whatever input remains after it. Exposes this crate's internal chunk
+/// parser for advanced callers that want to drive parsing manually —
+/// skipping chunks, stopping early, or handling a chunk type this crate
+/// doesn't know about alongside [`Chunk::Unknown`] — instead of going
+/// through [`parse`] end to end. Ignores the chunk's CRC-32 trailer, like
+/// [`parse`].
+pub fn parse_chunk(input: &[u8]) -> Result<(Chunk, &[u8]), GoxError> {
+    match chunk(input, false, false, DuplicateKeyPolicy::KeepLast) {
+        Ok((rest, parsed)) => Ok((parsed, rest)),
+        Err(_) => Err(diagnose(input, input, false)),
+    }
+}
+
+/// Parses a `.gox` model starting at `offset` within `input`, returning it
+/// alongside the byte position just past its last chunk, so a caller can
+/// feed that position back in as the next `offset` to parse several models
+/// (or a model followed by a caller's own trailing data) out of one
+/// buffer. Unlike [`parse`], stops as soon as it reaches a `"GOX "` magic
+/// header rather than misinterpreting it as an unknown chunk tag, so a
+/// second concatenated model's header doesn't get swallowed into the
+/// first. Ignores each chunk's CRC-32 trailer, like [`parse`].
+pub fn parse_at(input: &[u8], offset: usize) -> Result<(Goxel, usize), GoxError> {
+    let slice = input.get(offset..).ok_or(GoxError::TruncatedChunk { offset })?;
+    check_header(slice)?;
+    let version = i32::from_le_bytes(slice[4..8].try_into().unwrap());
+
+    let mut rest = &slice[8..];
+    let mut chunks = Vec::new();
+    while !rest.is_empty() && !rest.starts_with(b"GOX ") {
+        match chunk(rest, false, false, DuplicateKeyPolicy::KeepLast) {
+            Ok((remaining, decoded)) => {
+                chunks.push(decoded);
+                rest = remaining;
+            }
+            Err(_) => return Err(diagnose(slice, rest, false)),
+        }
+    }
+
+    let consumed = slice.len() - rest.len();
+    Ok((Goxel { version, chunks }, offset + consumed))
+}
+
+/// Like [`parse`], but fails with [`GoxError::NoChunks`] instead of
+/// returning a valid, empty [`Goxel`] when the file has no chunks at all.
+/// A well-formed `.gox` almost always has at least an `IMG` chunk, so an
+/// empty chunk list is more often a sign that a file got cut off before any
+/// data was written than a deliberately blank one; use this over [`parse`]
+/// when that distinction matters to the caller. Check [`Goxel::is_empty`]
+/// after a plain [`parse`] if you'd rather decide for yourself.
+///
+/// Also verifies that parsing consumed the entire input. [`parse`] stops
+/// silently at the first byte sequence it can't parse as a chunk (the usual
+/// `many0` behavior), which would otherwise let a truncated-then-garbage
+/// file "succeed" with the garbage quietly dropped; this returns
+/// [`GoxError::TrailingBytes`] instead. A trailing run of zero bytes is
+/// tolerated as padding, since some encoders pad their output out to a
+/// block boundary.
+pub fn parse_checked(input: &[u8]) -> Result<Goxel, GoxError> {
+    check_header(input)?;
+    let (rest, goxel) = parse_nom(input, false, false, DuplicateKeyPolicy::KeepLast)
+        .expect("magic and version were already validated above");
+    if !rest.is_empty() && !rest.iter().all(|&b| b == 0) {
+        return Err(GoxError::TrailingBytes {
+            offset: input.len() - rest.len(),
+            len: rest.len(),
+        });
+    }
+    if goxel.is_empty() {
+        return Err(GoxError::NoChunks);
+    }
+    Ok(goxel)
+}
+
+/// Combines [`parse_verified`] and [`parse_strict`]: verifies each chunk's
+/// CRC-32 trailer and rejects an invalid UTF-8 dict key, rather than
+/// silently accepting either kind of corruption.
+pub fn parse_strict_verified(input: &[u8]) -> Result<Goxel, GoxError> {
+    parse_with(input, true, true, DuplicateKeyPolicy::KeepLast)
+}
+
+/// Options controlling [`parse_with_options`]'s resource usage.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    /// Aborts parsing with [`GoxError::LimitExceeded`] once the cumulative
+    /// size of every chunk's owned payload (dict keys/values, block lists,
+    /// `PREV`/`BL16`/`Unknown` data) would exceed this many bytes. Defaults
+    /// to `usize::MAX`, i.e. no limit, matching [`parse`]'s behavior.
+    /// Lowering it is recommended whenever a `.gox` comes from an untrusted
+    /// source (e.g. a server accepting file uploads), since a file's
+    /// declared chunk lengths aren't otherwise bounded by anything but the
+    /// input's own size.
+    ///
+    /// This bounds the *sum* across chunks, not any individual one: the
+    /// check runs after [`ChunkIter::next`] has already parsed and
+    /// allocated a chunk's owned data, so one chunk declaring a size up to
+    /// the remaining input length is never intercepted before that
+    /// allocation happens. `max_alloc` can be overshot by up to one chunk's
+    /// size before the next iteration catches it — don't treat it as a hard
+    /// per-chunk ceiling.
+    pub max_alloc: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions { max_alloc: usize::MAX }
+    }
+}
+
+/// Like [`parse`], but aborts with [`GoxError::LimitExceeded`] once
+/// `options.max_alloc` is exceeded, rather than parsing the whole file
+/// regardless of how much memory its chunks end up claiming. The check runs
+/// after each chunk finishes parsing (see [`ParseOptions::max_alloc`]), so
+/// it bounds the total across chunks rather than any single one.
+pub fn parse_with_options(input: &[u8], options: ParseOptions) -> Result<Goxel, GoxError> {
+    let mut chunks = ChunkIter::new(input, false, false, DuplicateKeyPolicy::KeepLast)?;
+    let version = chunks.version();
+    let mut allocated = 0usize;
+    let mut owned = Vec::new();
+    for chunk in chunks.by_ref() {
+        let chunk = chunk?;
+        allocated += chunk.heap_size();
+        if allocated > options.max_alloc {
+            return Err(GoxError::LimitExceeded { limit: options.max_alloc });
+        }
+        owned.push(chunk);
+    }
+    Ok(Goxel { version, chunks: owned })
+}
+
+/// Timing and counts collected by [`parse_timed`], for profiling large-batch
+/// ingestion. `chunk_parse_time` excludes any `BL16` PNG decode time, since
+/// [`Goxel::model`]/[`Block::voxels`] decode pixel data lazily, not during
+/// parsing itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMetrics {
+    /// Wall-clock time spent walking and framing chunks, not counting any
+    /// lazy `BL16` PNG decoding (which doesn't happen during parsing).
+    pub chunk_parse_time: std::time::Duration,
+    /// The number of bytes in the input that was parsed.
+    pub bytes: usize,
+    /// The number of chunks successfully parsed before parsing stopped,
+    /// whether that's because the input was exhausted or a chunk failed.
+    pub chunks: usize,
+}
+
+/// Like [`parse`], but also returns [`ParseMetrics`] describing how long
+/// parsing took and how much it processed, for callers profiling ingestion
+/// of a large batch of files. The default [`parse`] has zero timing
+/// overhead; reach for this only when you're actually measuring.
+pub fn parse_timed(input: &[u8]) -> (Result<Goxel, GoxError>, ParseMetrics) {
+    let start = std::time::Instant::now();
+
+    let result = (|| {
+        let mut chunks = ChunkIter::new(input, false, false, DuplicateKeyPolicy::KeepLast)?;
+        let version = chunks.version();
+        let mut owned = Vec::new();
+        for chunk in chunks.by_ref() {
+            owned.push(chunk?);
+        }
+        Ok(Goxel { version, chunks: owned })
+    })();
+
+    let metrics = ParseMetrics {
+        chunk_parse_time: start.elapsed(),
+        bytes: input.len(),
+        chunks: result.as_ref().map(|goxel: &Goxel| goxel.chunks.len()).unwrap_or(0),
+    };
+    (result, metrics)
+}
+
+/// Parses a `.gox` byte stream like [`parse`], pairing each chunk with its
+/// exact byte range in `input`: type tag, length, body, and CRC-32 trailer
+/// together. Concatenating the 8-byte magic header and every span in order
+/// reconstructs `input` exactly, which makes this useful for tools that
+/// want to relocate, copy, or patch a chunk verbatim without re-serializing
+/// it. Ignores each chunk's CRC-32 trailer, like [`parse`]; use
+/// [`ChunkIter`] directly for strict UTF-8 or CRC verification alongside
+/// spans.
+pub fn parse_with_spans(input: &[u8]) -> Result<Vec<(Chunk, std::ops::Range<usize>)>, GoxError> {
+    check_header(input)?;
+
+    let mut rest = &input[8..];
+    let mut spans = Vec::new();
+    while !rest.is_empty() {
+        let start = input.len() - rest.len();
+        match chunk(rest, false, false, DuplicateKeyPolicy::KeepLast) {
+            Ok((next, parsed)) => {
+                let end = input.len() - next.len();
+                spans.push((parsed, start..end));
+                rest = next;
+            }
+            Err(_) => return Err(diagnose(input, rest, false)),
+        }
+    }
+    Ok(spans)
+}
+
+fn parse_borrowed_with(input: &[u8], verify_crc: bool) -> Result<GoxelRef<'_>, GoxError> {
+    check_header(input)?;
+
+    let (rest, goxel) = parse_nom_ref(input, verify_crc)
+        .expect("magic and version were already validated above");
+    if !rest.is_empty() {
+        return Err(diagnose(input, rest, verify_crc));
+    }
+    Ok(goxel)
+}
+
+/// Parses a `.gox` byte stream without copying chunk bodies or dict
+/// values: `PREV`/`BL16`/`Unknown` data and dict values in the returned
+/// [`GoxelRef`] are slices borrowed from `input`. Ignores each chunk's
+/// CRC-32 trailer; see [`parse_borrowed_verified`] to check it. For big
+/// files full of `BL16` blobs this avoids copying megabytes of pixel data
+/// just to read it.
+pub fn parse_borrowed(input: &[u8]) -> Result<GoxelRef<'_>, GoxError> {
+    parse_borrowed_with(input, false)
+}
+
+/// Like [`parse_borrowed`], but verifies each chunk's CRC-32 trailer and
+/// fails instead of silently accepting a corrupt chunk.
+pub fn parse_borrowed_verified(input: &[u8]) -> Result<GoxelRef<'_>, GoxError> {
+    parse_borrowed_with(input, true)
+}
+
+/// Reads an entire `.gox` stream from `reader` and parses it, verifying
+/// each chunk's CRC-32 trailer. This buffers the whole stream before
+/// parsing it with [`parse_verified`]; `reader` need not support seeking,
+/// so this works equally well on files, sockets, or pipes.
+pub fn from_reader<R: Read>(mut reader: R) -> Result<Goxel, GoxError> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    parse_verified(&buf)
+}
+
+/// The largest decompressed size [`from_gz`] will read before giving up.
+/// Gzip can expand a tiny compressed file into an enormous one, the same
+/// "zip bomb" hazard `src/png.rs`'s inflate size cap guards a single `BL16`
+/// PNG payload against; without a cap here, a crafted `.gox.gz` could
+/// exhaust memory before [`parse_verified`] ever gets a chance to reject
+/// it.
+#[cfg(feature = "gzip")]
+const MAX_GZ_INFLATED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Reads a gzip-compressed `.gox` stream from `reader`, decompresses it,
+/// and parses it with [`parse_verified`]. Some tools ship `.gox.gz` files;
+/// this saves callers from wiring up a `GzDecoder` themselves. Only
+/// available with the `gzip` feature, so the default build stays
+/// dependency-light. Decompression stops and returns
+/// [`GoxError::LimitExceeded`] past [`MAX_GZ_INFLATED_SIZE`] bytes.
+#[cfg(feature = "gzip")]
+pub fn from_gz<R: Read>(reader: R) -> Result<Goxel, GoxError> {
+    let mut buf = Vec::new();
+    flate2::read::GzDecoder::new(reader)
+        .take(MAX_GZ_INFLATED_SIZE + 1)
+        .read_to_end(&mut buf)
+        .map_err(GoxError::Decompress)?;
+    if buf.len() as u64 > MAX_GZ_INFLATED_SIZE {
+        return Err(GoxError::LimitExceeded { limit: MAX_GZ_INFLATED_SIZE as usize });
+    }
+    parse_verified(&buf)
+}
+
+/// The largest chunk body [`from_async_reader`] will allocate a buffer
+/// for. A streaming reader has no total length to sanity-check a declared
+/// chunk length against the way the slice-based parser does, so this caps
+/// it outright: a chunk claiming more than this is rejected before we
+/// allocate anything for its body.
+#[cfg(feature = "tokio")]
+const MAX_ASYNC_CHUNK_BODY: u32 = 256 * 1024 * 1024;
+
+/// Reads a `.gox` stream from an async reader, one chunk at a time,
+/// verifying each chunk's CRC-32 trailer the same as [`parse_verified`].
+/// Unlike [`from_reader`], this doesn't buffer the whole stream up front:
+/// each chunk's declared length is checked against
+/// [`MAX_ASYNC_CHUNK_BODY`] before its body is read, so a malicious or
+/// corrupt declared length can't force a gigabytes-sized allocation before
+/// parsing has a chance to fail. Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(
+    mut reader: R,
+) -> Result<Goxel, GoxError> {
+    use tokio::io::AsyncReadExt;
+
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header).await?;
+    check_header(&header)?;
+    let version = i32::from_le_bytes(header[4..8].try_into().unwrap());
+
+    let mut chunks = Vec::new();
+    loop {
+        let mut tag = [0u8; 4];
+        let first = reader.read(&mut tag[..1]).await?;
+        if first == 0 {
+            break; // clean end of stream between chunks
+        }
+        reader.read_exact(&mut tag[1..]).await?;
+
+        let mut len_bytes = [0u8; 4];
+        reader.read_exact(&mut len_bytes).await?;
+        let declared = u32::from_le_bytes(len_bytes);
+        if declared > MAX_ASYNC_CHUNK_BODY {
+            return Err(GoxError::ChunkLengthOverrun {
+                chunk: tag,
+                declared,
+                available: MAX_ASYNC_CHUNK_BODY as usize,
+            });
+        }
+
+        let mut framed = Vec::with_capacity(8 + declared as usize + 4);
+        framed.extend_from_slice(&tag);
+        framed.extend_from_slice(&len_bytes);
+        let body_start = framed.len();
+        framed.resize(body_start + declared as usize + 4, 0); // body + CRC trailer
+        reader.read_exact(&mut framed[body_start..]).await?;
+
+        let (_, chunk) = chunk(&framed, true, false).map_err(|_| diagnose(&framed, &framed, true))?;
+        chunks.push(chunk);
+    }
+
+    Ok(Goxel { version, chunks })
+}
+
+/// Encodes `data` as a `le_u32` length prefix followed by the bytes
+/// themselves, mirroring [`length_prefixed`] on the read side.
+fn write_length_prefixed(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+}
+
+/// Encodes a dict's entries as `key_len/key/value_len/value` tuples in their
+/// `IndexMap` order (the order they were parsed in, for a parsed chunk), then
+/// the trailing 0-length-key terminator that marks the end of the dict on
+/// disk.
+fn encode_dict(dict: &Dict) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (key, value) in dict {
+        write_length_prefixed(&mut out, key.as_bytes());
+        write_length_prefixed(&mut out, value);
+    }
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out
+}
+
+impl Block {
+    fn encode(&self) -> [u8; 20] {
+        let mut out = [0u8; 20];
+        for (i, field) in [self.index, self.x, self.y, self.z, 0].iter().enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&field.to_le_bytes());
+        }
+        out
+    }
+}
+
+impl Chunk {
+    /// Encodes this chunk's type tag, length-prefixed body and CRC-32
+    /// trailer, in the same framing `chunk_common` expects to read back.
+    fn to_bytes(&self) -> Vec<u8> {
+        let (tag, body): (&[u8; 4], Vec<u8>) = match self {
+            Chunk::Img { dict } => (b"IMG ", encode_dict(dict)),
+            Chunk::Prev { data } => (b"PREV", data.clone()),
+            Chunk::Bl16 { data } => (b"BL16", data.clone()),
+            Chunk::Layr { blocks, dict } => {
+                let mut inner = Vec::new();
+                inner.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+                for block in blocks {
+                    inner.extend_from_slice(&block.encode());
+                }
+                inner.extend_from_slice(&encode_dict(dict));
+                (b"LAYR", inner)
+            }
+            Chunk::Camr { dict } => (b"CAMR", encode_dict(dict)),
+            Chunk::Ligh { dict } => (b"LIGH", encode_dict(dict)),
+            Chunk::Mate { dict } => (b"MATE", encode_dict(dict)),
+            Chunk::Pale { colors, dict } => {
+                let mut inner = Vec::new();
+                inner.extend_from_slice(&(colors.len() as u32).to_le_bytes());
+                for color in colors {
+                    inner.extend_from_slice(color);
+                }
+                inner.extend_from_slice(&encode_dict(dict));
+                (b"PALE", inner)
+            }
+            Chunk::Unknown { tag, data } => (tag, data.clone()),
+        };
+
+        let mut framed = Vec::with_capacity(4 + 4 + body.len());
+        framed.extend_from_slice(tag);
+        write_length_prefixed(&mut framed, &body);
+
+        let crc = crc32(&framed);
+        framed.extend_from_slice(&crc.to_le_bytes());
+        framed
+    }
+}
+
+impl Goxel {
+    /// Writes this `Goxel` back out as a `.gox` byte stream: the `"GOX "`
+    /// magic, the version, then each chunk in the framing `parse` expects.
+    ///
+    /// `Block::index` counts only `BL16` chunks, in file order, so before
+    /// writing we dedup identical `BL16` payloads down to a single chunk
+    /// and remap every `Block` that referenced a dropped duplicate to the
+    /// surviving copy. Goxel commonly reuses the same solid block across
+    /// many placements; without this, a naive writer would emit one
+    /// `BL16` per placement and bloat the file.
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"GOX ")?;
+        w.write_all(&self.version.to_le_bytes())?;
+
+        let mut first_seen: HashMap<&[u8], i32> = HashMap::new();
+        let mut deduped_index: Vec<i32> = Vec::new();
+        for chunk in &self.chunks {
+            if let Chunk::Bl16 { data } = chunk {
+                let next = first_seen.len() as i32;
+                deduped_index.push(*first_seen.entry(data.as_slice()).or_insert(next));
+            }
+        }
+
+        let mut written = vec![false; first_seen.len()];
+        let mut ordinal = 0usize;
+        for chunk in &self.chunks {
+            match chunk {
+                Chunk::Bl16 { .. } => {
+                    let new_index = deduped_index[ordinal] as usize;
+                    ordinal += 1;
+                    if !written[new_index] {
+                        written[new_index] = true;
+                        w.write_all(&chunk.to_bytes())?;
+                    }
+                }
+                Chunk::Layr { blocks, dict } => {
+                    let remapped = blocks
+                        .iter()
+                        .map(|block| Block {
+                            index: deduped_index
+                                .get(block.index as usize)
+                                .copied()
+                                .unwrap_or(block.index),
+                            x: block.x,
+                            y: block.y,
+                            z: block.z,
+                        })
+                        .collect();
+                    w.write_all(&Chunk::Layr { blocks: remapped, dict: dict.clone() }.to_bytes())?;
+                }
+                _ => w.write_all(&chunk.to_bytes())?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Convenience wrapper around [`Goxel::write`] for callers who just want
+    /// the encoded bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out)
+            .expect("writing to a Vec<u8> cannot fail");
+        out
+    }
+
+    /// Convenience wrapper around [`parse`] for callers who'd rather call a
+    /// method on `Goxel` than a free function, mirroring [`Goxel::to_bytes`].
+    pub fn from_bytes(input: &[u8]) -> Result<Goxel, GoxError> {
+        parse(input)
+    }
+
+    /// Parses `input` into `self`, reusing `self.chunks`'s existing
+    /// allocation instead of building a fresh `Vec` the way [`parse`]
+    /// does. Intended for a hot loop that repeatedly parses similarly
+    /// shaped files into the same `Goxel`, where the backing allocation
+    /// surviving across calls avoids paying for it on every iteration.
+    /// Ignores each chunk's CRC-32 trailer, like [`parse`].
+    ///
+    /// If a chunk fails to parse, `self.chunks` is left holding whatever
+    /// chunks parsed successfully before the failure, and `self.version`
+    /// is left at `input`'s version; callers shouldn't rely on `self`'s
+    /// old contents surviving a failed call.
+    pub fn parse_into(&mut self, input: &[u8]) -> Result<(), GoxError> {
+        let mut iter = ChunkIter::new(input, false, false)?;
+        self.version = iter.version();
+        self.chunks.clear();
+        for chunk in iter.by_ref() {
+            self.chunks.push(chunk?);
+        }
+        Ok(())
+    }
+
+    /// Convenience wrapper around [`from_reader`] for callers who'd rather
+    /// call a method on `Goxel` than a free function. Useful for loading
+    /// large files or reading from sockets/pipes without pre-buffering the
+    /// whole stream themselves first.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Goxel, GoxError> {
+        from_reader(reader)
+    }
+
+    /// Convenience wrapper around [`from_gz`] for callers who'd rather call
+    /// a method on `Goxel` than a free function. Only available with the
+    /// `gzip` feature.
+    #[cfg(feature = "gzip")]
+    pub fn from_gz<R: Read>(reader: R) -> Result<Goxel, GoxError> {
+        from_gz(reader)
+    }
+
+    /// Convenience wrapper around [`from_async_reader`] for callers who'd
+    /// rather call a method on `Goxel` than a free function. Only
+    /// available with the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(
+        reader: R,
+    ) -> Result<Goxel, GoxError> {
+        from_async_reader(reader).await
+    }
+
+    /// Reads and parses a `.gox` file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Goxel, GoxError> {
+        Goxel::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Writes this `Goxel` out to `path` as `.gox` bytes.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GoxError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Memory-maps the file at `path` instead of reading it into a heap
+    /// buffer, then parses it with [`parse_borrowed`] to confirm it's
+    /// well-formed. Returns a [`MappedGoxel`] that keeps the mapping alive
+    /// so callers can keep borrowing a zero-copy [`GoxelRef`] from it via
+    /// [`MappedGoxel::goxel`]. Only available with the `mmap` feature.
+    ///
+    /// See [`MappedGoxel`]'s docs for the safety considerations that come
+    /// with memory-mapping a file.
+    #[cfg(feature = "mmap")]
+    pub fn open_mmap<P: AsRef<Path>>(path: P) -> Result<MappedGoxel, GoxError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: see `MappedGoxel`'s docs; the caller is trusted not to
+        // mutate, truncate, or remove the file while it's mapped.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let mapped = MappedGoxel { mmap };
+        mapped.goxel()?;
+        Ok(mapped)
+    }
+}
+
+/// A `.gox` file memory-mapped from disk via [`Goxel::open_mmap`]. Owns
+/// the mapping so the zero-copy [`GoxelRef`] returned by
+/// [`MappedGoxel::goxel`] stays valid for as long as this does, without
+/// ever reading the file's bytes into a heap-allocated buffer.
+///
+/// # Safety
+/// A memory-mapped file is only sound to read from if nothing mutates,
+/// truncates, or removes the underlying file for as long as the mapping
+/// exists — including another process. If that happens, the bytes backing
+/// every [`GoxelRef`] borrowed from this mapping can change or become
+/// invalid out from under the reader, which is undefined behavior. Only
+/// use this on files you know won't be modified concurrently.
+#[cfg(feature = "mmap")]
+pub struct MappedGoxel {
+    mmap: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedGoxel {
+    /// The mapped file's raw bytes.
+    pub fn bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Parses the mapped bytes as a zero-copy `.gox` file, ignoring each
+    /// chunk's CRC-32 trailer; see [`parse_borrowed`].
+    pub fn goxel(&self) -> Result<GoxelRef<'_>, GoxError> {
+        parse_borrowed(&self.mmap)
+    }
+
+    /// Like [`MappedGoxel::goxel`], but verifies each chunk's CRC-32
+    /// trailer; see [`parse_borrowed_verified`].
+    pub fn goxel_verified(&self) -> Result<GoxelRef<'_>, GoxError> {
+        parse_borrowed_verified(&self.mmap)
+    }
+}
+
+/// Incrementally writes a `.gox` stream one chunk at a time, rather than
+/// building a whole [`Goxel`] in memory first the way [`Goxel::write`]
+/// requires. Writes the `"GOX "` magic and version on construction; each
+/// `write_*` call encodes one chunk's length and CRC-32 trailer and
+/// flushes it straight to the sink. Unlike [`Goxel::write`], this never
+/// dedups `BL16` payloads — a generator streaming chunks has no later
+/// chance to rewrite an already-flushed `Block::index`, so it's on the
+/// caller to avoid repeating an identical block if that matters to them.
+pub struct GoxWriter<W: Write> {
+    sink: W,
+}
+
+impl<W: Write> GoxWriter<W> {
+    /// Writes the `"GOX "` magic and `version` to `sink`, returning a
+    /// writer ready for `write_chunk`/`write_bl16`/`write_layer` calls.
+    pub fn new(mut sink: W, version: i32) -> io::Result<Self> {
+        sink.write_all(b"GOX ")?;
+        sink.write_all(&version.to_le_bytes())?;
+        Ok(GoxWriter { sink })
+    }
+
+    /// Encodes `chunk`'s tag, length-prefixed body and CRC-32 trailer, and
+    /// writes it to the sink.
+    pub fn write_chunk(&mut self, chunk: &Chunk) -> io::Result<()> {
+        self.sink.write_all(&chunk.to_bytes())
+    }
+
+    /// Writes a `BL16` chunk wrapping `png_data`, a pre-encoded 16×16×16
+    /// voxel grid PNG (see [`Block::voxels`] for the decode side).
+    pub fn write_bl16(&mut self, png_data: Vec<u8>) -> io::Result<()> {
+        self.write_chunk(&Chunk::Bl16 { data: png_data })
+    }
+
+    /// Writes a `LAYR` chunk for `blocks` and `dict`. Each block's `index`
+    /// must already reference a `BL16` chunk written earlier in this
+    /// stream, same as the on-disk format expects.
+    pub fn write_layer(&mut self, blocks: Vec<Block>, dict: Dict) -> io::Result<()> {
+        self.write_chunk(&Chunk::Layr { blocks, dict })
+    }
+}
+
+/// Streams voxels into a single `.gox` layer's `BL16` blocks as they fill,
+/// instead of buffering the whole model in a [`Model`]/[`ModelBuilder`]
+/// before writing anything out. Built for authoring huge procedural
+/// models where holding every voxel in memory at once isn't an option.
+///
+/// For bounded memory, this holds only one 16×16×16 block open at a time:
+/// call [`StreamingModelWriter::push`] for each voxel, and as soon as a
+/// push lands outside the currently open block's extent, that block is
+/// PNG-encoded and written out as a `BL16` chunk before a new one opens.
+/// **Push voxels in block-local order** (finish one 16×16×16 region
+/// before moving to the next) for this to pay off; pushing out of order
+/// still produces a correct file, but bounces the open block back and
+/// forth, flushing (and re-opening, as a second [`Block`] entry for the
+/// same origin) on every push that crosses a block boundary.
+///
+/// [`StreamingModelWriter::finish`] flushes the last open block and
+/// writes the `LAYR` chunk referencing every `BL16` chunk this writer
+/// produced, completing the file.
+pub struct StreamingModelWriter<W: Write> {
+    writer: GoxWriter<W>,
+    layer_name: String,
+    open: Option<((i32, i32, i32), Box<Voxels>)>,
+    blocks: Vec<Block>,
+    bl16_count: usize,
+}
+
+impl<W: Write> StreamingModelWriter<W> {
+    /// Writes the `"GOX "` header and starts a new layer named
+    /// `layer_name`. Write any `IMG`/`CAMR`/... chunks first with
+    /// [`StreamingModelWriter::write_chunk`], before pushing voxels.
+    pub fn new(sink: W, version: i32, layer_name: impl Into<String>) -> io::Result<Self> {
+        Ok(StreamingModelWriter {
+            writer: GoxWriter::new(sink, version)?,
+            layer_name: layer_name.into(),
+            open: None,
+            blocks: Vec::new(),
+            bl16_count: 0,
+        })
+    }
+
+    /// Writes a chunk directly to the underlying stream, ahead of the
+    /// `BL16`/`LAYR` chunks streaming voxels produces.
+    pub fn write_chunk(&mut self, chunk: &Chunk) -> io::Result<()> {
+        self.writer.write_chunk(chunk)
+    }
+
+    /// Places a voxel at world-space `pos`. See the type docs for why
+    /// pushing in block-local order matters.
+    pub fn push(&mut self, pos: (i32, i32, i32), rgba: [u8; 4]) -> io::Result<()> {
+        let (origin, [lx, ly, lz]) = world_to_block([pos.0, pos.1, pos.2]);
+        let origin = (origin[0], origin[1], origin[2]);
+        if self.open.as_ref().map(|(o, _)| *o) != Some(origin) {
+            self.flush_open()?;
+            self.open = Some((origin, Box::new([[[[0u8; 4]; 16]; 16]; 16])));
+        }
+        let (_, voxels) = self.open.as_mut().expect("just set above");
+        voxels[lx][ly][lz] = rgba;
+        Ok(())
+    }
+
+    fn flush_open(&mut self) -> io::Result<()> {
+        let Some((origin, voxels)) = self.open.take() else {
+            return Ok(());
+        };
+        self.writer.write_bl16(encode_bl16(&voxels))?;
+        self.blocks.push(Block {
+            index: self.bl16_count as i32,
+            x: origin.0,
+            y: origin.1,
+            z: origin.2,
+        });
+        self.bl16_count += 1;
+        Ok(())
+    }
+
+    /// Flushes any still-open block and writes the `LAYR` chunk
+    /// referencing every block this writer produced, completing the file.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.flush_open()?;
+        self.writer.write_layer(self.blocks, layer_dict(&self.layer_name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn world_to_block_and_back_round_trips_negative_coordinates() {
+        for pos in [[-1, -1, -1], [-16, -16, -16], [-17, 5, -33], [0, 0, 0], [31, -1, 16]] {
+            let (origin, local) = world_to_block(pos);
+            assert!(local.iter().all(|&l| l < 16), "local index {local:?} out of range");
+            assert_eq!(block_to_world(origin, local), pos);
+        }
+    }
+
+    #[test]
+    fn world_to_block_floors_negative_coordinates_instead_of_truncating() {
+        // -1 should land in the block at -16 (local index 15), not be
+        // truncated toward zero into the block at 0 (which would give a
+        // nonsensical negative local index).
+        let (origin, local) = world_to_block([-1, -1, -1]);
+        assert_eq!(origin, [-16, -16, -16]);
+        assert_eq!(local, [15, 15, 15]);
+    }
+
+    #[test]
+    fn img_should_parse() {
+        let input: &[u8] = &[
+            // Chunk Header
+            b'I', b'M', b'G', b' ', // Type
+            0x9, 0x0, 0x0, 0x0, // Size
+            // Dict
+            0x1, 0x0, 0x0, 0x0,  // Key Length
+            0x41, // Key Data
+            0x0, 0x0, 0x0, 0x0, // End Dict
+            0x0, 0x0, 0x0, 0x0, // CRC
+        ];
+
+        img(input, false, false, DuplicateKeyPolicy::KeepLast).expect("Couldn't get img chunk");
+    }
+
+    #[test]
+    fn pale_parses_its_colors_and_trailing_dict() {
+        let input: &[u8] = &[
+            // Chunk Header
+            b'P', b'A', b'L', b'E', // Type
+            0xC, 0x0, 0x0, 0x0, // Size (12 bytes)
+            // Colors
+            0x1, 0x0, 0x0, 0x0, // Color count
+            10, 20, 30, 40, // One RGBA entry
+            // Dict
+            0x0, 0x0, 0x0, 0x0, // End Dict (no entries)
+            0x0, 0x0, 0x0, 0x0, // CRC
+        ];
+
+        let (_, chunk) =
+            pale(input, false, false, DuplicateKeyPolicy::KeepLast).expect("should parse PALE");
+        match chunk {
+            Chunk::Pale { colors, .. } => assert_eq!(colors, vec![[10, 20, 30, 40]]),
+            other => panic!("expected a Pale chunk, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_chunk_reads_one_standalone_bl16_chunk_and_returns_the_rest() {
+        let input: &[u8] = &[
+            b'B', b'L', b'1', b'6', // Type
+            0x3, 0x0, 0x0, 0x0, // Size
+            0xAA, 0xBB, 0xCC, // Data
+            0x0, 0x0, 0x0, 0x0, // CRC
+            0xFF, 0xFF, // trailing bytes after the chunk
+        ];
+
+        let (chunk, rest) = parse_chunk(input).expect("should parse a standalone BL16 chunk");
+        assert_eq!(chunk, Chunk::Bl16 { data: vec![0xAA, 0xBB, 0xCC] });
+        assert_eq!(rest, &[0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn parse_chunk_reports_a_truncated_chunk() {
+        let err = parse_chunk(b"BL16").expect_err("too short to be a chunk");
+        assert!(matches!(err, GoxError::TruncatedChunk { offset: 0 }));
+    }
+
+    #[test]
+    fn parse_verified_rejects_bad_crc() {
+        let input: &[u8] = &[
+            b'B', b'L', b'1', b'6', // Type
+            0x3, 0x0, 0x0, 0x0, // Size
+            0xAA, 0xBB, 0xCC, // Data
+            0x0, 0x0, 0x0, 0x0, // CRC (wrong)
+        ];
+
+        bl16(input, false).expect("lenient parse should ignore the bad CRC");
+        bl16(input, true).expect_err("verified parse should reject the bad CRC");
+    }
+
+    #[test]
+    fn parse_verified_accepts_correct_crc() {
+        let mut input: Vec<u8> = vec![
+            b'B', b'L', b'1', b'6', // Type
+            0x3, 0x0, 0x0, 0x0, // Size
+            0xAA, 0xBB, 0xCC, // Data
+        ];
+        let crc = crc32(&input);
+        input.extend_from_slice(&crc.to_le_bytes());
+
+        bl16(&input, true).expect("verified parse should accept a correct CRC");
+    }
+
+    #[test]
+    fn crc32_gox_matches_the_standard_crc_32_iso_hdlc_check_value() {
+        // The canonical "check" vector for CRC-32/ISO-HDLC (a.k.a. CRC-32,
+        // zlib's crc32, PKZIP's): CRC32(b"123456789") == 0xCBF43926. Goxel's
+        // `gox.c` uses this exact variant for each chunk's trailer.
+        assert_eq!(crc32_gox(b"123456789"), 0xCBF43926);
+        assert_eq!(crc32_gox(&[]), 0);
+        assert_eq!(crc32_gox(b"123456789"), crc32(b"123456789"));
+    }
+
+    #[test]
+    fn voxels_from_image_maps_slices_to_pixel_offsets() {
+        // A 64x64 image where every pixel encodes its own (x, y) position,
+        // so we can check each z-slice was read from the right 16x16 tile.
+        let mut rgba = vec![0u8; 64 * 64 * 4];
+        for py in 0..64usize {
+            for px in 0..64usize {
+                let i = (py * 64 + px) * 4;
+                rgba[i..i + 4].copy_from_slice(&[px as u8, py as u8, 0, 255]);
+            }
+        }
+        let image = png::Image {
+            width: 64,
+            height: 64,
+            rgba,
+        };
+
+        let voxels = voxels_from_image(&image, Bl16Layout::default());
+        #[allow(clippy::needless_range_loop)]
+        for z in 0..16usize {
+            let tile_x = (z % 4) * 16;
+            let tile_y = (z / 4) * 16;
+            assert_eq!(
+                voxels[3][5][z],
+                [(tile_x + 3) as u8, (tile_y + 5) as u8, 0, 255]
+            );
+        }
+    }
+
+    #[test]
+    fn encode_bl16_round_trips_a_cube_with_varied_voxels() {
+        let mut voxels: Voxels = [[[[0u8; 4]; 16]; 16]; 16];
+        for (x, plane) in voxels.iter_mut().enumerate() {
+            for (y, column) in plane.iter_mut().enumerate() {
+                for (z, voxel) in column.iter_mut().enumerate() {
+                    let seed = (x * 251 + y * 17 + z) as u8;
+                    *voxel = [seed, seed.wrapping_mul(3), seed.wrapping_mul(7), seed.wrapping_add(1)];
+                }
+            }
+        }
+
+        let encoded = encode_bl16(&voxels);
+        let decoded = decode_bl16(&encoded).expect("should decode what we just encoded");
+        assert_eq!(decoded, voxels);
+    }
+
+    #[test]
+    fn encode_bl16_round_trips_an_empty_cube() {
+        let voxels: Voxels = [[[[0u8; 4]; 16]; 16]; 16];
+
+        let encoded = encode_bl16(&voxels);
+        let decoded = decode_bl16(&encoded).expect("should decode an empty cube");
+        assert_eq!(decoded, voxels);
+    }
+
+    #[test]
+    fn decoding_under_the_two_bl16_layouts_gives_different_but_valid_cubes() {
+        let mut voxels: Voxels = [[[[0u8; 4]; 16]; 16]; 16];
+        for (x, plane) in voxels.iter_mut().enumerate() {
+            for (y, column) in plane.iter_mut().enumerate() {
+                for (z, voxel) in column.iter_mut().enumerate() {
+                    let seed = (x * 251 + y * 17 + z) as u8;
+                    *voxel = [seed, seed.wrapping_mul(3), seed.wrapping_mul(7), 255];
+                }
+            }
+        }
+        // Encoded with the row-major tile layout, same as `encode_bl16`.
+        let encoded = encode_bl16(&voxels);
+
+        let row_major = decode_bl16_with_layout(&encoded, Bl16Layout::RowMajor)
+            .expect("row-major decode should succeed");
+        let column_major = decode_bl16_with_layout(&encoded, Bl16Layout::ColumnMajor)
+            .expect("column-major decode should also succeed, just scrambled");
+
+        assert_eq!(row_major, voxels, "row-major matches how it was encoded");
+        assert_ne!(column_major, voxels, "column-major reads the tiles transposed");
+        assert_eq!(decode_bl16(&encoded).expect("default layout"), row_major);
+    }
+
+    #[test]
+    fn detect_bl16_layout_defaults_to_row_major_with_or_without_the_goxel_authoring_key() {
+        let native = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img {
+                dict: Dict::from(IndexMap::from([("goxel".to_string(), b"0.12.0".to_vec())])),
+            }],
+        };
+        assert_eq!(native.detect_bl16_layout(), Bl16Layout::default());
+
+        let missing_key = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img { dict: Dict::default() }],
+        };
+        assert_eq!(missing_key.detect_bl16_layout(), Bl16Layout::default());
+    }
+
+    #[test]
+    fn decode_bl16_rejects_a_wrong_sized_image() {
+        let png = include_bytes!("../tests/fixtures/fixed_huffman.png");
+
+        let err = decode_bl16(png).expect_err("a 3x2 PNG isn't a valid BL16 payload");
+        assert!(matches!(
+            err,
+            VoxelError::UnexpectedImageSize {
+                width: 3,
+                height: 2,
+            }
+        ));
+    }
+
+    #[test]
+    fn decode_bl16_reports_an_error_instead_of_panicking_on_garbage_bytes() {
+        // Nothing here resembles a PNG signature at all.
+        let garbage: Vec<u8> = (0..256u32).map(|b| b as u8).collect();
+        let err = decode_bl16(&garbage).expect_err("random bytes aren't a valid PNG");
+        assert!(matches!(err, VoxelError::Png(_)));
+    }
+
+    #[test]
+    fn decode_bl16_reports_an_error_instead_of_panicking_on_a_truncated_png() {
+        let png = include_bytes!("../tests/fixtures/solid_red_64.png");
+        let truncated = &png[..png.len() / 2];
+
+        let err = decode_bl16(truncated).expect_err("a half-written PNG shouldn't decode");
+        assert!(matches!(err, VoxelError::Png(_)));
+    }
+
+    #[test]
+    fn preview_is_none_without_a_prev_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![],
+        };
+        assert!(goxel.preview().is_none());
+    }
+
+    #[test]
+    fn preview_decodes_the_prev_chunks_png() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Prev {
+                data: include_bytes!("../tests/fixtures/fixed_huffman.png").to_vec(),
+            }],
+        };
+        let image = goxel
+            .preview()
+            .expect("a PREV chunk is present")
+            .expect("should decode a well-formed PNG");
+        assert_eq!((image.width(), image.height()), (3, 2));
+    }
+
+    #[test]
+    fn preview_surfaces_a_malformed_png_as_an_error() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Prev {
+                data: vec![0, 1, 2, 3],
+            }],
+        };
+        assert!(matches!(goxel.preview(), Some(Err(GoxError::Preview(_)))));
+    }
+
+    #[test]
+    fn thumbnail_scales_down_the_prev_chunk_when_present() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Prev {
+                data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+            }],
+        };
+        let image = goxel
+            .thumbnail(16)
+            .expect("a PREV chunk is present")
+            .expect("should decode and scale the PREV PNG");
+        assert_eq!((image.width(), image.height()), (16, 16));
+    }
+
+    #[test]
+    fn thumbnail_falls_back_to_a_front_view_render_without_a_prev_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let image = goxel
+            .thumbnail(64)
+            .expect("no PREV chunk, so the fallback render kicks in")
+            .expect("the model decodes cleanly");
+        assert!(image.width() > 0 && image.height() > 0);
+    }
+
+    #[test]
+    fn thumbnail_surfaces_a_fallback_decode_failure() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Layr {
+                blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                dict: Dict::new(),
+            }],
+        };
+
+        assert!(matches!(
+            goxel.thumbnail(64),
+            Some(Err(GoxError::Voxel(VoxelError::MissingBl16 { index: 0 })))
+        ));
+    }
+
+    #[test]
+    fn set_preview_round_trips_through_preview() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![],
+        };
+
+        let mut image = image::RgbaImage::new(2, 1);
+        image.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+        image.put_pixel(1, 0, image::Rgba([0, 255, 0, 255]));
+        goxel.set_preview(&image);
+
+        let round_tripped = goxel
+            .preview()
+            .expect("set_preview should have inserted a PREV chunk")
+            .expect("should decode the PNG we just encoded");
+        assert_eq!(round_tripped, image);
+    }
+
+    #[test]
+    fn set_preview_inserts_right_after_the_img_chunk() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Camr { dict: Dict::default() },
+            ],
+        };
+
+        goxel.set_preview(&image::RgbaImage::new(1, 1));
+
+        assert!(matches!(goxel.chunks()[0], Chunk::Img { .. }));
+        assert!(matches!(goxel.chunks()[1], Chunk::Prev { .. }));
+        assert!(matches!(goxel.chunks()[2], Chunk::Camr { .. }));
+    }
+
+    #[test]
+    fn set_preview_replaces_an_existing_prev_chunk_in_place() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Prev { data: vec![0, 1, 2, 3] },
+                Chunk::Camr { dict: Dict::default() },
+            ],
+        };
+
+        goxel.set_preview(&image::RgbaImage::new(1, 1));
+
+        assert_eq!(goxel.chunks().len(), 2);
+        assert!(matches!(goxel.chunks()[0], Chunk::Prev { .. }));
+    }
+
+    #[test]
+    fn as_voxels_only_decodes_bl16_chunks() {
+        let chunk = Chunk::Prev {
+            data: include_bytes!("../tests/fixtures/fixed_huffman.png").to_vec(),
+        };
+        assert!(chunk.as_voxels().is_none());
+
+        let chunk = Chunk::Bl16 {
+            data: include_bytes!("../tests/fixtures/fixed_huffman.png").to_vec(),
+        };
+        assert!(chunk.as_voxels().unwrap().is_err());
+    }
+
+    #[test]
+    fn decode_blocks_is_empty_without_any_bl16_chunks() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![],
+        };
+        assert_eq!(goxel.decode_blocks().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn decode_blocks_surfaces_the_first_decode_error() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Bl16 {
+                data: include_bytes!("../tests/fixtures/fixed_huffman.png").to_vec(),
+            }],
+        };
+        let err = goxel.decode_blocks().unwrap_err();
+        let VoxelError::BlockDecode { index, source } = err else {
+            panic!("expected a BlockDecode error, got {err}");
+        };
+        assert_eq!(index, 0);
+        assert!(matches!(
+            *source,
+            VoxelError::UnexpectedImageSize {
+                width: 3,
+                height: 2,
+            }
+        ));
+    }
+
+    #[test]
+    fn decode_blocks_names_the_index_of_the_corrupt_bl16_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/fixed_huffman.png").to_vec(),
+                },
+            ],
+        };
+        let err = goxel.decode_blocks().unwrap_err();
+        let VoxelError::BlockDecode { index, .. } = err else {
+            panic!("expected a BlockDecode error, got {err}");
+        };
+        assert_eq!(index, 1);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn decode_blocks_par_matches_decode_blocks_errors() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Bl16 {
+                data: include_bytes!("../tests/fixtures/fixed_huffman.png").to_vec(),
+            }],
+        };
+        let serial_err = goxel.decode_blocks().unwrap_err();
+        let parallel_err = goxel.decode_blocks_par().unwrap_err();
+        assert_eq!(format!("{serial_err}"), format!("{parallel_err}"));
+    }
+
+    #[test]
+    fn blocks_pairs_each_block_reference_with_its_decoded_cube() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block {
+                            index: 1,
+                            x: 2,
+                            y: 0,
+                            z: 0,
+                        },
+                        Block {
+                            index: 0,
+                            x: 0,
+                            y: 0,
+                            z: 0,
+                        },
+                    ],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let paired: Vec<_> = goxel.blocks().collect();
+        assert_eq!(paired.len(), 2);
+
+        let (block, decoded) = &paired[0];
+        assert_eq!(block.x, 2);
+        assert_eq!(decoded.as_ref().unwrap().index, 1);
+        assert_eq!(decoded.as_ref().unwrap().voxels[0][0][0], [0, 255, 0, 255]);
+
+        let (block, decoded) = &paired[1];
+        assert_eq!(block.x, 0);
+        assert_eq!(decoded.as_ref().unwrap().index, 0);
+        assert_eq!(decoded.as_ref().unwrap().voxels[0][0][0], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn blocks_reports_a_dangling_reference_without_failing_the_rest() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block {
+                            index: 0,
+                            x: 0,
+                            y: 0,
+                            z: 0,
+                        },
+                        Block {
+                            index: 5,
+                            x: 1,
+                            y: 0,
+                            z: 0,
+                        },
+                    ],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let paired: Vec<_> = goxel.blocks().collect();
+        assert!(paired[0].1.is_ok());
+        assert!(matches!(paired[1].1, Err(VoxelError::MissingBl16 { index: 5 })));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn goxel_round_trips_through_json() {
+        let mut dict = Dict::new();
+        dict.insert("name".to_string(), vec![0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict },
+                Chunk::Bl16 {
+                    data: vec![1, 2, 3, 4, 5],
+                },
+                Chunk::Unknown {
+                    tag: *b"TEST",
+                    data: vec![9, 9, 9],
+                },
+            ],
+        };
+
+        let json = serde_json::to_string(&goxel).expect("should serialize to JSON");
+        assert!(json.contains("3q2+7w==")); // base64 of 0xDE 0xAD 0xBE 0xEF
+        let round_tripped: Goxel =
+            serde_json::from_str(&json).expect("should deserialize back from JSON");
+
+        assert_eq!(round_tripped.to_bytes(), goxel.to_bytes());
+    }
+
+    #[test]
+    fn flatten_lets_a_later_visible_layer_win_at_the_intersection() {
+        let mut hidden = Dict::new();
+        hidden.insert("visible".to_string(), 0i32.to_le_bytes().to_vec());
+
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 1,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                // Invisible, and points at a BL16 chunk that doesn't exist;
+                // if this were decoded, it would fail.
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 5,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: hidden,
+                },
+            ],
+        };
+
+        let model = goxel.flatten().expect("invisible layer should be skipped, not decoded");
+        assert_eq!(model.voxel_at(0, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn flatten_sums_channels_at_the_intersection_when_the_later_layer_adds() {
+        let mut additive = Dict::new();
+        additive.insert("mode".to_string(), 1i32.to_le_bytes().to_vec());
+
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 1,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: additive,
+                },
+            ],
+        };
+
+        let model = goxel.flatten().expect("both layers should decode");
+        assert_eq!(model.voxel_at(0, 0, 0), Some([255, 255, 0, 255]));
+    }
+
+    #[test]
+    fn remove_layer_drops_its_orphaned_bl16_and_reindexes_the_rest() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([("name".to_string(), b"red".to_vec())])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([("name".to_string(), b"green".to_vec())])),
+                },
+            ],
+        };
+
+        goxel.remove_layer(0);
+
+        // The "red" layer and its now-unreferenced BL16 are both gone.
+        assert_eq!(goxel.chunks().len(), 2);
+        assert!(matches!(goxel.chunks()[0], Chunk::Bl16 { .. }));
+        let Chunk::Layr { blocks, dict } = &goxel.chunks()[1] else {
+            panic!("expected the remaining Layr chunk");
+        };
+        assert_eq!(dict.get_str("name"), Some("green"));
+        // Only one BL16 chunk survives, so the remaining block must now
+        // point at index 0 instead of 1.
+        assert_eq!(blocks[0].index, 0);
+
+        let model = goxel.flatten().expect("the remapped block should still decode");
+        assert_eq!(model.voxel_at(0, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn remove_layer_keeps_a_bl16_still_referenced_by_another_layer() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 1, y: 0, z: 0 }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        goxel.remove_layer(0);
+
+        assert_eq!(goxel.chunks().len(), 2);
+        assert!(matches!(goxel.chunks()[0], Chunk::Bl16 { .. }));
+        let Chunk::Layr { blocks, .. } = &goxel.chunks()[1] else {
+            panic!("expected the remaining Layr chunk");
+        };
+        assert_eq!(blocks[0].index, 0);
+    }
+
+    #[test]
+    fn orphaned_blocks_finds_a_bl16_chunk_no_layer_references() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 { data: vec![1, 1, 1] },
+                Chunk::Bl16 { data: vec![2, 2, 2] }, // never referenced
+                Chunk::Bl16 { data: vec![3, 3, 3] },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block { index: 0, x: 0, y: 0, z: 0 },
+                        Block { index: 2, x: 16, y: 0, z: 0 },
+                    ],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        assert_eq!(goxel.orphaned_blocks(), vec![1]);
+    }
+
+    #[test]
+    fn prune_orphaned_blocks_removes_them_and_fixes_up_indices() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: vec![9, 9, 9], // orphaned
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block { index: 0, x: 0, y: 0, z: 0 },
+                        Block { index: 2, x: 16, y: 0, z: 0 },
+                    ],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        goxel.prune_orphaned_blocks();
+
+        assert!(goxel.orphaned_blocks().is_empty());
+        let bl16_count = goxel.all(ChunkKind::Bl16).count();
+        assert_eq!(bl16_count, 2, "the orphaned BL16 chunk should be gone");
+
+        let model = goxel.flatten().expect("remapped blocks should still decode");
+        assert_eq!(model.voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(model.voxel_at(16, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn set_layer_visible_hides_a_layers_voxels_from_the_flattened_model() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            goxel.flatten().expect("layer starts visible").voxel_at(0, 0, 0),
+            Some([255, 0, 0, 255])
+        );
+
+        goxel.set_layer_visible(0, false);
+
+        assert_eq!(goxel.flatten().expect("layer now hidden").voxel_at(0, 0, 0), None);
+    }
+
+    #[test]
+    fn set_layer_name_renames_a_layer_and_survives_a_round_trip() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Layr {
+                blocks: vec![],
+                dict: layer_dict("original"),
+            }],
+        };
+
+        goxel.set_layer_name(0, "a much longer renamed layer");
+
+        let mut buf = Vec::new();
+        goxel.write(&mut buf).expect("writing to a Vec<u8> cannot fail");
+        let reparsed = parse(&buf).expect("should reparse the renamed layer");
+        let (view, _) = reparsed
+            .layer_by_name("a much longer renamed layer")
+            .expect("renamed layer should be found");
+        assert_eq!(view.name, "a much longer renamed layer");
+    }
+
+    #[test]
+    fn set_layer_name_does_nothing_for_an_out_of_range_index() {
+        let mut goxel = Goxel { version: 2, chunks: vec![] };
+        goxel.set_layer_name(0, "nope");
+        assert!(goxel.is_empty());
+    }
+
+    #[test]
+    fn frames_keeps_overlapping_layers_in_separate_uncomposited_models() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 1,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let frames = goxel.frames(false).expect("both layers should decode");
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(frames[1].voxel_at(0, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn frames_honor_visibility_toggle_includes_or_skips_hidden_layers() {
+        let mut hidden = Dict::new();
+        hidden.insert("visible".to_string(), 0i32.to_le_bytes().to_vec());
+
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: hidden,
+                },
+            ],
+        };
+
+        let honored = goxel.frames(true).expect("visible layer should still decode");
+        assert_eq!(honored.len(), 1);
+
+        let all = goxel.frames(false).expect("both layers should decode");
+        assert_eq!(all.len(), 2);
+    }
+
+    #[test]
+    fn voxel_count_sums_non_empty_voxels_across_overlapping_layers() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                // Overlaps the first layer's block entirely; voxel_count
+                // counts placements, so both blocks' voxels are tallied.
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 1,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let count = goxel.voxel_count().expect("both blocks should decode");
+        assert_eq!(count, 2 * 16 * 16 * 16);
+        assert_eq!(goxel.model().expect("should merge into one model").len(), 16 * 16 * 16);
+    }
+
+    #[test]
+    fn model_reports_coordinate_overflow_for_a_block_offset_near_i32_max() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: i32::MAX - 1,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let err = goxel.model().expect_err("block offset should overflow i32");
+        assert!(matches!(
+            err,
+            VoxelError::CoordinateOverflow { block_x, .. } if block_x == i32::MAX - 1
+        ));
+    }
+
+    #[test]
+    fn goxel_new_builds_an_empty_file_that_can_be_populated_and_round_tripped() {
+        let mut goxel = Goxel::new(2);
+        assert_eq!(goxel.version(), 2);
+        assert!(goxel.is_empty());
+        assert_eq!(goxel, Goxel::default());
+
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+        builder.add_layer("main");
+        goxel.chunks = builder.build().chunks;
+
+        let bytes = goxel.to_bytes();
+        let parsed = parse(&bytes).expect("should re-parse what we just wrote");
+        assert_eq!(parsed.model().unwrap().voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn model_with_options_drops_voxels_below_the_alpha_threshold() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+        builder.set_voxel(1, 0, 0, [255, 0, 0, 40]);
+        builder.add_layer("edges");
+        let goxel = builder.build();
+
+        let default = goxel.model().expect("should assemble with the default threshold");
+        assert_eq!(default.voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(default.voxel_at(1, 0, 0), Some([255, 0, 0, 40]));
+
+        let filtered = goxel
+            .model_with_options(ModelOptions { alpha_threshold: 100 })
+            .expect("should assemble with a raised threshold");
+        assert_eq!(filtered.voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(filtered.voxel_at(1, 0, 0), None);
+    }
+
+    #[test]
+    fn model_with_progress_calls_back_once_per_block_with_the_right_totals() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 1,
+                        x: 16,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::new(),
+                },
+            ],
+        };
+
+        let mut calls = Vec::new();
+        let model = goxel
+            .model_with_progress(|done, total| calls.push((done, total)))
+            .expect("both blocks should decode");
+
+        assert_eq!(calls, vec![(1, 2), (2, 2)]);
+        assert_eq!(model.voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(model.voxel_at(16, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn model_resolves_block_indices_even_when_layr_precedes_its_bl16() {
+        // `index` always counts BL16 chunks in file order regardless of
+        // where other chunks fall, so a LAYR referencing a BL16 chunk that
+        // comes later in the file still resolves correctly.
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 0, y: 0, z: 0 }],
+                    dict: Dict::new(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_green_64.png").to_vec(),
+                },
+            ],
+        };
+
+        let model = goxel.model().expect("should resolve the block despite the LAYR/BL16 order");
+        assert_eq!(model.voxel_at(0, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn stats_never_decodes_bl16_and_still_reports_chunk_counts() {
+        // No Bl16 chunks at all; if stats() tried to decode a block's
+        // voxels it would panic on the out-of-range index.
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Prev {
+                    data: vec![0, 1, 2, 3],
+                },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block {
+                            index: 0,
+                            x: 0,
+                            y: 0,
+                            z: 0,
+                        },
+                        Block {
+                            index: 1,
+                            x: 16,
+                            y: 0,
+                            z: 0,
+                        },
+                    ],
+                    dict: Dict::new(),
+                },
+                Chunk::Layr {
+                    blocks: vec![],
+                    dict: Dict::new(),
+                },
+                Chunk::Camr { dict: Dict::new() },
+                Chunk::Ligh { dict: Dict::new() },
+                Chunk::Mate { dict: Dict::new() },
+            ],
+        };
+
+        let stats = goxel.stats();
+        assert_eq!(stats.version, 2);
+        assert_eq!(stats.layer_count, 2);
+        assert_eq!(stats.camera_count, 1);
+        assert_eq!(stats.light_count, 1);
+        assert_eq!(stats.material_count, 1);
+        assert_eq!(stats.block_count, 2);
+        assert!(stats.has_preview);
+        assert_eq!(stats.max_voxel_count, 2 * 16 * 16 * 16);
+        assert_eq!(
+            stats.block_bounding_box,
+            Some(BoundingBox {
+                min: (0, 0, 0),
+                max: (31, 15, 15),
+            })
+        );
+    }
+
+    #[test]
+    fn stats_saturates_instead_of_overflowing_on_a_block_near_i32_max() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Layr {
+                blocks: vec![Block { index: 0, x: i32::MAX - 1, y: 0, z: 0 }],
+                dict: Dict::new(),
+            }],
+        };
+
+        let stats = goxel.stats();
+        assert_eq!(
+            stats.block_bounding_box,
+            Some(BoundingBox {
+                min: (i32::MAX - 1, 0, 0),
+                max: (i32::MAX, 15, 15),
+            })
+        );
+    }
+
+    #[test]
+    fn stats_reports_no_bounding_box_without_any_layers() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![],
+        };
+
+        assert_eq!(goxel.stats().block_bounding_box, None);
+    }
+
+    #[test]
+    fn model_with_transforms_translates_voxels_by_the_layer_mat() {
+        let mut mat = identity_mat();
+        mat[12] = 10.0; // translate +10 along x
+
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::from(IndexMap::from([("mat".to_string(), mat_bytes(mat))])),
+                },
+            ],
+        };
+
+        let model = goxel
+            .model_with_transforms()
+            .expect("should decode the one real block");
+        assert_eq!(model.voxel_at(0, 0, 0), None);
+        assert_eq!(model.voxel_at(10, 0, 0), Some([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn model_with_transforms_skips_the_float_round_trip_for_identity_mat() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::from(IndexMap::from([(
+                        "mat".to_string(),
+                        mat_bytes(identity_mat()),
+                    )])),
+                },
+            ],
+        };
+
+        let model = goxel
+            .model_with_transforms()
+            .expect("should decode the one real block");
+        assert_eq!(model, goxel.model().expect("should decode the same block"));
+    }
+
+    #[test]
+    fn model_merges_empty_layers_into_an_empty_map() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Layr {
+                    blocks: vec![],
+                    dict: Dict::default(),
+                },
+                Chunk::Layr {
+                    blocks: vec![],
+                    dict: Dict::default(),
+                },
+            ],
+        };
+
+        let model = goxel.model().expect("no blocks means no voxels to decode");
+        assert!(model.is_empty());
+    }
+
+    #[test]
+    fn layer_by_name_finds_the_matching_layer_and_its_blocks() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"visual".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 16, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"collision".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+            ],
+        };
+
+        let (view, blocks) = goxel
+            .layer_by_name("collision")
+            .expect("should find the collision layer");
+        assert_eq!(view.name, "collision");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!((blocks[0].x, blocks[0].y, blocks[0].z), (16, 0, 0));
+
+        assert!(goxel.layer_by_name("nonexistent").is_none());
+    }
+
+    #[test]
+    fn layer_by_name_returns_the_first_match_when_names_collide() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"dup".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 32, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"dup".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+            ],
+        };
+
+        let (_, blocks) = goxel.layer_by_name("dup").expect("should find a dup layer");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!((blocks[0].x, blocks[0].y, blocks[0].z), (0, 0, 0));
+    }
+
+    #[test]
+    fn layer_block_indices_lists_each_layers_referenced_blocks_in_order() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Layr {
+                    blocks: vec![
+                        Block { index: 0, x: 0, y: 0, z: 0 },
+                        Block { index: 1, x: 16, y: 0, z: 0 },
+                    ],
+                    dict: Dict::default(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 16, y: 0, z: 0 }],
+                    dict: Dict::default(),
+                },
+            ],
+        };
+
+        assert_eq!(goxel.layer_block_indices(), vec![vec![0, 1], vec![1]]);
+    }
+
+    #[test]
+    fn export_layer_selects_by_index_or_name() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"visual".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 16, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"collision".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+            ],
+        };
+
+        let by_index = goxel
+            .export_layer(LayerSelector::ByIndex(1))
+            .expect("index 1 is the collision layer");
+        let by_name = goxel
+            .export_layer(LayerSelector::ByName("collision".to_string()))
+            .expect("should find the collision layer by name");
+        assert_eq!(by_index, by_name);
+
+        let bounds = by_index.bounding_box().expect("the collision layer has voxels");
+        assert!(bounds.min.0 >= 16, "collision layer should start at its block's x offset");
+
+        let visual = goxel
+            .export_layer(LayerSelector::ByIndex(0))
+            .expect("index 0 is the visual layer");
+        assert_ne!(visual, by_index);
+
+        assert!(matches!(
+            goxel.export_layer(LayerSelector::ByIndex(5)),
+            Err(GoxError::UnknownLayer { selector: LayerSelector::ByIndex(5) })
+        ));
+        assert!(matches!(
+            goxel.export_layer(LayerSelector::ByName("nonexistent".to_string())),
+            Err(GoxError::UnknownLayer { selector: LayerSelector::ByName(name) }) if name == "nonexistent"
+        ));
+    }
+
+    #[test]
+    fn layer_bounds_pairs_each_layer_view_with_its_own_bounding_box() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"solid".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+                Chunk::Layr {
+                    blocks: vec![],
+                    dict: Dict::from(IndexMap::from([
+                        ("name".to_string(), b"empty".to_vec()),
+                        ("mat".to_string(), mat_bytes(identity_mat())),
+                    ])),
+                },
+            ],
+        };
+
+        let bounds = goxel
+            .layer_bounds()
+            .expect("should decode both layers' bounds");
+
+        assert_eq!(bounds.len(), 2);
+        assert_eq!(bounds[0].0.name, "solid");
+        assert_eq!(
+            bounds[0].1,
+            Some(BoundingBox {
+                min: (0, 0, 0),
+                max: (15, 15, 15),
+            })
+        );
+        assert_eq!(bounds[1].0.name, "empty");
+        assert_eq!(bounds[1].1, None);
+    }
+
+    #[test]
+    fn voxel_material_reports_the_placing_layers_material_index() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([(
+                        "material".to_string(),
+                        3i32.to_le_bytes().to_vec(),
+                    )])),
+                },
+            ],
+        };
+
+        let model = goxel.model().expect("should decode the one real block");
+        assert_eq!(model.voxel_material(0, 0, 0), Some(3));
+        assert_eq!(model.voxel_material(100, 100, 100), None);
+    }
+
+    #[test]
+    fn voxel_material_is_none_without_a_material_dict_entry() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::default(),
+                },
+            ],
+        };
+
+        let model = goxel.model().expect("should decode the one real block");
+        assert_eq!(model.voxel_material(0, 0, 0), None);
+    }
+
+    #[test]
+    fn voxel_material_reports_the_winning_layers_material_when_layers_overlap() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Bl16 {
+                    data: include_bytes!("../tests/fixtures/solid_red_64.png").to_vec(),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([(
+                        "material".to_string(),
+                        1i32.to_le_bytes().to_vec(),
+                    )])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block { index: 1, x: 0, y: 0, z: 0 }],
+                    dict: Dict::from(IndexMap::from([(
+                        "material".to_string(),
+                        2i32.to_le_bytes().to_vec(),
+                    )])),
+                },
+            ],
+        };
+
+        let model = goxel.model().expect("should decode both overlapping blocks");
+        assert_eq!(model.voxel_material(0, 0, 0), Some(2));
+    }
+
+    #[test]
+    fn neighbors_are_all_occupied_inside_a_solid_block() {
+        let mut model = Model::default();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    model.extend([((x, y, z), [255, 0, 0, 255])]);
+                }
+            }
+        }
+
+        assert_eq!(model.neighbors([1, 1, 1]), [true; 6]);
+        assert_eq!(model.neighbors26([1, 1, 1]), [true; 26]);
+    }
+
+    #[test]
+    fn neighbors_are_all_empty_around_a_lone_voxel() {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        assert_eq!(model.neighbors([0, 0, 0]), [false; 6]);
+        assert_eq!(model.neighbors26([0, 0, 0]), [false; 26]);
+    }
+
+    #[test]
+    fn srgb_to_linear_and_back_round_trips_every_byte_value() {
+        for value in 0..=255u8 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(value)), value);
+        }
+    }
+
+    #[test]
+    fn srgb_to_linear_maps_black_and_white_to_their_endpoints() {
+        assert_eq!(srgb_to_linear(0), 0.0);
+        assert_eq!(srgb_to_linear(255), 1.0);
+    }
+
+    #[test]
+    fn to_linear_converts_colors_and_leaves_alpha_linear() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [255, 255, 255, 128])]);
+
+        let linear = model.to_linear();
+        let (_, [r, g, b, a]) = linear.iter().next().expect("model has one voxel");
+        assert_eq!((r, g, b), (1.0, 1.0, 1.0));
+        assert!((a - 128.0 / 255.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn to_linear_then_to_srgb_round_trips_a_model() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [10, 20, 30, 128]),
+        ]);
+
+        assert_eq!(model.to_linear().to_srgb(), model);
+    }
+
+    #[test]
+    fn voxel_at_looks_up_by_world_coordinate() {
+        let mut model = Model::new();
+        model.extend([((1, 2, 3), [255, 0, 0, 255])]);
+
+        assert_eq!(model.voxel_at(1, 2, 3), Some([255, 0, 0, 255]));
+        assert_eq!(model.voxel_at(0, 0, 0), None);
+    }
+
+    #[test]
+    fn from_iter_collects_positioned_colors_and_lets_later_ones_win_ties() {
+        let model: Model = [
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+            ((0, 0, 0), [0, 0, 255, 255]),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(model.voxel_at(0, 0, 0), Some([0, 0, 255, 255]));
+        assert_eq!(model.voxel_at(1, 0, 0), Some([0, 255, 0, 255]));
+        assert_eq!(model.len(), 2);
+    }
+
+    #[test]
+    fn iter_visits_every_occupied_voxel_exactly_once() {
+        let mut model = Model::new();
+        model.extend([
+            ((1, 2, 3), [255, 0, 0, 255]),
+            ((-1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        assert_eq!(model.len(), 2);
+        let mut voxels: Vec<_> = model.iter().collect();
+        voxels.sort_by_key(|&(pos, _)| pos);
+        assert_eq!(
+            voxels,
+            vec![
+                ([-1, 0, 0], [0, 255, 0, 255]),
+                ([1, 2, 3], [255, 0, 0, 255]),
+            ]
+        );
+    }
+
+    #[test]
+    fn any_in_box_detects_an_occupied_voxel_inside_the_region() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((10, 10, 10), [0, 255, 0, 255]),
+        ]);
+
+        assert!(model.any_in_box([-1, -1, -1], [1, 1, 1]));
+        assert!(!model.any_in_box([5, 5, 5], [9, 9, 9]));
+    }
+
+    #[test]
+    fn voxels_in_box_yields_only_occupied_voxels_within_the_region() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 1, 1), [0, 255, 0, 255]),
+            ((10, 10, 10), [0, 0, 255, 255]),
+        ]);
+
+        let mut found: Vec<_> = model.voxels_in_box([0, 0, 0], [1, 1, 1]).collect();
+        found.sort_by_key(|&(pos, _)| pos);
+        assert_eq!(
+            found,
+            vec![
+                ([0, 0, 0], [255, 0, 0, 255]),
+                ([1, 1, 1], [0, 255, 0, 255]),
+            ]
+        );
+    }
+
+    #[test]
+    fn bounding_box_spans_occupied_voxels() {
+        let mut model = Model::new();
+        model.extend([
+            ((1, 2, 3), [255, 0, 0, 255]),
+            ((-1, 5, 0), [0, 255, 0, 255]),
+            ((4, -2, 8), [0, 0, 255, 255]),
+        ]);
+
+        let bbox = model.bounding_box().expect("model is non-empty");
+        assert_eq!(bbox.min, (-1, -2, 0));
+        assert_eq!(bbox.max, (4, 5, 8));
+    }
+
+    #[test]
+    fn bounding_box_is_none_for_an_empty_model() {
+        assert_eq!(Model::new().bounding_box(), None);
+    }
+
+    #[test]
+    fn bounding_sphere_reaches_every_corner_of_a_rectangular_model() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((4, 0, 0), [0, 255, 0, 255]),
+            ((0, 3, 0), [0, 0, 255, 255]),
+        ]);
+
+        let (center, radius) = model.bounding_sphere().expect("model is non-empty");
+        assert_eq!(center, [2.0, 1.5, 0.0]);
+        assert_eq!(radius, 2.5);
+    }
+
+    #[test]
+    fn bounding_sphere_does_not_overflow_on_coordinates_near_i32_max() {
+        let mut model = Model::new();
+        model.extend([
+            ((i32::MAX - 1, 0, 0), [255, 0, 0, 255]),
+            ((i32::MAX, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let (center, _) = model.bounding_sphere().expect("model is non-empty");
+        assert_eq!(center[0], i32::MAX as f32 - 0.5);
+    }
+
+    #[test]
+    fn bounding_sphere_is_none_for_an_empty_model() {
+        assert_eq!(Model::new().bounding_sphere(), None);
+    }
+
+    #[test]
+    fn centroid_of_a_symmetric_model_lands_at_the_center() {
+        let mut model = Model::new();
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    model.extend([((x, y, z), [255, 0, 0, 255])]);
+                }
+            }
+        }
+
+        assert_eq!(model.centroid(), Some([0.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn centroid_is_none_for_an_empty_model() {
+        assert_eq!(Model::new().centroid(), None);
+    }
+
+    #[test]
+    fn alpha_weighted_centroid_pulls_toward_more_opaque_voxels() {
+        let mut model = Model::new();
+        model.extend([
+            ((-10, 0, 0), [255, 0, 0, 32]),
+            ((10, 0, 0), [255, 0, 0, 255]),
+        ]);
+
+        let centroid = model.alpha_weighted_centroid().expect("model is non-empty");
+        assert!(centroid[0] > 0.0, "the more opaque voxel should pull the centroid toward it");
+    }
+
+    #[test]
+    fn alpha_weighted_centroid_is_none_when_every_voxel_is_fully_transparent() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [255, 0, 0, 0])]);
+        assert_eq!(model.alpha_weighted_centroid(), None);
+    }
+
+    #[test]
+    fn cropped_shifts_the_bounding_box_to_the_origin() {
+        let mut model = Model::new();
+        model.extend([
+            ((5, -2, 3), [255, 0, 0, 255]),
+            ((8, 4, 6), [0, 255, 0, 255]),
+        ]);
+
+        let (cropped, offset) = model.cropped();
+        assert_eq!(offset, (5, -2, 3));
+        let bbox = cropped.bounding_box().expect("cropped model is non-empty");
+        assert_eq!(bbox.min, (0, 0, 0));
+        assert_eq!(bbox.max, (3, 6, 3));
+
+        // Undoing the offset restores the original voxels.
+        let mut restored = Model::new();
+        restored.extend(
+            cropped
+                .iter()
+                .map(|([x, y, z], rgba)| ((x + offset.0, y + offset.1, z + offset.2), rgba)),
+        );
+        assert_eq!(restored, model);
+    }
+
+    #[test]
+    fn translated_shifts_voxels_and_bounds_by_the_offset() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 2, 3), [0, 255, 0, 255]),
+        ]);
+        let original_bbox = model.bounding_box().expect("non-empty model");
+
+        let translated = model.translated([10, -5, 2]);
+        assert_eq!(translated.voxel_at(10, -5, 2), Some([255, 0, 0, 255]));
+        assert_eq!(translated.voxel_at(11, -3, 5), Some([0, 255, 0, 255]));
+
+        let bbox = translated.bounding_box().expect("translated model is non-empty");
+        assert_eq!(
+            bbox.min,
+            (original_bbox.min.0 + 10, original_bbox.min.1 - 5, original_bbox.min.2 + 2)
+        );
+        assert_eq!(
+            bbox.max,
+            (original_bbox.max.0 + 10, original_bbox.max.1 - 5, original_bbox.max.2 + 2)
+        );
+    }
+
+    #[test]
+    fn translated_by_zero_offset_is_unchanged() {
+        let mut model = Model::new();
+        model.extend([((3, 4, 5), [255, 0, 0, 255])]);
+        assert_eq!(model.translated([0, 0, 0]), model);
+    }
+
+    #[test]
+    fn mirror_swaps_the_min_and_max_corners() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+            ((3, 4, 5), [0, 0, 255, 255]),
+        ]);
+
+        let mirrored = model.mirror(Axis::X);
+        assert_eq!(mirrored.voxel_at(3, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(mirrored.voxel_at(0, 4, 5), Some([0, 0, 255, 255]));
+        assert_eq!(mirrored.len(), model.len());
+    }
+
+    #[test]
+    fn mirroring_an_odd_width_model_keeps_it_centered() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+            ((2, 0, 0), [0, 0, 255, 255]),
+        ]);
+
+        let mirrored = model.mirror(Axis::X);
+        assert_eq!(mirrored.voxel_at(0, 0, 0), Some([0, 0, 255, 255]));
+        assert_eq!(mirrored.voxel_at(1, 0, 0), Some([0, 255, 0, 255]));
+        assert_eq!(mirrored.voxel_at(2, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(mirrored.len(), 3);
+    }
+
+    #[test]
+    fn rotate90_around_z_maps_x_y_to_minus_y_x() {
+        let mut model = Model::new();
+        model.extend([
+            ((10, 10, 0), [255, 0, 0, 255]),
+            ((11, 10, 0), [0, 255, 0, 255]),
+            ((10, 11, 0), [0, 0, 255, 255]),
+        ]);
+
+        let rotated = model.rotate90(Axis::Z, 1);
+        assert_eq!(rotated.voxel_at(11, 10, 0), Some([255, 0, 0, 255]));
+        assert_eq!(rotated.voxel_at(11, 11, 0), Some([0, 255, 0, 255]));
+        assert_eq!(rotated.voxel_at(10, 10, 0), Some([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn four_quarter_turns_compose_back_to_identity() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+            ((0, 1, 0), [0, 0, 255, 255]),
+        ]);
+
+        for axis in [Axis::X, Axis::Y, Axis::Z] {
+            let mut rotated = model.clone();
+            for _ in 0..4 {
+                rotated = rotated.rotate90(axis, 1);
+            }
+            assert_eq!(rotated, model);
+        }
+    }
+
+    #[test]
+    fn rotate90_normalizes_quarter_turns_modulo_4() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [255, 0, 0, 255]), ((2, 1, 0), [0, 255, 0, 255])]);
+
+        assert_eq!(model.rotate90(Axis::Z, 1), model.rotate90(Axis::Z, 5));
+    }
+
+    #[test]
+    fn downsample_half_halves_a_solid_block_keeping_its_color() {
+        let mut model = Model::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    model.extend([((x, y, z), [100, 150, 200, 255])]);
+                }
+            }
+        }
+
+        let downsampled = model.downsample_half();
+        assert_eq!(downsampled.len(), 8);
+        for x in 0..2 {
+            for y in 0..2 {
+                for z in 0..2 {
+                    assert_eq!(downsampled.voxel_at(x, y, z), Some([100, 150, 200, 255]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn downsample_half_averages_colors_and_tracks_coverage() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [0, 0, 0, 255]),
+            ((1, 0, 0), [100, 0, 0, 255]),
+        ]);
+
+        let downsampled = model.downsample_half();
+        assert_eq!(downsampled.len(), 1);
+        // 2 of the cell's 8 sub-voxels are occupied: coverage = 2/8*255 ≈ 64.
+        assert_eq!(downsampled.voxel_at(0, 0, 0), Some([50, 0, 0, 64]));
+    }
+
+    #[test]
+    fn downsample_half_ignores_transparent_voxels_in_the_average() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [100, 0, 0, 255]),
+            ((1, 0, 0), [0, 0, 0, 0]),
+        ]);
+
+        let downsampled = model.downsample_half();
+        assert_eq!(downsampled.voxel_at(0, 0, 0), Some([100, 0, 0, 32]));
+    }
+
+    #[test]
+    fn upsample_by_2_produces_8x_the_voxel_count_at_correct_positions() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let upsampled = model.upsample(2);
+        assert_eq!(upsampled.len(), model.len() * 8);
+
+        for dx in 0..2 {
+            for dy in 0..2 {
+                for dz in 0..2 {
+                    assert_eq!(upsampled.voxel_at(dx, dy, dz), Some([255, 0, 0, 255]));
+                    assert_eq!(upsampled.voxel_at(2 + dx, dy, dz), Some([0, 255, 0, 255]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn upsample_by_0_or_1_leaves_the_model_unchanged() {
+        let mut model = Model::new();
+        model.extend([((3, -1, 4), [10, 20, 30, 255])]);
+
+        assert_eq!(model.upsample(0), model);
+        assert_eq!(model.upsample(1), model);
+    }
+
+    #[test]
+    fn upsample_clamps_a_runaway_factor() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        assert_eq!(model.upsample(1_000_000), model.upsample(MAX_UPSAMPLE_FACTOR));
+    }
+
+    #[test]
+    fn slice_z_renders_only_the_voxels_at_that_plane() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 5), [0, 255, 0, 255]),
+        ]);
+
+        let slice = model.slice_z(0);
+        // Sized to the model's overall XY bounds (x: 0..=1, y: 0..=0).
+        assert_eq!(slice.dimensions(), (2, 1));
+        assert_eq!(*slice.get_pixel(0, 0), image::Rgba([255, 0, 0, 255]));
+        assert_eq!(*slice.get_pixel(1, 0), image::Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn slice_z_of_an_empty_model_is_a_zero_sized_image() {
+        assert_eq!(Model::new().slice_z(0).dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn to_png_stack_has_one_image_per_z_layer_matching_slice_z() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 5), [0, 255, 0, 255]),
+        ]);
+
+        let stack = model.to_png_stack();
+        // z ranges 0..=5 across the model's bounds.
+        assert_eq!(stack.len(), 6);
+        assert_eq!(stack[0], model.slice_z(0));
+        assert_eq!(stack[5], model.slice_z(5));
+        assert_eq!(*stack[5].get_pixel(1, 0), image::Rgba([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn to_png_stack_of_an_empty_model_is_empty() {
+        assert!(Model::new().to_png_stack().is_empty());
+    }
+
+    #[test]
+    fn slice_x_and_slice_y_render_their_respective_planes() {
+        let mut model = Model::new();
+        model.extend([((2, 3, 4), [10, 20, 30, 255])]);
+
+        let x_slice = model.slice_x(2);
+        assert_eq!(x_slice.dimensions(), (1, 1));
+        assert_eq!(*x_slice.get_pixel(0, 0), image::Rgba([10, 20, 30, 255]));
+
+        let y_slice = model.slice_y(3);
+        assert_eq!(y_slice.dimensions(), (1, 1));
+        assert_eq!(*y_slice.get_pixel(0, 0), image::Rgba([10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn ascii_slice_draws_occupied_cells_as_hashes() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [255, 0, 0, 255]),
+            ((1, 1, 0), [255, 0, 0, 255]),
+            ((1, 1, 5), [0, 255, 0, 255]), // a different z plane, shouldn't show up
+        ]);
+
+        assert_eq!(model.ascii_slice(0), "# #\n # ");
+    }
+
+    #[test]
+    fn ascii_slice_of_an_empty_model_is_an_empty_string() {
+        assert_eq!(Model::new().ascii_slice(0), "");
+    }
+
+    #[test]
+    fn render_ortho_centers_a_single_voxels_color_for_every_view_direction() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [10, 20, 30, 255])]);
+
+        for view in [ViewDir::Front, ViewDir::Top, ViewDir::Side] {
+            let image = model.render_ortho(view, 8);
+            assert_eq!((image.width(), image.height()), (8, 8));
+            assert_eq!(*image.get_pixel(4, 4), image::Rgba([10, 20, 30, 255]));
+        }
+    }
+
+    #[test]
+    fn render_ortho_of_an_empty_model_is_blank_but_correctly_sized() {
+        let image = Model::new().render_ortho(ViewDir::Front, 4);
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(*image.get_pixel(0, 0), image::Rgba([0, 0, 0, 0]));
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn to_ndarray_is_sized_to_the_bounding_box_and_zero_filled_elsewhere() {
+        let mut model = Model::new();
+        model.extend([
+            ((2, 3, 4), [10, 20, 30, 255]),
+            ((3, 3, 5), [40, 50, 60, 255]),
+        ]);
+
+        let (array, offset) = model.to_ndarray();
+        assert_eq!(offset, (2, 3, 4));
+        // width=2 (x), height=1 (y), depth=2 (z), 4 channels.
+        assert_eq!(array.shape(), &[2, 1, 2, 4]);
+        assert_eq!(&array.slice(ndarray::s![0, 0, 0, ..]).to_vec(), &[10, 20, 30, 255]);
+        assert_eq!(&array.slice(ndarray::s![1, 0, 1, ..]).to_vec(), &[40, 50, 60, 255]);
+        assert_eq!(&array.slice(ndarray::s![0, 0, 1, ..]).to_vec(), &[0, 0, 0, 0]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn to_ndarray_of_an_empty_model_is_zero_sized_with_a_zero_offset() {
+        let model = Model::new();
+        let (array, offset) = model.to_ndarray();
+        assert_eq!(offset, (0, 0, 0));
+        assert_eq!(array.shape(), &[0, 0, 0, 4]);
+    }
+
+    #[cfg(feature = "block-mesh")]
+    #[test]
+    fn to_block_mesh_buffer_produces_quads_for_a_solid_cube() {
+        let mut model = Model::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    model.extend([((x, y, z), [200, 100, 50, 255])]);
+                }
+            }
+        }
+
+        let (buffer, _, offset) = model.to_block_mesh_buffer();
+        assert_eq!(offset, (-1, -1, -1));
+        // A solid cube has 6 faces, each a single merged quad.
+        assert_eq!(buffer.quads.num_quads(), 6);
+    }
+
+    #[cfg(feature = "block-mesh")]
+    #[test]
+    fn to_block_mesh_buffer_of_an_empty_model_has_no_quads() {
+        let model = Model::new();
+        let (buffer, _, offset) = model.to_block_mesh_buffer();
+        assert_eq!(offset, (0, 0, 0));
+        assert_eq!(buffer.quads.num_quads(), 0);
+    }
+
+    #[test]
+    fn occupancy_matches_the_sparse_model_exactly() {
+        let mut model = Model::new();
+        model.extend([
+            ((-2, 5, 1), [255, 0, 0, 255]),
+            ((0, 7, 1), [0, 255, 0, 255]),
+            ((1, 5, 3), [0, 0, 255, 255]),
+        ]);
+
+        let (occupancy, offset) = model.occupancy();
+        let bbox = model.bounding_box().unwrap();
+        assert_eq!(offset, [bbox.min.0, bbox.min.1, bbox.min.2]);
+
+        for x in bbox.min.0..=bbox.max.0 {
+            for y in bbox.min.1..=bbox.max.1 {
+                for z in bbox.min.2..=bbox.max.2 {
+                    let expected = model.voxel_at(x, y, z).is_some();
+                    let actual = occupancy.get(x - offset[0], y - offset[1], z - offset[2]);
+                    assert_eq!(actual, expected, "mismatch at ({x}, {y}, {z})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn occupancy_treats_out_of_range_coordinates_as_unoccupied() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        let (occupancy, _) = model.occupancy();
+        assert!(!occupancy.get(-1, 0, 0));
+        assert!(!occupancy.get(0, 0, 1));
+    }
+
+    #[test]
+    fn occupancy_of_an_empty_model_is_empty_with_a_zero_offset() {
+        let model = Model::new();
+        let (occupancy, offset) = model.occupancy();
+        assert_eq!(offset, [0, 0, 0]);
+        assert!(!occupancy.get(0, 0, 0));
+    }
+
+    #[test]
+    fn shell_only_keeps_only_the_outer_layer_of_a_solid_cube() {
+        let mut model = Model::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    model.extend([((x, y, z), [200, 100, 50, 255])]);
+                }
+            }
+        }
+
+        let shell = model.shell_only();
+        // The 2x2x2 interior core (coordinates 1..3 on every axis) is the
+        // only part with no exposed face.
+        assert_eq!(shell.len(), 4 * 4 * 4 - 2 * 2 * 2);
+        for x in 1..3 {
+            for y in 1..3 {
+                for z in 1..3 {
+                    assert_eq!(shell.voxel_at(x, y, z), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn volume_and_surface_area_of_a_solid_cube_match_the_known_formulas() {
+        const N: i32 = 4;
+        let mut model = Model::new();
+        for x in 0..N {
+            for y in 0..N {
+                for z in 0..N {
+                    model.extend([((x, y, z), [200, 100, 50, 255])]);
+                }
+            }
+        }
+
+        assert_eq!(model.volume(), (N * N * N) as usize);
+        assert_eq!(model.surface_area(), (6 * N * N) as usize);
+    }
+
+    #[test]
+    fn volume_and_surface_area_of_an_empty_model_are_zero() {
+        let model = Model::new();
+        assert_eq!(model.volume(), 0);
+        assert_eq!(model.surface_area(), 0);
+    }
+
+    #[test]
+    fn merge_keep_self_ignores_the_incoming_voxel_at_a_conflict() {
+        let mut a = Model::new();
+        a.extend([((0, 0, 0), [255, 0, 0, 255])]);
+        let mut b = Model::new();
+        b.extend([((0, 0, 0), [0, 255, 0, 255]), ((1, 0, 0), [0, 0, 255, 255])]);
+
+        a.merge(&b, MergePolicy::KeepSelf).expect("KeepSelf never errors");
+        assert_eq!(a.voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(a.voxel_at(1, 0, 0), Some([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn merge_take_other_overwrites_the_conflicting_voxel() {
+        let mut a = Model::new();
+        a.extend([((0, 0, 0), [255, 0, 0, 255])]);
+        let mut b = Model::new();
+        b.extend([((0, 0, 0), [0, 255, 0, 255])]);
+
+        a.merge(&b, MergePolicy::TakeOther).expect("TakeOther never errors");
+        assert_eq!(a.voxel_at(0, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn merge_error_fails_without_mutating_self_on_conflict() {
+        let mut a = Model::new();
+        a.extend([((0, 0, 0), [255, 0, 0, 255])]);
+        let mut b = Model::new();
+        b.extend([((0, 0, 0), [0, 255, 0, 255]), ((1, 0, 0), [0, 0, 255, 255])]);
+
+        let err = a.merge(&b, MergePolicy::Error).expect_err("should report the conflict");
+        assert_eq!(err.position, (0, 0, 0));
+        assert_eq!(a.len(), 1); // untouched, even the non-conflicting voxel
+        assert_eq!(a.voxel_at(1, 0, 0), None);
+    }
+
+    #[test]
+    fn merge_error_succeeds_when_there_is_no_overlap() {
+        let mut a = Model::new();
+        a.extend([((0, 0, 0), [255, 0, 0, 255])]);
+        let mut b = Model::new();
+        b.extend([((1, 0, 0), [0, 255, 0, 255])]);
+
+        a.merge(&b, MergePolicy::Error).expect("disjoint models never conflict");
+        assert_eq!(a.len(), 2);
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_voxels() {
+        let mut before = Model::new();
+        before.extend([
+            ((0, 0, 0), [255, 0, 0, 255]), // unchanged
+            ((1, 0, 0), [0, 255, 0, 255]), // removed
+            ((2, 0, 0), [0, 0, 255, 255]), // recolored
+        ]);
+
+        let mut after = Model::new();
+        after.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),   // unchanged
+            ((2, 0, 0), [0, 0, 200, 255]),   // recolored
+            ((3, 0, 0), [255, 255, 0, 255]), // added
+        ]);
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, vec![((3, 0, 0), [255, 255, 0, 255])]);
+        assert_eq!(diff.removed, vec![((1, 0, 0), [0, 255, 0, 255])]);
+        assert_eq!(
+            diff.changed,
+            vec![ChangedVoxel {
+                position: (2, 0, 0),
+                old: [0, 0, 255, 255],
+                new: [0, 0, 200, 255],
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_of_identical_models_is_empty() {
+        let mut model = Model::new();
+        model.extend([((0, 0, 0), [255, 0, 0, 255])]);
+
+        let diff = model.diff(&model.clone());
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn shell_only_keeps_every_voxel_of_a_single_layer_plane() {
+        let mut model = Model::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                model.extend([((x, y, 0), [0, 0, 255, 255])]);
+            }
+        }
+
+        // Every voxel is exposed on the front/back face since the plane
+        // is only one voxel thick, so none get dropped.
+        let shell = model.shell_only();
+        assert_eq!(shell.len(), model.len());
+    }
+
+    fn cube(model: &mut Model, origin: (i32, i32, i32), rgba: [u8; 4]) {
+        for x in 0..2 {
+            for y in 0..2 {
+                for z in 0..2 {
+                    model.extend([((origin.0 + x, origin.1 + y, origin.2 + z), rgba)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn components_splits_two_separated_cubes() {
+        let mut model = Model::new();
+        cube(&mut model, (0, 0, 0), [255, 0, 0, 255]);
+        cube(&mut model, (10, 10, 10), [0, 255, 0, 255]);
+
+        let components = model.components(Connectivity::Six);
+        assert_eq!(components.len(), 2);
+        assert!(components.iter().all(|c| c.len() == 8));
+    }
+
+    #[test]
+    fn components_with_twenty_six_connectivity_merges_diagonal_cubes() {
+        // These two cubes touch only at the single corner (2, 2, 2) /
+        // (1, 1, 1) boundary, so they share no face but do share a corner.
+        let mut model = Model::new();
+        cube(&mut model, (0, 0, 0), [255, 0, 0, 255]);
+        cube(&mut model, (2, 2, 2), [0, 255, 0, 255]);
+
+        assert_eq!(model.components(Connectivity::Six).len(), 2);
+        assert_eq!(model.components(Connectivity::TwentySix).len(), 1);
+    }
+
+    #[test]
+    fn palette_excludes_transparent_voxels_and_is_sorted() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+            ((2, 0, 0), [255, 0, 0, 255]),
+            ((3, 0, 0), [0, 0, 0, 0]),
+        ]);
+
+        assert_eq!(model.palette(), vec![[0, 255, 0, 255], [255, 0, 0, 255]]);
+    }
+
+    #[test]
+    fn palette_with_counts_tallies_occurrences() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        assert_eq!(
+            model.palette_with_counts(),
+            vec![([0, 255, 0, 255], 1), ([255, 0, 0, 255], 2)]
+        );
+    }
+
+    #[test]
+    fn dominant_color_picks_the_most_used_non_transparent_color() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [255, 0, 0, 255]),
+            ((3, 0, 0), [0, 255, 0, 255]),
+            ((4, 0, 0), [0, 0, 0, 0]),
+        ]);
+
+        assert_eq!(model.dominant_color(), Some([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn dominant_color_breaks_ties_with_the_lowest_rgba() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        assert_eq!(model.dominant_color(), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn dominant_color_is_none_for_an_empty_model() {
+        assert_eq!(Model::new().dominant_color(), None);
+    }
+
+    #[test]
+    fn quantize_reduces_a_rich_palette_to_the_requested_color_count() {
+        let mut model = Model::new();
+        for r in 0..16u8 {
+            for g in 0..16u8 {
+                model.extend([((r as i32, g as i32, 0), [r * 16, g * 16, 0, 255])]);
+            }
+        }
+        assert_eq!(model.palette().len(), 256);
+
+        let (quantized, palette) = model.quantize(16, false);
+        assert!(palette.len() <= 16);
+        assert!(quantized.palette().len() <= 16);
+    }
+
+    #[test]
+    fn quantize_leaves_a_model_under_the_limit_unchanged_in_color_count() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let (quantized, palette) = model.quantize(256, false);
+        assert_eq!(palette.len(), 2);
+        assert_eq!(quantized.palette().len(), 2);
+    }
+
+    #[test]
+    fn quantize_preserves_voxel_positions_and_materials() {
+        let mut model = Model::new();
+        model.voxels.insert((0, 0, 0), [255, 0, 0, 255]);
+        model.materials.insert((0, 0, 0), 3);
+
+        let (quantized, _) = model.quantize(1, false);
+        assert_eq!(quantized.len(), 1);
+        assert!(quantized.voxel_at(0, 0, 0).is_some());
+        assert_eq!(quantized.voxel_material(0, 0, 0), Some(3));
+    }
+
+    #[test]
+    fn quantize_with_dither_still_respects_the_color_cap() {
+        let mut model = Model::new();
+        for r in 0..16u8 {
+            for g in 0..16u8 {
+                model.extend([((r as i32, g as i32, 0), [r * 16, g * 16, 0, 255])]);
+            }
+        }
+
+        let (quantized, palette) = model.quantize(16, true);
+        assert!(palette.len() <= 16);
+        assert!(quantized.palette().len() <= 16);
+    }
+
+    #[test]
+    fn histogram_at_8_bits_gives_an_exact_per_color_count() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let histogram = model.histogram(8);
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram[&[255, 0, 0, 255]], 2);
+        assert_eq!(histogram[&[0, 255, 0, 255]], 1);
+    }
+
+    #[test]
+    fn histogram_at_low_bit_depth_merges_near_colors() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [250, 4, 4, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [0, 0, 255, 255]),
+        ]);
+
+        // At full precision the two near-reds are distinct colors.
+        assert_eq!(model.histogram(8).len(), 3);
+
+        // At 2 bits per channel, both near-reds quantize to the same bucket.
+        let coarse = model.histogram(2);
+        assert_eq!(coarse.len(), 2);
+        assert_eq!(coarse[&[192, 0, 0, 192]], 2);
+    }
+
+    #[test]
+    fn drop_transparent_removes_only_alpha_zero_voxels_and_their_materials() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 0, 0, 0]),
+            ((2, 0, 0), [0, 255, 0, 1]),
+        ]);
+        model.materials.insert((0, 0, 0), 1);
+        model.materials.insert((1, 0, 0), 2);
+
+        model.drop_transparent();
+
+        assert_eq!(model.len(), 2);
+        assert!(model.voxel_at(1, 0, 0).is_none());
+        assert!(model.voxel_at(0, 0, 0).is_some());
+        assert!(model.voxel_at(2, 0, 0).is_some());
+        assert_eq!(model.materials.get(&(1, 0, 0)), None);
+        assert_eq!(model.materials.get(&(0, 0, 0)), Some(&1));
+    }
+
+    #[test]
+    fn replace_color_swaps_only_the_matching_voxels() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        model.replace_color([255, 0, 0, 255], [0, 0, 255, 255]);
+
+        let histogram = model.histogram(8);
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram[&[0, 0, 255, 255]], 2);
+        assert_eq!(histogram[&[0, 255, 0, 255]], 1);
+    }
+
+    #[test]
+    fn remap_colors_can_drop_voxels_via_alpha_and_drop_transparent() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        model.remap_colors(|rgba| if rgba == [255, 0, 0, 255] { [0, 0, 0, 0] } else { rgba });
+        assert_eq!(model.len(), 2);
+
+        model.drop_transparent();
+        assert_eq!(model.len(), 1);
+        assert_eq!(model.voxel_at(1, 0, 0), Some([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn content_hash_matches_a_clone_but_differs_after_translation() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        assert_eq!(model.content_hash(), model.clone().content_hash());
+
+        let translated = model.translated([1, 0, 0]);
+        assert_ne!(model.content_hash(), translated.content_hash());
+    }
+
+    #[test]
+    fn to_rle_collapses_contiguous_same_color_voxels_along_x() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((2, 0, 0), [255, 0, 0, 255]),
+            ((3, 0, 0), [0, 255, 0, 255]), // different color breaks the run
+            ((5, 0, 0), [0, 255, 0, 255]), // gap in x breaks the run
+        ]);
+
+        let mut runs = model.to_rle();
+        runs.sort_by_key(|run| run.start);
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0], RleRun { start: (0, 0, 0), length: 3, color: [255, 0, 0, 255] });
+        assert_eq!(runs[1], RleRun { start: (3, 0, 0), length: 1, color: [0, 255, 0, 255] });
+        assert_eq!(runs[2], RleRun { start: (5, 0, 0), length: 1, color: [0, 255, 0, 255] });
+    }
+
+    #[test]
+    fn to_rle_then_from_rle_round_trips_a_model() {
+        let mut model = Model::new();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [255, 0, 0, 255]),
+            ((0, 1, 0), [0, 255, 0, 255]),
+            ((0, 0, 1), [0, 0, 255, 255]),
+            ((10, -5, 3), [1, 2, 3, 4]),
+        ]);
+
+        let rebuilt = Model::from_rle(&model.to_rle());
+        assert_eq!(rebuilt, model);
+    }
+
+    #[test]
+    fn to_rle_of_an_empty_model_is_empty() {
+        assert!(Model::new().to_rle().is_empty());
+    }
+
+    #[test]
+    fn write_dedups_identical_bl16_blocks_across_layers() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Bl16 {
+                    data: vec![9, 9, 9],
+                },
+                Chunk::Bl16 {
+                    data: vec![9, 9, 9], // identical payload, different placement
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 0,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::from(IndexMap::from([("mat".to_string(), vec![0u8; 4])])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 1,
+                        x: 16,
+                        y: 0,
+                        z: 0,
+                    }],
+                    dict: Dict::from(IndexMap::from([("mat".to_string(), vec![0u8; 4])])),
+                },
+            ],
+        };
+
+        let bytes = goxel.to_bytes();
+        let written = parse(&bytes).expect("should parse what we just wrote");
+
+        let bl16_count = written
+            .chunks()
+            .iter()
+            .filter(|c| matches!(c, Chunk::Bl16 { .. }))
+            .count();
+        assert_eq!(bl16_count, 1, "identical BL16 payloads should collapse to one chunk");
+
+        let indices: Vec<i32> = written
+            .chunks()
+            .iter()
+            .filter_map(|c| match c {
+                Chunk::Layr { blocks, .. } => Some(blocks[0].index),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(indices, vec![0, 0], "both layers should reference the surviving BL16 chunk");
+    }
+
+    #[test]
+    fn gox_writer_streams_a_header_and_two_chunks_that_reparse_correctly() {
+        let mut buf = Vec::new();
+        let mut writer = GoxWriter::new(&mut buf, 2).expect("writing to a Vec<u8> cannot fail");
+
+        writer
+            .write_bl16(vec![9, 9, 9])
+            .expect("writing to a Vec<u8> cannot fail");
+        writer
+            .write_layer(
+                vec![Block { index: 0, x: 0, y: 0, z: 0 }],
+                Dict::from(IndexMap::from([("name".to_string(), b"layer".to_vec())])),
+            )
+            .expect("writing to a Vec<u8> cannot fail");
+
+        let goxel = parse(&buf).expect("should reparse what GoxWriter just wrote");
+        assert_eq!(goxel.version(), 2);
+        assert_eq!(goxel.chunks().len(), 2);
+        assert!(matches!(goxel.chunks()[0], Chunk::Bl16 { .. }));
+        match &goxel.chunks()[1] {
+            Chunk::Layr { blocks, dict } => {
+                assert_eq!(blocks.len(), 1);
+                assert_eq!(dict.get_str("name"), Some("layer"));
+            }
+            other => panic!("expected a Layr chunk, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn streaming_model_writer_round_trips_a_few_thousand_voxels() {
+        let mut voxels = Vec::new();
+        for x in 0..20 {
+            for y in 0..20 {
+                for z in 0..20 {
+                    let seed = ((x * 400 + y * 20 + z) % 256) as u8;
+                    voxels.push(((x, y, z), [seed, seed.wrapping_mul(3), seed.wrapping_mul(7), 255]));
+                }
+            }
+        }
+        assert_eq!(voxels.len(), 8000);
+
+        let mut buf = Vec::new();
+        let mut writer = StreamingModelWriter::new(&mut buf, 2, "streamed")
+            .expect("writing to a Vec<u8> cannot fail");
+        for &(pos, rgba) in &voxels {
+            writer.push(pos, rgba).expect("writing to a Vec<u8> cannot fail");
+        }
+        writer.finish().expect("writing to a Vec<u8> cannot fail");
+
+        let goxel = parse(&buf).expect("should reparse what StreamingModelWriter just wrote");
+        // A 20x20x20 cube spans a 2x2x2 grid of 16^3 blocks.
+        let bl16_count = goxel.all(ChunkKind::Bl16).count();
+        assert_eq!(bl16_count, 8);
+
+        let model = goxel.model().expect("every referenced BL16 chunk should decode");
+        assert_eq!(model.len(), voxels.len());
+        for (pos, rgba) in voxels {
+            assert_eq!(model.voxel_at(pos.0, pos.1, pos.2), Some(rgba));
+        }
+    }
+
+    #[test]
+    fn write_then_parse_then_write_round_trips_byte_identical() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img {
+                    dict: Dict::from(IndexMap::from([("name".to_string(), b"test".to_vec())])),
+                },
+                Chunk::Prev {
+                    data: vec![1, 2, 3, 4],
+                },
+                Chunk::Bl16 {
+                    data: vec![9, 9, 9],
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 16,
+                        y: 32,
+                        z: 48,
+                    }],
+                    dict: Dict::from(IndexMap::from([("mat".to_string(), vec![0u8; 4])])),
+                },
+                Chunk::Camr {
+                    dict: Dict::from(IndexMap::from([("dist".to_string(), vec![1, 0, 0, 0])])),
+                },
+                Chunk::Ligh {
+                    dict: Dict::from(IndexMap::from([("pitch".to_string(), vec![0, 0, 0, 0])])),
+                },
+                Chunk::Mate {
+                    dict: Dict::from(IndexMap::from([("metallic".to_string(), vec![0, 0, 0, 0])])),
+                },
+            ],
+        };
+
+        let original = goxel.to_bytes();
+        let parsed = parse(&original).expect("should parse what we just wrote");
+        assert_eq!(parsed.to_bytes(), original);
+    }
+
+    #[test]
+    fn two_independent_parses_of_the_same_bytes_are_equal() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img {
+                    dict: Dict::from(IndexMap::from([("name".to_string(), b"test".to_vec())])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 16,
+                        y: 32,
+                        z: 48,
+                    }],
+                    dict: Dict::from(IndexMap::from([("mat".to_string(), vec![0u8; 4])])),
+                },
+            ],
+        };
+        let bytes = goxel.to_bytes();
+
+        let first = parse(&bytes).expect("first parse should succeed");
+        let second = parse(&bytes).expect("second parse should succeed");
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn a_cloned_goxel_is_equal_to_the_original() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img {
+                    dict: Dict::from(IndexMap::from([("name".to_string(), b"test".to_vec())])),
+                },
+                Chunk::Layr {
+                    blocks: vec![Block {
+                        index: 0,
+                        x: 16,
+                        y: 32,
+                        z: 48,
+                    }],
+                    dict: Dict::from(IndexMap::from([("mat".to_string(), vec![0u8; 4])])),
+                },
+            ],
+        };
+
+        let cloned = goxel.clone();
+
+        assert_eq!(cloned, goxel);
+    }
+
+    #[test]
+    fn written_chunks_have_crcs_that_pass_verification() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Prev {
+                    data: vec![1, 2, 3, 4],
+                },
+                Chunk::Camr {
+                    dict: Dict::from(IndexMap::from([("dist".to_string(), vec![1, 0, 0, 0])])),
+                },
+            ],
+        };
+
+        let bytes = goxel.to_bytes();
+        parse_verified(&bytes).expect("a freshly written file's CRCs should verify");
+    }
+
+    #[test]
+    fn parse_then_write_round_trips_a_real_chunk_byte_identical() {
+        // A hand-assembled IMG chunk, framed exactly like a real .gox file:
+        // a two-entry dict followed by its 0-length-key terminator, all
+        // within the bounds the chunk's size field declares.
+        let mut body = Vec::new();
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(b"name");
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(b"test");
+        body.extend_from_slice(&3u32.to_le_bytes());
+        body.extend_from_slice(b"box");
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&[0u8]);
+        body.extend_from_slice(&0u32.to_le_bytes()); // dict terminator
+
+        let mut chunk = Vec::new();
+        chunk.extend_from_slice(b"IMG ");
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let goxel = parse_verified(&input).expect("should parse a well-formed real chunk");
+        assert_eq!(goxel.to_bytes(), input);
+    }
+
+    #[test]
+    fn parse_accepts_an_img_chunk_with_an_empty_dict() {
+        // Just the dict terminator, no entries before it.
+        let body = 0u32.to_le_bytes().to_vec();
+
+        let mut chunk = Vec::new();
+        chunk.extend_from_slice(b"IMG ");
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let goxel = parse_verified(&input).expect("an empty dict should still parse");
+        match &goxel.chunks()[0] {
+            Chunk::Img { dict } => assert_eq!(dict.get_str("name"), None),
+            other => panic!("expected an Img chunk, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_borrowed_matches_parse_after_converting_to_owned() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(b"name");
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(b"test");
+        body.extend_from_slice(&0u32.to_le_bytes()); // dict terminator
+
+        let mut chunk = Vec::new();
+        chunk.extend_from_slice(b"IMG ");
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let owned = parse_verified(&input).expect("should parse with the owned path");
+        let borrowed =
+            parse_borrowed_verified(&input).expect("should parse with the borrowed path");
+
+        assert_eq!(borrowed.version(), owned.version());
+        match &borrowed.chunks()[0] {
+            ChunkRef::Img { dict } => assert_eq!(dict.get_str("name"), Some("test")),
+            other => panic!("expected an Img chunk, got {other:?}"),
+        }
+        assert_eq!(borrowed.to_owned_goxel().to_bytes(), owned.to_bytes());
+    }
+
+    #[test]
+    fn gox_error_is_a_proper_std_error() {
+        fn assert_error<E: std::error::Error>() {}
+        assert_error::<GoxError>();
+    }
+
+    #[test]
+    fn parse_reports_bad_magic() {
+        let err = parse(b"NOPE").unwrap_err();
+        assert!(matches!(err, GoxError::BadMagic { found } if found == *b"NOPE"));
+    }
+
+    #[test]
+    fn parse_reports_bad_magic_on_an_empty_buffer() {
+        let err = parse(b"").unwrap_err();
+        assert!(matches!(err, GoxError::BadMagic { found } if found == [0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn parse_reports_bad_magic_on_a_truncated_magic() {
+        let err = parse(b"GO").unwrap_err();
+        assert!(matches!(err, GoxError::BadMagic { found } if found == *b"GO\0\0"));
+    }
+
+    #[test]
+    fn parse_accepts_a_header_only_file_as_a_valid_empty_goxel() {
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+
+        let goxel = parse(&input).expect("a header with no chunks is still valid");
+        assert!(goxel.is_empty());
+    }
+
+    #[test]
+    fn parse_checked_rejects_a_header_only_file() {
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+
+        assert!(matches!(parse_checked(&input), Err(GoxError::NoChunks)));
+    }
+
+    #[test]
+    fn parse_checked_accepts_a_file_with_at_least_one_chunk() {
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&[1, 2, 3, 4]); // truncated chunk, but non-empty
+
+        assert!(!matches!(parse_checked(&input), Err(GoxError::NoChunks)));
+    }
+
+    #[test]
+    fn parse_checked_rejects_junk_appended_after_a_valid_file() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img { dict: Dict::default() }],
+        };
+        let mut input = goxel.to_bytes();
+        let junk_offset = input.len();
+        input.extend_from_slice(b"not a chunk");
+
+        let err = parse_checked(&input).expect_err("trailing junk should be rejected");
+        assert!(matches!(
+            err,
+            GoxError::TrailingBytes { offset, len } if offset == junk_offset && len == 11
+        ));
+    }
+
+    #[test]
+    fn parse_checked_tolerates_trailing_zero_padding() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img { dict: Dict::default() }],
+        };
+        let mut input = goxel.to_bytes();
+        input.extend_from_slice(&[0u8; 3]);
+
+        assert!(parse_checked(&input).is_ok());
+    }
+
+    #[test]
+    fn parse_at_reads_two_concatenated_models_from_one_buffer() {
+        let first = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img {
+                dict: Dict::from(IndexMap::from([("name".to_string(), b"first".to_vec())])),
+            }],
+        };
+        let second = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img {
+                dict: Dict::from(IndexMap::from([("name".to_string(), b"second".to_vec())])),
+            }],
+        };
+
+        let mut buffer = first.to_bytes();
+        let second_offset = buffer.len();
+        buffer.extend_from_slice(&second.to_bytes());
+
+        let (parsed_first, next_offset) = parse_at(&buffer, 0).expect("should parse the first model");
+        assert_eq!(parsed_first.chunks()[0], first.chunks()[0]);
+        assert_eq!(next_offset, second_offset);
+
+        let (parsed_second, next_offset) = parse_at(&buffer, next_offset).expect("should parse the second model");
+        assert_eq!(parsed_second.chunks()[0], second.chunks()[0]);
+        assert_eq!(next_offset, buffer.len());
+    }
+
+    #[test]
+    fn parse_at_rejects_an_out_of_bounds_offset() {
+        let buffer = Goxel {
+            version: 2,
+            chunks: vec![],
+        }
+        .to_bytes();
+
+        assert!(matches!(
+            parse_at(&buffer, buffer.len() + 1),
+            Err(GoxError::TruncatedChunk { offset }) if offset == buffer.len() + 1
+        ));
+    }
+
+    #[test]
+    fn parse_reports_unsupported_version() {
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&99i32.to_le_bytes());
+
+        let err = parse(&input).unwrap_err();
+        assert!(matches!(err, GoxError::UnsupportedVersion(99)));
+    }
+
+    #[test]
+    fn parse_accepts_both_supported_format_versions() {
+        let mut v1 = b"GOX ".to_vec();
+        v1.extend_from_slice(&1i32.to_le_bytes());
+        let mut v2 = b"GOX ".to_vec();
+        v2.extend_from_slice(&2i32.to_le_bytes());
+
+        let goxel_v1 = parse(&v1).expect("version 1 should be accepted");
+        let goxel_v2 = parse(&v2).expect("version 2 should be accepted");
+
+        assert_eq!(goxel_v1.version(), 1);
+        assert_eq!(goxel_v2.version(), 2);
+        // Both versions share the same (empty, here) chunk layout.
+        assert!(goxel_v1.chunks().is_empty() && goxel_v2.chunks().is_empty());
+    }
+
+    #[test]
+    fn from_reader_parses_the_same_as_from_bytes() {
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![],
+        };
+        goxel.chunks.push(Chunk::Unknown {
+            tag: *b"TEST",
+            data: vec![1, 2, 3],
+        });
+        let bytes = goxel.to_bytes();
+
+        let from_bytes = Goxel::from_bytes(&bytes).expect("should parse from a slice");
+        let from_reader =
+            Goxel::from_reader(&bytes[..]).expect("should parse the same bytes from a Read");
+
+        assert_eq!(from_bytes.version(), from_reader.version());
+        assert_eq!(from_bytes.chunks().len(), from_reader.chunks().len());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn from_gz_parses_gzip_compressed_bytes() {
+        use std::io::Write as _;
+
+        let mut goxel = Goxel {
+            version: 2,
+            chunks: vec![],
+        };
+        goxel.chunks.push(Chunk::Unknown {
+            tag: *b"TEST",
+            data: vec![1, 2, 3],
+        });
+        let bytes = goxel.to_bytes();
+
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&bytes).unwrap();
+        let gz_bytes = encoder.finish().unwrap();
+
+        let from_bytes = Goxel::from_bytes(&bytes).expect("should parse from a slice");
+        let from_gz =
+            Goxel::from_gz(&gz_bytes[..]).expect("should parse the same bytes once gzipped");
+
+        assert_eq!(from_bytes.version(), from_gz.version());
+        assert_eq!(from_bytes.chunks().len(), from_gz.chunks().len());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn from_gz_rejects_a_gzip_bomb_before_fully_inflating_it() {
+        use std::io::Write as _;
+
+        // Tiny on the wire, huge once inflated: exactly the shape a zip
+        // bomb takes advantage of if nothing caps the decompressed size.
+        let zeros = vec![0u8; MAX_GZ_INFLATED_SIZE as usize + 1];
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&zeros).unwrap();
+        let gz_bytes = encoder.finish().unwrap();
+        assert!(gz_bytes.len() < 1024, "a run of zeros should compress tiny");
+
+        let err = Goxel::from_gz(&gz_bytes[..]).expect_err("should refuse to fully inflate it");
+        assert!(matches!(
+            err,
+            GoxError::LimitExceeded { limit } if limit == MAX_GZ_INFLATED_SIZE as usize
+        ));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn from_gz_reports_decompress_error_on_non_gzip_bytes() {
+        let err = Goxel::from_gz(&b"not gzip data"[..])
+            .expect_err("garbage bytes aren't a valid gzip stream");
+        assert!(matches!(err, GoxError::Decompress(_)));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn from_async_reader_parses_real_gox_bytes_over_an_in_memory_stream() {
+        use tokio::io::AsyncWriteExt;
+
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Unknown {
+                    tag: *b"TEST",
+                    data: vec![1, 2, 3],
+                },
+                Chunk::Prev { data: vec![4, 5] },
+            ],
+        };
+        let bytes = goxel.to_bytes();
+
+        let (mut writer, reader) = tokio::io::duplex(bytes.len() + 1);
+        writer.write_all(&bytes).await.expect("should write to the in-memory pipe");
+        drop(writer); // signal end of stream
+
+        let parsed = Goxel::from_async_reader(reader)
+            .await
+            .expect("should parse the streamed bytes");
+        assert_eq!(parsed.to_bytes(), goxel.to_bytes());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn from_async_reader_rejects_an_absurd_declared_chunk_length_before_allocating() {
+        let mut stream = b"GOX ".to_vec();
+        stream.extend_from_slice(&2i32.to_le_bytes());
+        stream.extend_from_slice(b"BL16");
+        stream.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        let err = Goxel::from_async_reader(&stream[..])
+            .await
+            .expect_err("a chunk length past the cap shouldn't be accepted");
+        assert!(matches!(
+            err,
+            GoxError::ChunkLengthOverrun {
+                chunk: [b'B', b'L', b'1', b'6'],
+                declared: u32::MAX,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn save_then_open_round_trips_through_a_file() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown {
+                tag: *b"TEST",
+                data: vec![1, 2, 3],
+            }],
+        };
+
+        let path = std::env::temp_dir().join("gox_rs_save_then_open_round_trips_through_a_file.gox");
+        goxel.save(&path).expect("should write to a temp file");
+        let opened = Goxel::open(&path).expect("should read back what we just wrote");
+        std::fs::remove_file(&path).expect("should clean up the temp file");
+
+        assert_eq!(opened.to_bytes(), goxel.to_bytes());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn open_mmap_parses_a_memory_mapped_file() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown {
+                tag: *b"TEST",
+                data: vec![1, 2, 3],
+            }],
+        };
+
+        let path = std::env::temp_dir().join("gox_rs_open_mmap_parses_a_memory_mapped_file.gox");
+        goxel.save(&path).expect("should write to a temp file");
+
+        let mapped = Goxel::open_mmap(&path).expect("should memory-map and parse the file");
+        let view = mapped.goxel().expect("should parse the mapped bytes");
+        std::fs::remove_file(&path).expect("should clean up the temp file");
+
+        assert_eq!(view.version(), goxel.version());
+        assert_eq!(view.to_owned_goxel().to_bytes(), goxel.to_bytes());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn open_mmap_surfaces_a_missing_file_as_io_error() {
+        let path =
+            std::env::temp_dir().join("gox_rs_open_mmap_surfaces_a_missing_file_as_io_error.gox");
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(Goxel::open_mmap(&path), Err(GoxError::Io(_))));
+    }
+
+    #[test]
+    fn open_surfaces_a_missing_file_as_io_error() {
+        let path = std::env::temp_dir().join("gox_rs_open_surfaces_a_missing_file_as_io_error.gox");
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(Goxel::open(&path), Err(GoxError::Io(_))));
+    }
+
+    #[test]
+    fn parse_preserves_unknown_chunk_types() {
+        let mut chunk = b"NOPE".to_vec();
+        chunk.extend_from_slice(&3u32.to_le_bytes());
+        chunk.extend_from_slice(&[0xAA, 0xBB, 0xCC]);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let goxel = parse_verified(&input).expect("an unrecognized tag shouldn't fail parsing");
+        assert_eq!(goxel.chunks().len(), 1);
+        assert!(matches!(
+            &goxel.chunks()[0],
+            Chunk::Unknown {
+                tag: [b'N', b'O', b'P', b'E'],
+                data,
+            } if data == &[0xAA, 0xBB, 0xCC]
+        ));
+        assert_eq!(goxel.to_bytes(), input);
+    }
+
+    #[test]
+    fn chunk_kinds_lists_each_chunks_discriminant_in_file_order() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Layr { blocks: vec![], dict: Dict::default() },
+                Chunk::Unknown { tag: *b"FOOO", data: vec![] },
+            ],
+        };
+
+        assert_eq!(
+            goxel.chunk_kinds(),
+            vec![ChunkKind::Img, ChunkKind::Layr, ChunkKind::Unknown(*b"FOOO")]
+        );
+    }
+
+    #[test]
+    fn first_and_all_find_chunks_by_kind() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Bl16 { data: vec![1] },
+                Chunk::Bl16 { data: vec![2] },
+                Chunk::Camr { dict: Dict::default() },
+            ],
+        };
+
+        assert!(matches!(goxel.first(ChunkKind::Img), Some(Chunk::Img { .. })));
+        assert!(goxel.first(ChunkKind::Ligh).is_none());
+
+        let bl16: Vec<&Chunk> = goxel.all(ChunkKind::Bl16).collect();
+        assert_eq!(bl16.len(), 2);
+        assert!(matches!(bl16[0], Chunk::Bl16 { data } if data == &[1]));
+        assert!(matches!(bl16[1], Chunk::Bl16 { data } if data == &[2]));
+    }
+
+    #[test]
+    fn chunk_kind_displays_as_its_four_character_tag() {
+        assert_eq!(ChunkKind::Img.to_string(), "IMG ");
+        assert_eq!(ChunkKind::Bl16.to_string(), "BL16");
+        assert_eq!(ChunkKind::Unknown(*b"FOOO").to_string(), "FOOO");
+    }
+
+    #[test]
+    fn tag_returns_each_variants_raw_four_byte_chunk_type() {
+        assert_eq!(Chunk::Img { dict: Dict::default() }.tag(), *b"IMG ");
+        assert_eq!(Chunk::Prev { data: vec![] }.tag(), *b"PREV");
+        assert_eq!(Chunk::Bl16 { data: vec![] }.tag(), *b"BL16");
+        assert_eq!(Chunk::Layr { blocks: vec![], dict: Dict::default() }.tag(), *b"LAYR");
+        assert_eq!(Chunk::Camr { dict: Dict::default() }.tag(), *b"CAMR");
+        assert_eq!(Chunk::Ligh { dict: Dict::default() }.tag(), *b"LIGH");
+        assert_eq!(Chunk::Mate { dict: Dict::default() }.tag(), *b"MATE");
+        assert_eq!(Chunk::Unknown { tag: *b"FOOO", data: vec![] }.tag(), *b"FOOO");
+    }
+
+    #[test]
+    fn chunk_iter_yields_the_same_chunks_parse_would() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Unknown { tag: *b"AAAA", data: vec![1, 2, 3] },
+                Chunk::Unknown { tag: *b"BBBB", data: vec![4, 5] },
+            ],
+        };
+        let bytes = goxel.to_bytes();
+
+        let iter = ChunkIter::new(&bytes, false, false).expect("well-formed header");
+        assert_eq!(iter.version(), 2);
+        let chunks: Vec<Chunk> = iter.collect::<Result<_, _>>().expect("no parse errors");
+
+        assert_eq!(chunks.len(), 2);
+        assert!(matches!(&chunks[0], Chunk::Unknown { tag: [b'A', b'A', b'A', b'A'], data } if data == &[1, 2, 3]));
+        assert!(matches!(&chunks[1], Chunk::Unknown { tag: [b'B', b'B', b'B', b'B'], data } if data == &[4, 5]));
+    }
+
+    #[test]
+    fn parse_into_reused_across_two_different_inputs_matches_a_fresh_parse() {
+        let first = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Unknown { tag: *b"AAAA", data: vec![1, 2, 3] },
+                Chunk::Unknown { tag: *b"BBBB", data: vec![4, 5] },
+                Chunk::Unknown { tag: *b"CCCC", data: vec![6] },
+            ],
+        }
+        .to_bytes();
+        let second = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown { tag: *b"DDDD", data: vec![7, 8, 9, 10] }],
+        }
+        .to_bytes();
+
+        let mut reused = Goxel { version: 0, chunks: Vec::new() };
+        reused.parse_into(&first).expect("first parse should succeed");
+        assert_eq!(reused.chunks().len(), 3);
+
+        let reused_capacity = reused.chunks.capacity();
+        reused.parse_into(&second).expect("second parse should succeed");
+
+        let fresh = parse(&second).expect("a fresh parse of the same bytes should succeed");
+        assert_eq!(reused.to_bytes(), fresh.to_bytes());
+        assert_eq!(reused.version(), fresh.version());
+        assert_eq!(reused.chunks().len(), fresh.chunks().len());
+        assert!(reused.chunks.capacity() >= reused_capacity.min(fresh.chunks.len()));
+    }
+
+    #[test]
+    fn parse_into_leaves_successfully_parsed_chunks_in_place_after_a_later_failure() {
+        let mut reused = Goxel { version: 0, chunks: Vec::new() };
+        let good = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown { tag: *b"AAAA", data: vec![1, 2, 3] }],
+        }
+        .to_bytes();
+        reused.parse_into(&good).expect("well-formed input should parse");
+
+        let err = reused.parse_into(b"NOPE").unwrap_err();
+        assert!(matches!(err, GoxError::BadMagic { found } if found == *b"NOPE"));
+        assert_eq!(reused.chunks().len(), 1);
+    }
+
+    #[test]
+    fn chunk_iter_can_stop_before_reaching_malformed_trailing_bytes() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown {
+                tag: *b"AAAA",
+                data: vec![1, 2, 3],
+            }],
+        };
+        let mut bytes = goxel.to_bytes();
+        bytes.extend_from_slice(&[0xFF; 3]); // trailing garbage, not a valid chunk
+
+        // A consumer that only wants the first chunk never has to learn
+        // that the rest of the stream is malformed.
+        let mut iter = ChunkIter::new(&bytes, false, false).expect("well-formed header");
+        assert!(matches!(iter.next(), Some(Ok(Chunk::Unknown { .. }))));
+    }
+
+    #[test]
+    fn chunk_iter_reports_the_same_error_parse_would() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown {
+                tag: *b"AAAA",
+                data: vec![1, 2, 3],
+            }],
+        };
+        let mut bytes = goxel.to_bytes();
+        bytes.extend_from_slice(&[0xFF; 3]);
+
+        let iter = ChunkIter::new(&bytes, false, false).expect("well-formed header");
+        let result: Result<Vec<Chunk>, GoxError> = iter.collect();
+        assert!(matches!(result, Err(GoxError::TruncatedChunk { .. })));
+        assert!(matches!(parse(&bytes), Err(GoxError::TruncatedChunk { .. })));
+    }
+
+    #[test]
+    fn parse_reports_the_absolute_offset_of_a_truncated_second_chunk() {
+        // Two well-formed chunks, then cut off partway through the second
+        // chunk's header. The reported offset should point at the start of
+        // the second chunk (in the original file), not the first, and not
+        // some position relative to the second chunk's own bytes.
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Unknown { tag: *b"AAAA", data: vec![1, 2, 3, 4] },
+                Chunk::Unknown { tag: *b"BBBB", data: vec![5, 6, 7, 8] },
+            ],
+        };
+        let bytes = goxel.to_bytes();
+
+        let header_len = 8;
+        let first_chunk_len = 4 + 4 + 4 + 4; // tag + length + 4-byte body + crc
+        let second_chunk_offset = header_len + first_chunk_len;
+        let truncated = &bytes[..second_chunk_offset + 6]; // cuts off mid tag/length
+
+        let err = parse(truncated).expect_err("a chunk cut off mid-header shouldn't parse");
+        assert!(matches!(
+            err,
+            GoxError::TruncatedChunk { offset } if offset == second_chunk_offset
+        ));
+    }
+
+    #[test]
+    fn parse_with_spans_reconstructs_the_original_file() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Unknown { tag: *b"AAAA", data: vec![1, 2, 3] },
+                Chunk::Unknown { tag: *b"BBBB", data: vec![4, 5] },
+            ],
+        };
+        let bytes = goxel.to_bytes();
+
+        let spans = parse_with_spans(&bytes).expect("well-formed file");
+        assert_eq!(spans.len(), 2);
+
+        let mut reconstructed = bytes[..8].to_vec(); // magic + version
+        for (_, range) in &spans {
+            reconstructed.extend_from_slice(&bytes[range.clone()]);
+        }
+        assert_eq!(reconstructed, bytes);
+
+        assert!(matches!(&spans[0].0, Chunk::Unknown { tag: [b'A', b'A', b'A', b'A'], data } if data == &[1, 2, 3]));
+        assert!(matches!(&spans[1].0, Chunk::Unknown { tag: [b'B', b'B', b'B', b'B'], data } if data == &[4, 5]));
+    }
+
+    #[test]
+    fn parse_with_spans_reports_the_same_error_parse_would() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Unknown {
+                tag: *b"AAAA",
+                data: vec![1, 2, 3],
+            }],
+        };
+        let mut bytes = goxel.to_bytes();
+        bytes.extend_from_slice(&[0xFF; 3]);
+
+        assert!(matches!(parse_with_spans(&bytes), Err(GoxError::TruncatedChunk { .. })));
+    }
+
+    #[test]
+    fn parse_reports_chunk_length_overrun_for_a_bl16_with_an_absurd_declared_length() {
+        // A BL16 chunk that claims a body of (almost) u32::MAX bytes, far
+        // more than the handful of bytes actually following it. This should
+        // be diagnosed explicitly rather than folded into the generic
+        // "truncated" case.
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(b"BL16");
+        input.extend_from_slice(&u32::MAX.to_le_bytes());
+        input.extend_from_slice(&[0x00; 4]); // a few stray bytes, nowhere near enough
+
+        let err = parse(&input).expect_err("an overrun length shouldn't parse");
+        assert!(matches!(
+            err,
+            GoxError::ChunkLengthOverrun {
+                chunk: [b'B', b'L', b'1', b'6'],
+                declared: u32::MAX,
+                available: 4,
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_with_options_reports_limit_exceeded_for_an_oversized_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Prev { data: vec![0u8; 1024] }],
+        };
+        let bytes = goxel.to_bytes();
+
+        let err = parse_with_options(&bytes, ParseOptions { max_alloc: 16 })
+            .expect_err("a 1024-byte chunk shouldn't fit under a 16-byte cap");
+        assert!(matches!(err, GoxError::LimitExceeded { limit: 16 }));
+
+        let goxel = parse_with_options(&bytes, ParseOptions::default())
+            .expect("the default of no limit should still parse a large chunk");
+        assert_eq!(goxel.chunks().len(), 1);
+    }
+
+    #[test]
+    fn parse_timed_reports_plausible_metrics() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Ligh { dict: Dict::default() },
+            ],
+        };
+        let bytes = goxel.to_bytes();
+
+        let (result, metrics) = parse_timed(&bytes);
+        let parsed = result.expect("should parse a well-formed file");
+
+        assert_eq!(parsed.chunks().len(), 2);
+        assert_eq!(metrics.chunks, 2);
+        assert_eq!(metrics.bytes, bytes.len());
+        assert!(metrics.chunk_parse_time < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn parse_verified_reports_crc_mismatch() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Prev {
+                data: vec![1, 2, 3],
+            }],
+        };
+        let mut bytes = goxel.to_bytes();
+        *bytes.last_mut().unwrap() ^= 0xFF; // corrupt the stored CRC
+
+        let err = parse_verified(&bytes).unwrap_err();
+        assert!(matches!(err, GoxError::CrcMismatch { offset: 8, .. }));
+    }
+
+    #[test]
+    fn parse_reports_dict_decode_when_an_entry_lies_about_its_size() {
+        // A dict entry claiming a 1-byte key "a", then a 100-byte value —
+        // but the chunk's own declared length only leaves room for the
+        // value's 4-byte length prefix, not a single byte of the value
+        // itself. The inner dict parser must be confined to the chunk's
+        // declared length, or it would read past this chunk into whatever
+        // comes next in `input` instead of failing cleanly.
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes()); // key length
+        body.push(b'a'); // key
+        body.extend_from_slice(&100u32.to_le_bytes()); // value length (a lie)
+
+        let mut chunk = b"IMG ".to_vec();
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let err = parse(&input).expect_err("a truncated value shouldn't parse");
+        assert!(matches!(err, GoxError::DictDecode { offset: 8 }));
+    }
+
+    #[test]
+    fn strict_utf8_mode_rejects_an_invalid_key_that_the_lenient_default_replaces() {
+        // A dict entry whose one-byte key, 0xFF, is not valid UTF-8 on its
+        // own (it's a continuation byte with no leading byte before it).
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_le_bytes()); // key length
+        body.push(0xFF); // key: invalid UTF-8
+        body.extend_from_slice(&0u32.to_le_bytes()); // value length
+        body.extend_from_slice(&0u32.to_le_bytes()); // dict terminator
+
+        let mut chunk = b"IMG ".to_vec();
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let goxel = parse(&input).expect("the lenient default replaces the bad key with U+FFFD");
+        match &goxel.chunks[0] {
+            Chunk::Img { dict } => assert_eq!(dict.get_str("\u{FFFD}"), Some("")),
+            other => panic!("expected an Img chunk, got {other:?}"),
+        }
+
+        let err = parse_strict(&input).expect_err("strict mode should reject the bad key");
+        assert!(matches!(err, GoxError::InvalidKeyUtf8 { offset: 20 }));
+
+        let err = parse_strict_verified(&input).expect_err("strict mode should reject the bad key even with CRC verification on");
+        assert!(matches!(err, GoxError::InvalidKeyUtf8 { offset: 20 }));
+    }
+
+    #[test]
+    fn duplicate_key_policy_controls_how_a_repeated_dict_key_is_resolved() {
+        // A dict with key "a" given twice: first value "1", then "2". A
+        // well-formed Goxel file never does this.
+        let mut body = Vec::new();
+        for (key, value) in [(b'a', b'1'), (b'a', b'2')] {
+            body.extend_from_slice(&1u32.to_le_bytes()); // key length
+            body.push(key);
+            body.extend_from_slice(&1u32.to_le_bytes()); // value length
+            body.push(value);
+        }
+        body.extend_from_slice(&0u32.to_le_bytes()); // dict terminator
+
+        let mut chunk = b"IMG ".to_vec();
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&body);
+        let crc = crc32(&chunk);
+        chunk.extend_from_slice(&crc.to_le_bytes());
+
+        let mut input = b"GOX ".to_vec();
+        input.extend_from_slice(&2i32.to_le_bytes());
+        input.extend_from_slice(&chunk);
+
+        let goxel = parse_with_duplicate_key_policy(&input, DuplicateKeyPolicy::KeepLast)
+            .expect("KeepLast should accept a duplicate key");
+        match &goxel.chunks[0] {
+            Chunk::Img { dict } => assert_eq!(dict.get_str("a"), Some("2")),
+            other => panic!("expected an Img chunk, got {other:?}"),
+        }
+
+        let goxel = parse_with_duplicate_key_policy(&input, DuplicateKeyPolicy::KeepFirst)
+            .expect("KeepFirst should accept a duplicate key");
+        match &goxel.chunks[0] {
+            Chunk::Img { dict } => assert_eq!(dict.get_str("a"), Some("1")),
+            other => panic!("expected an Img chunk, got {other:?}"),
+        }
+
+        let err = parse_with_duplicate_key_policy(&input, DuplicateKeyPolicy::Error)
+            .expect_err("Error policy should reject a duplicate key");
+        assert!(matches!(err, GoxError::DuplicateDictKey { key } if key == "a"));
+    }
+
+    #[test]
+    fn validate_reports_a_block_index_past_the_end_of_the_bl16_list() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Bl16 { data: vec![9, 9, 9] },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block { index: 0, x: 0, y: 0, z: 0 },
+                        Block { index: 1, x: 16, y: 0, z: 0 }, // no second BL16 chunk
+                    ],
+                    dict: Dict::default(),
+                },
+            ],
+        };
+
+        let err = goxel.validate().expect_err("block 1 has no matching BL16 chunk");
+        assert!(matches!(err, GoxError::DanglingBlock { layer: 0, index: 1 }));
+    }
+
+    #[test]
+    fn validate_accepts_blocks_that_reference_existing_bl16_chunks() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Bl16 { data: vec![9, 9, 9] },
+                Chunk::Bl16 { data: vec![8, 8, 8] },
+                Chunk::Layr {
+                    blocks: vec![
+                        Block { index: 0, x: 0, y: 0, z: 0 },
+                        Block { index: 1, x: 16, y: 0, z: 0 },
+                    ],
+                    dict: Dict::default(),
+                },
+            ],
+        };
+
+        assert!(goxel.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_accepts_a_freshly_built_model_builder_file() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+        builder.add_layer("main");
+
+        assert!(builder.build().validate().is_ok());
+    }
+
+    #[test]
+    fn model_builder_built_files_do_not_claim_a_goxel_authoring_version() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+        builder.add_layer("main");
+
+        let goxel = builder.build();
+        assert_eq!(goxel.authoring_version(), None);
+        assert!(goxel.image_metadata().get("software").unwrap().starts_with("gox-rs "));
+    }
+
+    #[test]
+    fn validate_rejects_a_file_with_no_img_chunk() {
+        let goxel = Goxel { version: 2, chunks: vec![] };
+        assert!(matches!(goxel.validate(), Err(GoxError::MissingImage)));
+    }
+
+    #[test]
+    fn validate_rejects_a_file_with_more_than_one_img_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Img { dict: Dict::default() },
+                Chunk::Img { dict: Dict::default() },
+            ],
+        };
+        assert!(matches!(
+            goxel.validate(),
+            Err(GoxError::MultipleImages { count: 2 })
+        ));
+    }
+
+    #[test]
+    fn parse_stays_lenient_about_a_missing_img_chunk() {
+        let input: &[u8] = &[b'G', b'O', b'X', b' ', 0x2, 0x0, 0x0, 0x0];
+        let goxel = parse(input).expect("a header-only file should still parse");
+        assert!(matches!(goxel.validate(), Err(GoxError::MissingImage)));
+    }
+
+    fn identity_mat() -> [f32; 16] {
+        let mut mat = [0.0f32; 16];
+        mat[0] = 1.0;
+        mat[5] = 1.0;
+        mat[10] = 1.0;
+        mat[15] = 1.0;
+        mat
+    }
+
+    fn mat_bytes(mat: [f32; 16]) -> Vec<u8> {
+        mat.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn as_camera_decodes_a_well_formed_dict() {
+        let mat = identity_mat();
+        let chunk = Chunk::Camr {
+            dict: Dict::from(IndexMap::from([
+                ("mat".to_string(), mat_bytes(mat)),
+                ("dist".to_string(), 10.0f32.to_le_bytes().to_vec()),
+                ("ortho".to_string(), 1i32.to_le_bytes().to_vec()),
+                ("name".to_string(), b"Camera 1".to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_camera().expect("should decode a camera view");
+        assert_eq!(view.mat, mat);
+        assert_eq!(view.dist, 10.0);
+        assert!(view.ortho);
+        assert_eq!(view.name, Some("Camera 1".to_string()));
+    }
+
+    #[test]
+    fn as_camera_rejects_other_chunk_types() {
+        let chunk = Chunk::Ligh {
+            dict: Dict::default(),
+        };
+        assert_eq!(chunk.as_camera(), None);
+    }
+
+    #[test]
+    fn as_camera_rejects_missing_keys() {
+        let chunk = Chunk::Camr {
+            dict: Dict::default(),
+        };
+        assert_eq!(chunk.as_camera(), None);
+    }
+
+    #[test]
+    fn cameras_returns_every_camr_chunk_in_file_order() {
+        let mat = identity_mat();
+        let camera = |name: &str| Chunk::Camr {
+            dict: Dict::from(IndexMap::from([
+                ("mat".to_string(), mat_bytes(mat)),
+                ("dist".to_string(), 10.0f32.to_le_bytes().to_vec()),
+                ("name".to_string(), name.as_bytes().to_vec()),
+            ])),
+        };
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![camera("Front"), camera("Top")],
+        };
+
+        let names: Vec<Option<String>> = goxel.cameras().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, vec![Some("Front".to_string()), Some("Top".to_string())]);
+    }
+
+    #[test]
+    fn camera_by_name_finds_a_matching_camera() {
+        let mat = identity_mat();
+        let camera = |name: &str| Chunk::Camr {
+            dict: Dict::from(IndexMap::from([
+                ("mat".to_string(), mat_bytes(mat)),
+                ("dist".to_string(), 10.0f32.to_le_bytes().to_vec()),
+                ("name".to_string(), name.as_bytes().to_vec()),
+            ])),
+        };
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![camera("Front"), camera("Top")],
+        };
+
+        let view = goxel.camera_by_name("Top").expect("should find the Top camera");
+        assert_eq!(view.name, Some("Top".to_string()));
+        assert!(goxel.camera_by_name("Side").is_none());
+    }
+
+    #[test]
+    fn file_palette_returns_the_pale_chunks_colors() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Pale {
+                colors: vec![[255, 0, 0, 255], [0, 255, 0, 255]],
+                dict: Dict::default(),
+            }],
+        };
+
+        assert_eq!(goxel.file_palette(), Some(vec![[255, 0, 0, 255], [0, 255, 0, 255]]));
+    }
+
+    #[test]
+    fn file_palette_is_none_without_a_pale_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img { dict: Dict::default() }],
+        };
+
+        assert_eq!(goxel.file_palette(), None);
+    }
+
+    #[test]
+    fn camera_view_matrix_returns_goxels_column_major_layout() {
+        let view = CameraView {
+            mat: identity_mat(),
+            dist: 10.0,
+            ortho: false,
+            name: None,
+        };
+        assert_eq!(
+            view.matrix(),
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn camera_view_decompose_of_the_identity_is_trivial() {
+        let view = CameraView {
+            mat: identity_mat(),
+            dist: 10.0,
+            ortho: false,
+            name: None,
+        };
+        let (translation, rotation, scale) = view.decompose();
+        assert_eq!(translation, [0.0, 0.0, 0.0]);
+        assert_eq!(rotation, [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(scale, [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn camera_view_decompose_recovers_translation_rotation_and_scale() {
+        // A 90-degree rotation around Z, scaled by 2 on every axis, then
+        // translated by (1, 2, 3): column-major, so columns are the
+        // scaled, rotated basis vectors followed by the translation.
+        let mut mat = [0.0f32; 16];
+        mat[0..4].copy_from_slice(&[0.0, 2.0, 0.0, 0.0]); // scaled +x -> +y
+        mat[4..8].copy_from_slice(&[-2.0, 0.0, 0.0, 0.0]); // scaled +y -> -x
+        mat[8..12].copy_from_slice(&[0.0, 0.0, 2.0, 0.0]); // scaled +z -> +z
+        mat[12..16].copy_from_slice(&[1.0, 2.0, 3.0, 1.0]);
+
+        let view = CameraView {
+            mat,
+            dist: 10.0,
+            ortho: false,
+            name: None,
+        };
+        let (translation, rotation, scale) = view.decompose();
+
+        let approx_eq = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(translation.iter().zip([1.0, 2.0, 3.0]).all(|(&a, b)| approx_eq(a, b)));
+        assert!(scale.iter().all(|&s| approx_eq(s, 2.0)));
+
+        // A 90-degree rotation around Z is the quaternion (0, 0, sin45, cos45).
+        let half = std::f32::consts::FRAC_PI_4;
+        let expected = [0.0, 0.0, half.sin(), half.cos()];
+        assert!(rotation.iter().zip(expected).all(|(&a, b)| approx_eq(a, b)));
+    }
+
+    #[test]
+    fn as_layer_decodes_a_well_formed_dict() {
+        let mat = identity_mat();
+        let chunk = Chunk::Layr {
+            blocks: vec![],
+            dict: Dict::from(IndexMap::from([
+                ("name".to_string(), b"Layer 1".to_vec()),
+                ("mat".to_string(), mat_bytes(mat)),
+                ("visible".to_string(), 0i32.to_le_bytes().to_vec()),
+                ("id".to_string(), 3i32.to_le_bytes().to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_layer().expect("should decode a layer view");
+        assert_eq!(view.name, "Layer 1");
+        assert_eq!(view.mat, mat);
+        assert!(!view.visible);
+        assert_eq!(view.base_id, -1);
+        assert_eq!(view.id, 3);
+        assert_eq!(view.kind, LayerKind::Blocks);
+    }
+
+    #[test]
+    fn as_layer_recognizes_a_shape_layer_with_zero_blocks() {
+        let mat = identity_mat();
+        let chunk = Chunk::Layr {
+            blocks: vec![],
+            dict: Dict::from(IndexMap::from([
+                ("name".to_string(), b"Sphere".to_vec()),
+                ("mat".to_string(), mat_bytes(mat)),
+                ("shape".to_string(), b"sphere".to_vec()),
+                ("material".to_string(), 0i32.to_le_bytes().to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_layer().expect("should decode a layer view");
+        assert_eq!(
+            view.kind,
+            LayerKind::Shape {
+                name: "sphere".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn as_layer_treats_a_layer_with_blocks_as_blocks_even_if_shape_is_present() {
+        // A `shape` key alongside an actual block list shouldn't happen in
+        // practice, but if it does, the blocks win: this isn't a shape-only
+        // layer.
+        let mat = identity_mat();
+        let chunk = Chunk::Layr {
+            blocks: vec![Block {
+                index: 0,
+                x: 0,
+                y: 0,
+                z: 0,
+            }],
+            dict: Dict::from(IndexMap::from([
+                ("name".to_string(), b"Weird".to_vec()),
+                ("mat".to_string(), mat_bytes(mat)),
+                ("shape".to_string(), b"sphere".to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_layer().expect("should decode a layer view");
+        assert_eq!(view.kind, LayerKind::Blocks);
+    }
+
+    #[test]
+    fn as_light_decodes_a_well_formed_dict() {
+        let chunk = Chunk::Ligh {
+            dict: Dict::from(IndexMap::from([
+                ("pitch".to_string(), 1.5f32.to_le_bytes().to_vec()),
+                ("yaw".to_string(), 2.5f32.to_le_bytes().to_vec()),
+                ("intensity".to_string(), 3.5f32.to_le_bytes().to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_light().expect("should decode a light view");
+        assert_eq!(view.pitch, 1.5);
+        assert_eq!(view.yaw, 2.5);
+        assert_eq!(view.intensity, 3.5);
+        assert!(!view.fixed);
+        assert_eq!(view.ambient, 0.0);
+    }
+
+    #[test]
+    fn as_light_decodes_fixed_and_ambient_when_present() {
+        let chunk = Chunk::Ligh {
+            dict: Dict::from(IndexMap::from([
+                ("pitch".to_string(), 1.5f32.to_le_bytes().to_vec()),
+                ("yaw".to_string(), 2.5f32.to_le_bytes().to_vec()),
+                ("intensity".to_string(), 3.5f32.to_le_bytes().to_vec()),
+                ("fixed".to_string(), 1i32.to_le_bytes().to_vec()),
+                ("ambient".to_string(), 0.25f32.to_le_bytes().to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_light().expect("should decode a light view");
+        assert!(view.fixed);
+        assert_eq!(view.ambient, 0.25);
+    }
+
+    #[test]
+    fn light_finds_the_scenes_ligh_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![
+                Chunk::Unknown { tag: *b"AAAA", data: vec![] },
+                Chunk::Ligh {
+                    dict: Dict::from(IndexMap::from([
+                        ("pitch".to_string(), 1.0f32.to_le_bytes().to_vec()),
+                        ("yaw".to_string(), 2.0f32.to_le_bytes().to_vec()),
+                        ("intensity".to_string(), 3.0f32.to_le_bytes().to_vec()),
+                    ])),
+                },
+            ],
+        };
+
+        let light = goxel.light().expect("should find the LIGH chunk");
+        assert_eq!(light.pitch, 1.0);
+    }
+
+    #[test]
+    fn light_is_none_without_a_ligh_chunk() {
+        let goxel = Goxel { version: 2, chunks: vec![] };
+        assert!(goxel.light().is_none());
+    }
+
+    #[test]
+    fn as_material_decodes_a_well_formed_dict() {
+        let chunk = Chunk::Mate {
+            dict: Dict::from(IndexMap::from([
+                ("name".to_string(), b"Gold".to_vec()),
+                (
+                    "color".to_string(),
+                    [1.0f32, 0.8, 0.2, 1.0]
+                        .iter()
+                        .flat_map(|v| v.to_le_bytes())
+                        .collect(),
+                ),
+                ("metallic".to_string(), 0.9f32.to_le_bytes().to_vec()),
+                ("roughness".to_string(), 0.1f32.to_le_bytes().to_vec()),
+            ])),
+        };
+
+        let view = chunk.as_material().expect("should decode a material view");
+        assert_eq!(view.name, Some("Gold".to_string()));
+        assert_eq!(view.color, [1.0, 0.8, 0.2, 1.0]);
+        assert_eq!(view.metallic, 0.9);
+        assert_eq!(view.roughness, 0.1);
+    }
+
+    #[test]
+    fn as_image_decodes_a_well_formed_dict() {
+        let mat = identity_mat();
+        let chunk = Chunk::Img {
+            dict: Dict::from(IndexMap::from([("box".to_string(), mat_bytes(mat))])),
+        };
+
+        let view = chunk.as_image().expect("should decode an image view");
+        assert_eq!(view.bounding_box, mat);
+    }
+
+    #[test]
+    fn as_image_rejects_a_missing_box() {
+        let chunk = Chunk::Img {
+            dict: Dict::default(),
+        };
+        assert_eq!(chunk.as_image(), None);
+    }
+
+    #[test]
+    fn image_metadata_decodes_string_entries_and_skips_binary_ones() {
+        let chunk = Chunk::Img {
+            dict: Dict::from(IndexMap::from([
+                ("box".to_string(), mat_bytes(identity_mat())),
+                ("software".to_string(), b"goxel 0.14.1".to_vec()),
+                ("author".to_string(), b"jane".to_vec()),
+            ])),
+        };
+
+        let metadata = chunk.image_metadata();
+        assert_eq!(metadata.get("software"), Some(&"goxel 0.14.1".to_string()));
+        assert_eq!(metadata.get("author"), Some(&"jane".to_string()));
+        assert!(!metadata.contains_key("box"));
+    }
+
+    #[test]
+    fn image_metadata_is_empty_for_a_non_img_chunk() {
+        let chunk = Chunk::Ligh { dict: Dict::default() };
+        assert!(chunk.image_metadata().is_empty());
+    }
+
+    #[test]
+    fn goxel_image_metadata_reads_the_first_img_chunk() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img {
+                dict: Dict::from(IndexMap::from([(
+                    "software".to_string(),
+                    b"goxel 0.14.1".to_vec(),
+                )])),
+            }],
+        };
+        assert_eq!(
+            goxel.image_metadata().get("software"),
+            Some(&"goxel 0.14.1".to_string())
+        );
+
+        assert!(Goxel::new(2).image_metadata().is_empty());
+    }
+
+    #[test]
+    fn authoring_version_reads_the_goxel_key() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img {
+                dict: Dict::from(IndexMap::from([("goxel".to_string(), b"0.14.1".to_vec())])),
+            }],
+        };
+        assert_eq!(goxel.authoring_version(), Some("0.14.1".to_string()));
+    }
+
+    #[test]
+    fn authoring_version_falls_back_to_the_older_version_key() {
+        let goxel = Goxel {
+            version: 2,
+            chunks: vec![Chunk::Img {
+                dict: Dict::from(IndexMap::from([("version".to_string(), b"0.10.0".to_vec())])),
+            }],
+        };
+        assert_eq!(goxel.authoring_version(), Some("0.10.0".to_string()));
+    }
+
+    #[test]
+    fn authoring_version_is_none_without_either_key() {
+        assert_eq!(Goxel::new(2).authoring_version(), None);
+    }
+
+    #[test]
+    fn world_box_derives_min_max_corners_from_a_scale_and_translate_matrix() {
+        let mat = [
+            2.0, 0.0, 0.0, 0.0, //
+            0.0, 3.0, 0.0, 0.0, //
+            0.0, 0.0, 4.0, 0.0, //
+            10.0, 20.0, 30.0, 1.0,
+        ];
+        let view = ImageView { bounding_box: mat };
+
+        assert_eq!(
+            view.world_box(),
+            Some(([8.0, 17.0, 26.0], [12.0, 23.0, 34.0]))
+        );
+    }
+
+    #[test]
+    fn world_box_is_none_without_a_real_box() {
+        let view = ImageView { bounding_box: [0.0; 16] };
+        assert_eq!(view.world_box(), None);
+    }
+
+    #[test]
+    fn scene_aggregates_every_typed_view_with_its_layer_voxels() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+        builder.add_layer("terrain");
+        let mut goxel = builder.build();
+
+        let mat = identity_mat();
+        goxel.chunks.push(Chunk::Img {
+            dict: Dict::from(IndexMap::from([("box".to_string(), mat_bytes(mat))])),
+        });
+        goxel.chunks.push(Chunk::Camr {
+            dict: Dict::from(IndexMap::from([
+                ("mat".to_string(), mat_bytes(mat)),
+                ("dist".to_string(), 10.0f32.to_le_bytes().to_vec()),
+            ])),
+        });
+        goxel.chunks.push(Chunk::Ligh {
+            dict: Dict::from(IndexMap::from([
+                ("pitch".to_string(), 0.5f32.to_le_bytes().to_vec()),
+                ("yaw".to_string(), 0.25f32.to_le_bytes().to_vec()),
+                ("intensity".to_string(), 1.0f32.to_le_bytes().to_vec()),
+            ])),
+        });
+        goxel.chunks.push(Chunk::Mate {
+            dict: Dict::from(IndexMap::from([
+                ("name".to_string(), b"Gold".to_vec()),
+                (
+                    "color".to_string(),
+                    [1.0f32, 0.8, 0.2, 1.0].iter().flat_map(|v| v.to_le_bytes()).collect(),
+                ),
+                ("metallic".to_string(), 0.9f32.to_le_bytes().to_vec()),
+                ("roughness".to_string(), 0.1f32.to_le_bytes().to_vec()),
+            ])),
+        });
+
+        let scene = goxel.scene().expect("should assemble a scene");
+        assert!(scene.image.is_some());
+        assert_eq!(scene.cameras.len(), 1);
+        assert!(scene.light.is_some());
+        assert_eq!(scene.materials.len(), 1);
+        assert_eq!(scene.layers.len(), 1);
+
+        let (layer_view, model) = &scene.layers[0];
+        assert_eq!(layer_view.name, "terrain");
+        assert_eq!(model.voxel_at(0, 0, 0), Some([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn dict_typed_getters_decode_well_formed_values() {
+        let dict = Dict::from(IndexMap::from([
+            ("name".to_string(), b"crate".to_vec()),
+            ("count".to_string(), 7i32.to_le_bytes().to_vec()),
+            ("scale".to_string(), 1.5f32.to_le_bytes().to_vec()),
+            (
+                "origin".to_string(),
+                [1.0f32, 2.0, 3.0]
+                    .iter()
+                    .flat_map(|v| v.to_le_bytes())
+                    .collect(),
+            ),
+        ]));
+
+        assert_eq!(dict.get_str("name"), Some("crate"));
+        assert!(matches!(dict.get_i32("count"), Ok(Some(7))));
+        assert!(matches!(dict.get_f32("scale"), Ok(Some(v)) if v == 1.5));
+        assert!(matches!(dict.get_vec3("origin"), Ok(Some([1.0, 2.0, 3.0]))));
+    }
+
+    #[test]
+    fn dict_typed_getters_return_none_for_a_missing_key() {
+        let dict = Dict::from(IndexMap::from([("count".to_string(), 7i32.to_le_bytes().to_vec())]));
+
+        assert_eq!(dict.get_str("missing"), None);
+        assert!(matches!(dict.get_i32("missing"), Ok(None)));
+        assert!(matches!(dict.get_vec3("missing"), Ok(None)));
+    }
+
+    #[test]
+    fn dict_typed_getters_error_on_a_wrong_sized_value_instead_of_treating_it_as_missing() {
+        let dict = Dict::from(IndexMap::from([("count".to_string(), vec![1, 2, 3])]));
+
+        assert!(matches!(
+            dict.get_i32("count"),
+            Err(GoxError::DictValueLength { ref key, expected: 4, got: 3 }) if key == "count"
+        ));
+        assert!(matches!(
+            dict.get_vec3("count"),
+            Err(GoxError::DictValueLength { ref key, expected: 12, got: 3 }) if key == "count"
+        ));
+    }
+
+    #[test]
+    fn dict_key_enums_look_up_the_same_raw_bytes_as_their_string_keys() {
+        let dict = Dict::from(IndexMap::from([
+            ("name".to_string(), b"bob".to_vec()),
+            ("dist".to_string(), 3.0f32.to_le_bytes().to_vec()),
+            ("visible".to_string(), 1i32.to_le_bytes().to_vec()),
+            ("pitch".to_string(), 0.5f32.to_le_bytes().to_vec()),
+            ("color".to_string(), [1.0f32; 4].iter().flat_map(|v| v.to_le_bytes()).collect()),
+            ("box".to_string(), vec![0u8; 64]),
+        ]));
+
+        assert_eq!(dict.get_camr(CamrKey::Name), dict.get_str("name").map(str::as_bytes));
+        assert_eq!(dict.get_camr(CamrKey::Dist), dict.get("dist").map(Vec::as_slice));
+        assert_eq!(dict.get_layr(LayrKey::Visible), dict.get("visible").map(Vec::as_slice));
+        assert_eq!(dict.get_ligh(LighKey::Pitch), dict.get("pitch").map(Vec::as_slice));
+        assert_eq!(dict.get_mate(MateKey::Color), dict.get("color").map(Vec::as_slice));
+        assert_eq!(dict.get_img(ImgKey::BoundingBox), dict.get("box").map(Vec::as_slice));
+        assert_eq!(dict.get_camr(CamrKey::Ortho), None);
+    }
+
+    #[test]
+    fn dict_iter_preserves_insertion_order() {
+        let dict = Dict::from(IndexMap::from([
+            ("z".to_string(), vec![1]),
+            ("a".to_string(), vec![2]),
+            ("m".to_string(), vec![3]),
+        ]));
+
+        let keys: Vec<&str> = dict.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["z", "a", "m"]);
+    }
+
+    #[test]
+    fn model_builder_round_trips_a_single_layer_through_write_and_parse() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [255, 0, 0, 255]);
+        builder.set_voxel(1, 1, 1, [0, 255, 0, 255]);
+        builder.add_layer("line");
+
+        let goxel = builder.build();
+        let round_tripped = Goxel::from_bytes(&goxel.to_bytes()).expect("should parse what we built");
+
+        let mut expected = Model::default();
+        expected.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 1, 1), [0, 255, 0, 255]),
+        ]);
+        assert_eq!(round_tripped.model().expect("should assemble a model"), expected);
+    }
+
+    #[test]
+    fn model_builder_clear_voxel_removes_a_placed_voxel() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(5, 5, 5, [1, 2, 3, 255]);
+        builder.clear_voxel(5, 5, 5);
+        builder.add_layer("empty");
+
+        let goxel = builder.build();
+        let model = goxel.model().expect("should assemble a model");
+        assert!(model.is_empty());
+    }
+
+    #[test]
+    fn model_builder_splits_voxels_spanning_two_blocks_into_separate_bl16_chunks() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(0, 0, 0, [1, 0, 0, 255]);
+        builder.set_voxel(16, 0, 0, [0, 1, 0, 255]); // falls in the neighboring block along x
+        builder.add_layer("two blocks");
+
+        let goxel = builder.build();
+        let bl16_count = goxel.chunks().iter().filter(|c| matches!(c, Chunk::Bl16 { .. })).count();
+        assert_eq!(bl16_count, 2);
+
+        let round_tripped = Goxel::from_bytes(&goxel.to_bytes()).expect("should parse what we built");
+        let mut expected = Model::default();
+        expected.extend([((0, 0, 0), [1, 0, 0, 255]), ((16, 0, 0), [0, 1, 0, 255])]);
+        assert_eq!(round_tripped.model().expect("should assemble a model"), expected);
+    }
+
+    #[test]
+    fn model_builder_build_includes_unsealed_voxels_as_a_final_layer() {
+        let mut builder = ModelBuilder::new();
+        builder.set_voxel(2, 2, 2, [9, 9, 9, 255]);
+
+        let model = builder.build().model().expect("should assemble a model");
+        assert_eq!(model.voxel_at(2, 2, 2), Some([9, 9, 9, 255]));
+    }
+
+    #[test]
+    fn model_builder_build_is_byte_identical_across_separately_built_models() {
+        let voxels: [((i32, i32, i32), [u8; 4]); 4] = [
+            ((0, 0, 0), [1, 0, 0, 255]),
+            ((16, 0, 0), [0, 1, 0, 255]),
+            ((0, 16, 0), [0, 0, 1, 255]),
+            ((0, 0, 16), [1, 1, 0, 255]),
+        ];
+
+        let mut first = ModelBuilder::new();
+        for &(pos, rgba) in &voxels {
+            first.set_voxel(pos.0, pos.1, pos.2, rgba);
+        }
+        first.add_layer("scattered");
+
+        let mut second = ModelBuilder::new();
+        for &(pos, rgba) in voxels.iter().rev() {
+            second.set_voxel(pos.0, pos.1, pos.2, rgba);
+        }
+        second.add_layer("scattered");
 
-        let res = img(input).expect("Couldn't get img chunk");
+        assert_eq!(first.build().to_bytes(), second.build().to_bytes());
     }
 }
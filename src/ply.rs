@@ -0,0 +1,114 @@
+//! An exporter to the Stanford `.ply` point-cloud format.
+
+use crate::parser::Model;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlyError {
+    #[error("model has no voxels to export")]
+    Empty,
+}
+
+/// Which `.ply` encoding [`export`] should write. Binary is far more
+/// compact for large models; ASCII is easier to inspect by eye.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+fn header(format: PlyFormat, vertex_count: usize) -> String {
+    let format_line = match format {
+        PlyFormat::Ascii => "format ascii 1.0",
+        PlyFormat::BinaryLittleEndian => "format binary_little_endian 1.0",
+    };
+    format!(
+        "ply\n\
+         {format_line}\n\
+         element vertex {vertex_count}\n\
+         property float x\n\
+         property float y\n\
+         property float z\n\
+         property uchar red\n\
+         property uchar green\n\
+         property uchar blue\n\
+         end_header\n"
+    )
+}
+
+/// Exports `model` as a `.ply` point cloud, one vertex per occupied voxel at
+/// its integer coordinates (as floats), colored by its RGBA color's RGB
+/// channels (alpha isn't representable in this minimal vertex layout).
+pub fn export(model: &Model, format: PlyFormat) -> Result<Vec<u8>, PlyError> {
+    if model.is_empty() {
+        return Err(PlyError::Empty);
+    }
+
+    let mut out = header(format, model.len()).into_bytes();
+    match format {
+        PlyFormat::Ascii => {
+            for ([x, y, z], rgba) in model.iter() {
+                out.extend_from_slice(
+                    format!("{x} {y} {z} {} {} {}\n", rgba[0], rgba[1], rgba[2]).as_bytes(),
+                );
+            }
+        }
+        PlyFormat::BinaryLittleEndian => {
+            for ([x, y, z], rgba) in model.iter() {
+                out.extend_from_slice(&(x as f32).to_le_bytes());
+                out.extend_from_slice(&(y as f32).to_le_bytes());
+                out.extend_from_slice(&(z as f32).to_le_bytes());
+                out.extend_from_slice(&rgba[0..3]);
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn export_rejects_an_empty_model() {
+        let model = Model::default();
+        assert!(matches!(export(&model, PlyFormat::Ascii), Err(PlyError::Empty)));
+    }
+
+    #[test]
+    fn export_ascii_header_matches_the_vertex_count() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let bytes = export(&model, PlyFormat::Ascii).expect("should export a two-voxel model");
+        let text = String::from_utf8(bytes).expect("ascii PLY is valid utf-8");
+
+        assert!(text.starts_with("ply\nformat ascii 1.0\n"));
+        assert!(text.contains("element vertex 2\n"));
+        assert!(text.contains("end_header\n"));
+
+        let vertex_lines = text.lines().skip_while(|&l| l != "end_header").skip(1).count();
+        assert_eq!(vertex_lines, 2);
+    }
+
+    #[test]
+    fn export_binary_body_is_sized_per_vertex() {
+        let mut model = Model::default();
+        model.extend([((1, 2, 3), [10, 20, 30, 255])]);
+
+        let bytes = export(&model, PlyFormat::BinaryLittleEndian)
+            .expect("should export a single-voxel model");
+        let header_len = bytes
+            .windows(b"end_header\n".len())
+            .position(|w| w == b"end_header\n")
+            .expect("header should contain end_header")
+            + b"end_header\n".len();
+
+        // Each vertex is 3 little-endian f32 positions plus 3 u8 colors.
+        let body = &bytes[header_len..];
+        assert_eq!(body.len(), 3 * 4 + 3);
+        assert_eq!(&body[12..15], &[10, 20, 30]);
+    }
+}
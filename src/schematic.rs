@@ -0,0 +1,226 @@
+//! An exporter to the Sponge `.schem` format used by WorldEdit and other
+//! Minecraft world-editing tools.
+//!
+//! Only what [`export`] needs to write is implemented: a handful of NBT tag
+//! types, big-endian integers (NBT, unlike the `.gox` format this crate
+//! otherwise deals with, is always big-endian), and a gzip wrapper around
+//! the whole stream. Only available with the `gzip` feature, since gzipping
+//! the output is part of the format.
+
+use crate::parser::Model;
+use indexmap::IndexMap;
+use std::io::{self, Write};
+
+/// The largest size [`export`] can represent along one axis: `Width`,
+/// `Height`, and `Length` are stored as unsigned 16-bit NBT shorts.
+const MAX_SIZE: i32 = u16::MAX as i32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchematicError {
+    #[error("model has no voxels to export")]
+    Empty,
+
+    #[error("model is {size} voxels along an axis, which exceeds the .schem format's {MAX_SIZE} limit")]
+    TooLarge { size: i32 },
+
+    #[error("voxel color {color:?} has no matching entry in the supplied block palette")]
+    UnknownColor { color: [u8; 4] },
+}
+
+const TAG_END: u8 = 0;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_COMPOUND: u8 = 10;
+
+fn write_name<W: Write>(w: &mut W, name: &str) -> io::Result<()> {
+    w.write_all(&(name.len() as u16).to_be_bytes())?;
+    w.write_all(name.as_bytes())
+}
+
+fn write_tag_short<W: Write>(w: &mut W, name: &str, value: i16) -> io::Result<()> {
+    w.write_all(&[TAG_SHORT])?;
+    write_name(w, name)?;
+    w.write_all(&value.to_be_bytes())
+}
+
+fn write_tag_int<W: Write>(w: &mut W, name: &str, value: i32) -> io::Result<()> {
+    w.write_all(&[TAG_INT])?;
+    write_name(w, name)?;
+    w.write_all(&value.to_be_bytes())
+}
+
+fn write_tag_byte_array<W: Write>(w: &mut W, name: &str, data: &[u8]) -> io::Result<()> {
+    w.write_all(&[TAG_BYTE_ARRAY])?;
+    write_name(w, name)?;
+    w.write_all(&(data.len() as i32).to_be_bytes())?;
+    w.write_all(data)
+}
+
+fn write_compound_header<W: Write>(w: &mut W, name: &str) -> io::Result<()> {
+    w.write_all(&[TAG_COMPOUND])?;
+    write_name(w, name)
+}
+
+fn write_tag_end<W: Write>(w: &mut W) -> io::Result<()> {
+    w.write_all(&[TAG_END])
+}
+
+/// Appends `value` to `out` as a LEB128 varint, the encoding the Sponge
+/// format's `BlockData` byte array uses for each block's palette index.
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Exports `model` as gzip-compressed Sponge `.schem` NBT bytes, mapping
+/// each voxel's RGBA color to a block name via `palette`. A voxel whose
+/// color has no entry in `palette` fails with
+/// [`SchematicError::UnknownColor`]; any coordinate with no voxel at all is
+/// written out as `minecraft:air`.
+pub fn export(model: &Model, palette: &[([u8; 4], &str)]) -> Result<Vec<u8>, SchematicError> {
+    let bbox = model.bounding_box().ok_or(SchematicError::Empty)?;
+    let width = bbox.max.0 - bbox.min.0 + 1;
+    let height = bbox.max.1 - bbox.min.1 + 1;
+    let length = bbox.max.2 - bbox.min.2 + 1;
+    if width > MAX_SIZE || height > MAX_SIZE || length > MAX_SIZE {
+        return Err(SchematicError::TooLarge {
+            size: width.max(height).max(length),
+        });
+    }
+
+    let color_to_block: std::collections::HashMap<[u8; 4], &str> =
+        palette.iter().map(|&(color, block)| (color, block)).collect();
+
+    let mut block_palette: IndexMap<&str, i32> = IndexMap::new();
+    block_palette.insert("minecraft:air", 0);
+
+    let mut block_data = Vec::new();
+    for y in 0..height {
+        for z in 0..length {
+            for x in 0..width {
+                let world = (bbox.min.0 + x, bbox.min.1 + y, bbox.min.2 + z);
+                let index = match model.voxel_at(world.0, world.1, world.2) {
+                    Some(rgba) if rgba[3] != 0 => {
+                        let block = color_to_block
+                            .get(&rgba)
+                            .ok_or(SchematicError::UnknownColor { color: rgba })?;
+                        let next = block_palette.len() as i32;
+                        *block_palette.entry(block).or_insert(next)
+                    }
+                    _ => 0,
+                };
+                write_varint(&mut block_data, index as u32);
+            }
+        }
+    }
+
+    let mut nbt = Vec::new();
+    let write_failed = "writing to a Vec<u8> cannot fail";
+    write_compound_header(&mut nbt, "Schematic").expect(write_failed);
+    write_tag_int(&mut nbt, "Version", 2).expect(write_failed);
+    write_tag_int(&mut nbt, "DataVersion", 3700).expect(write_failed);
+    write_tag_short(&mut nbt, "Width", width as i16).expect(write_failed);
+    write_tag_short(&mut nbt, "Height", height as i16).expect(write_failed);
+    write_tag_short(&mut nbt, "Length", length as i16).expect(write_failed);
+
+    write_compound_header(&mut nbt, "Palette").expect(write_failed);
+    for (block, id) in &block_palette {
+        write_tag_int(&mut nbt, block, *id).expect(write_failed);
+    }
+    write_tag_end(&mut nbt).expect(write_failed);
+
+    write_tag_int(&mut nbt, "PaletteMax", block_palette.len() as i32).expect(write_failed);
+    write_tag_byte_array(&mut nbt, "BlockData", &block_data).expect(write_failed);
+    write_tag_end(&mut nbt).expect(write_failed);
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&nbt).expect(write_failed);
+    Ok(encoder.finish().expect(write_failed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Read;
+
+    /// Reads a length-prefixed name the way [`write_name`] wrote it,
+    /// returning it alongside whatever bytes follow.
+    fn read_name(input: &[u8]) -> (&str, &[u8]) {
+        let len = u16::from_be_bytes(input[0..2].try_into().unwrap()) as usize;
+        let name = std::str::from_utf8(&input[2..2 + len]).unwrap();
+        (name, &input[2 + len..])
+    }
+
+    #[test]
+    fn export_rejects_an_empty_model() {
+        let model = Model::default();
+        assert!(matches!(export(&model, &[]), Err(SchematicError::Empty)));
+    }
+
+    #[test]
+    fn export_rejects_a_voxel_color_missing_from_the_palette() {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [1, 2, 3, 255])]);
+
+        let err = export(&model, &[]).unwrap_err();
+        assert!(matches!(err, SchematicError::UnknownColor { color } if color == [1, 2, 3, 255]));
+    }
+
+    #[test]
+    fn export_writes_a_gzipped_nbt_stream_with_the_expected_dimensions() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 1), [0, 255, 0, 255]),
+        ]);
+        let palette = [([255, 0, 0, 255], "minecraft:red_wool"), ([0, 255, 0, 255], "minecraft:lime_wool")];
+
+        let bytes = export(&model, &palette).expect("should export a small model");
+
+        // Gzip's magic bytes, confirming the output is actually compressed.
+        assert_eq!(&bytes[0..2], &[0x1f, 0x8b]);
+
+        let mut nbt = Vec::new();
+        flate2::read::GzDecoder::new(&bytes[..])
+            .read_to_end(&mut nbt)
+            .expect("should decompress what we just compressed");
+
+        assert_eq!(nbt[0], TAG_COMPOUND);
+        let (root_name, rest) = read_name(&nbt[1..]);
+        assert_eq!(root_name, "Schematic");
+
+        assert_eq!(rest[0], TAG_INT);
+        let (version_name, rest) = read_name(&rest[1..]);
+        assert_eq!(version_name, "Version");
+        assert_eq!(i32::from_be_bytes(rest[0..4].try_into().unwrap()), 2);
+
+        assert_eq!(rest[4], TAG_INT);
+        let (data_version_name, rest) = read_name(&rest[5..]);
+        assert_eq!(data_version_name, "DataVersion");
+
+        assert_eq!(rest[4], TAG_SHORT);
+        let (width_name, rest) = read_name(&rest[5..]);
+        assert_eq!(width_name, "Width");
+        assert_eq!(i16::from_be_bytes(rest[0..2].try_into().unwrap()), 2);
+
+        assert_eq!(rest[2], TAG_SHORT);
+        let (height_name, rest) = read_name(&rest[3..]);
+        assert_eq!(height_name, "Height");
+        assert_eq!(i16::from_be_bytes(rest[0..2].try_into().unwrap()), 1);
+
+        assert_eq!(rest[2], TAG_SHORT);
+        let (length_name, rest) = read_name(&rest[3..]);
+        assert_eq!(length_name, "Length");
+        assert_eq!(i16::from_be_bytes(rest[0..2].try_into().unwrap()), 2);
+    }
+}
@@ -0,0 +1,300 @@
+//! An exporter and importer for MagicaVoxel's `.vox` format.
+//!
+//! Based on the spec at https://github.com/ephtracy/voxel-model/blob/master/MagicaVoxel-file-format-vox.txt
+
+use crate::parser::Model;
+use indexmap::IndexMap;
+use std::io::{self, Write};
+
+/// MagicaVoxel caps a model's bounding box at 256 voxels per axis.
+const MAX_SIZE: i32 = 256;
+
+/// MagicaVoxel's palette has 256 entries, but index `0` always means
+/// "empty", leaving 255 usable colors.
+const MAX_COLORS: usize = 255;
+
+#[derive(Debug, thiserror::Error)]
+pub enum VoxError {
+    #[error("model has no voxels to export")]
+    Empty,
+
+    #[error("model is {size} voxels along an axis, which exceeds MagicaVoxel's {MAX_SIZE}³ limit")]
+    TooLarge { size: i32 },
+
+    #[error("model uses {count} distinct colors, which exceeds the {MAX_COLORS}-entry .vox palette")]
+    TooManyColors { count: usize },
+
+    #[error("not a .vox file: missing the \"VOX \" magic header")]
+    BadMagic,
+
+    #[error("chunk data ended unexpectedly")]
+    Truncated,
+
+    #[error("file has no SIZE chunk")]
+    MissingSize,
+
+    #[error("file has no XYZI chunk")]
+    MissingXyzi,
+
+    #[error("file has no RGBA chunk; importing without a custom palette isn't supported")]
+    MissingPalette,
+}
+
+fn write_chunk<W: Write>(w: &mut W, id: &[u8; 4], content: &[u8]) -> io::Result<()> {
+    w.write_all(id)?;
+    w.write_all(&(content.len() as u32).to_le_bytes())?;
+    w.write_all(&0u32.to_le_bytes())?; // numBytesOfChildren, always 0 for leaf chunks
+    w.write_all(content)?;
+    Ok(())
+}
+
+/// A chunk's id, content, and children, plus whatever input follows it.
+type ChunkParts<'a> = (&'a [u8], &'a [u8], &'a [u8], &'a [u8]);
+
+/// Splits one chunk (id, content, children) off the front of `input`,
+/// returning it along with whatever follows.
+fn read_chunk(input: &[u8]) -> Result<ChunkParts<'_>, VoxError> {
+    let id = input.get(0..4).ok_or(VoxError::Truncated)?;
+    let content_len = u32::from_le_bytes(
+        input.get(4..8).ok_or(VoxError::Truncated)?.try_into().unwrap(),
+    ) as usize;
+    let children_len = u32::from_le_bytes(
+        input.get(8..12).ok_or(VoxError::Truncated)?.try_into().unwrap(),
+    ) as usize;
+
+    let content_start: usize = 12;
+    let content_end = content_start
+        .checked_add(content_len)
+        .ok_or(VoxError::Truncated)?;
+    let children_end = content_end
+        .checked_add(children_len)
+        .ok_or(VoxError::Truncated)?;
+
+    let content = input.get(content_start..content_end).ok_or(VoxError::Truncated)?;
+    let children = input.get(content_end..children_end).ok_or(VoxError::Truncated)?;
+    let rest = input.get(children_end..).ok_or(VoxError::Truncated)?;
+    Ok((id, content, children, rest))
+}
+
+/// Imports a MagicaVoxel `.vox` file's `SIZE`/`XYZI`/`RGBA` chunks into a
+/// sparse [`Model`]. Only the flat chunk layout `export` produces (and most
+/// real-world `.vox` files use) is supported: a single `MAIN` chunk whose
+/// children are the voxel chunks themselves, with no nested groups.
+pub fn import(bytes: &[u8]) -> Result<Model, VoxError> {
+    if !bytes.starts_with(b"VOX ") || bytes.len() < 8 {
+        return Err(VoxError::BadMagic);
+    }
+    let (_, _, main_children, _) = read_chunk(&bytes[8..])?;
+
+    let mut xyzi = None;
+    let mut palette = None;
+    let mut rest = main_children;
+    while !rest.is_empty() {
+        let (id, content, _, next) = read_chunk(rest)?;
+        match id {
+            b"XYZI" => xyzi = Some(content),
+            b"RGBA" => palette = Some(content),
+            _ => {}
+        }
+        rest = next;
+    }
+
+    let xyzi = xyzi.ok_or(VoxError::MissingXyzi)?;
+    let palette = palette.ok_or(VoxError::MissingPalette)?;
+
+    let num_voxels =
+        u32::from_le_bytes(xyzi.get(0..4).ok_or(VoxError::Truncated)?.try_into().unwrap())
+            as usize;
+    let voxels = xyzi
+        .get(4..4 + num_voxels * 4)
+        .ok_or(VoxError::Truncated)?;
+
+    let mut model = Model::default();
+    for voxel in voxels.chunks_exact(4) {
+        let [x, y, z, index] = voxel.try_into().unwrap();
+        if index == 0 {
+            continue;
+        }
+        let palette_offset = (index as usize - 1) * 4;
+        let rgba: [u8; 4] = palette
+            .get(palette_offset..palette_offset + 4)
+            .ok_or(VoxError::Truncated)?
+            .try_into()
+            .unwrap();
+        model.extend([((x as i32, y as i32, z as i32), rgba)]);
+    }
+    Ok(model)
+}
+
+/// Exports `model` as MagicaVoxel `.vox` bytes: a `SIZE` chunk giving the
+/// model's extent, an `XYZI` chunk listing each occupied voxel by palette
+/// index, and an `RGBA` chunk holding the palette itself.
+pub fn export(model: &Model) -> Result<Vec<u8>, VoxError> {
+    let bbox = model.bounding_box().ok_or(VoxError::Empty)?;
+    let size = (
+        bbox.max.0 - bbox.min.0 + 1,
+        bbox.max.1 - bbox.min.1 + 1,
+        bbox.max.2 - bbox.min.2 + 1,
+    );
+    if size.0 > MAX_SIZE || size.1 > MAX_SIZE || size.2 > MAX_SIZE {
+        return Err(VoxError::TooLarge {
+            size: size.0.max(size.1).max(size.2),
+        });
+    }
+
+    let mut palette: IndexMap<[u8; 4], u8> = IndexMap::new();
+    let mut voxels = Vec::new();
+    for ([x, y, z], rgba) in model.iter() {
+        let index = match palette.get(&rgba) {
+            Some(&index) => index,
+            None => {
+                let index = palette.len();
+                if index >= MAX_COLORS {
+                    return Err(VoxError::TooManyColors {
+                        count: palette.len() + 1,
+                    });
+                }
+                palette.insert(rgba, index as u8);
+                index as u8
+            }
+        };
+        voxels.push((
+            (x - bbox.min.0) as u8,
+            (y - bbox.min.1) as u8,
+            (z - bbox.min.2) as u8,
+            index + 1, // palette index 0 means "empty"
+        ));
+    }
+
+    let mut size_chunk = Vec::with_capacity(12);
+    size_chunk.extend_from_slice(&(size.0 as u32).to_le_bytes());
+    size_chunk.extend_from_slice(&(size.1 as u32).to_le_bytes());
+    size_chunk.extend_from_slice(&(size.2 as u32).to_le_bytes());
+
+    let mut xyzi_chunk = Vec::with_capacity(4 + voxels.len() * 4);
+    xyzi_chunk.extend_from_slice(&(voxels.len() as u32).to_le_bytes());
+    for (x, y, z, index) in &voxels {
+        xyzi_chunk.extend_from_slice(&[*x, *y, *z, *index]);
+    }
+
+    let mut rgba_chunk = vec![0u8; 256 * 4];
+    for (&color, &index) in &palette {
+        rgba_chunk[index as usize * 4..index as usize * 4 + 4].copy_from_slice(&color);
+    }
+
+    let mut children = Vec::new();
+    write_chunk(&mut children, b"SIZE", &size_chunk).expect("writing to a Vec<u8> cannot fail");
+    write_chunk(&mut children, b"XYZI", &xyzi_chunk).expect("writing to a Vec<u8> cannot fail");
+    write_chunk(&mut children, b"RGBA", &rgba_chunk).expect("writing to a Vec<u8> cannot fail");
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"VOX ");
+    out.extend_from_slice(&150i32.to_le_bytes());
+    out.extend_from_slice(b"MAIN");
+    out.extend_from_slice(&0u32.to_le_bytes()); // numBytesOfContent
+    out.extend_from_slice(&(children.len() as u32).to_le_bytes());
+    out.extend_from_slice(&children);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rgba_at(bytes: &[u8], offset: usize) -> [u8; 4] {
+        bytes[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn export_rejects_an_empty_model() {
+        let model = Model::default();
+        assert!(matches!(export(&model), Err(VoxError::Empty)));
+    }
+
+    #[test]
+    fn export_writes_size_and_voxels() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+        ]);
+
+        let bytes = export(&model).expect("should export a small model");
+        assert_eq!(&bytes[0..4], b"VOX ");
+        assert_eq!(i32::from_le_bytes(bytes[4..8].try_into().unwrap()), 150);
+        assert_eq!(&bytes[8..12], b"MAIN");
+
+        // MAIN's content is empty; its children start right after the header.
+        let size_chunk = &bytes[20..];
+        assert_eq!(&size_chunk[0..4], b"SIZE");
+        let size_content = &size_chunk[12..12 + 12];
+        assert_eq!(u32::from_le_bytes(size_content[0..4].try_into().unwrap()), 2);
+        assert_eq!(u32::from_le_bytes(size_content[4..8].try_into().unwrap()), 1);
+        assert_eq!(u32::from_le_bytes(size_content[8..12].try_into().unwrap()), 1);
+
+        let xyzi_chunk = &size_chunk[12 + 12..];
+        assert_eq!(&xyzi_chunk[0..4], b"XYZI");
+        let num_voxels = u32::from_le_bytes(xyzi_chunk[12..16].try_into().unwrap());
+        assert_eq!(num_voxels, 2);
+    }
+
+    #[test]
+    fn export_rejects_too_many_colors() {
+        let mut model = Model::default();
+        for i in 0..256i32 {
+            model.extend([((i, 0, 0), [i as u8, 0, 0, 255])]);
+        }
+
+        assert!(matches!(export(&model), Err(VoxError::TooManyColors { .. })));
+    }
+
+    #[test]
+    fn rgba_chunk_holds_the_palette_offset_by_one() {
+        let mut model = Model::default();
+        model.extend([((0, 0, 0), [10, 20, 30, 255])]);
+
+        let bytes = export(&model).expect("should export a single-voxel model");
+        let tag_start = bytes
+            .windows(4)
+            .position(|w| w == b"RGBA")
+            .expect("RGBA chunk should be present");
+        let content_start = tag_start + 12; // past id, numBytesOfContent, numBytesOfChildren
+
+        // Voxel color index 1 (the first color assigned) lives at palette
+        // slot 0, since .vox index 0 always means "empty".
+        assert_eq!(rgba_at(&bytes, content_start), [10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn import_rejects_bad_magic() {
+        assert!(matches!(import(b"NOPE"), Err(VoxError::BadMagic)));
+    }
+
+    #[test]
+    fn import_rejects_files_missing_xyzi() {
+        // A MAIN chunk with no children at all.
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"VOX ");
+        bytes.extend_from_slice(&150i32.to_le_bytes());
+        bytes.extend_from_slice(b"MAIN");
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+
+        assert!(matches!(import(&bytes), Err(VoxError::MissingXyzi)));
+    }
+
+    #[test]
+    fn import_round_trips_an_exported_model() {
+        let mut model = Model::default();
+        model.extend([
+            ((0, 0, 0), [255, 0, 0, 255]),
+            ((1, 0, 0), [0, 255, 0, 255]),
+            ((1, 1, 2), [0, 0, 255, 128]),
+        ]);
+
+        let bytes = export(&model).expect("should export a small model");
+        let round_tripped = import(&bytes).expect("should import what we just exported");
+
+        assert_eq!(round_tripped, model);
+    }
+}
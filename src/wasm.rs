@@ -0,0 +1,53 @@
+//! A `wasm-bindgen` entry point for running this crate in the browser.
+//!
+//! Gated behind the `wasm` feature so native builds don't pull in
+//! `wasm-bindgen`/`serde_wasm_bindgen`. Stays off `std::fs`, taking a byte
+//! slice in and handing a `JsValue` back out, so it compiles cleanly to
+//! `wasm32-unknown-unknown`.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::parser;
+
+#[derive(Serialize)]
+struct ParsedVoxel {
+    x: i32,
+    y: i32,
+    z: i32,
+    color: [u8; 4],
+}
+
+/// The subset of a parsed `.gox` file a browser viewer typically wants:
+/// the format version, how many layers it has, the merged voxel list (see
+/// [`parser::Goxel::model`]), and that model's color palette.
+#[derive(Serialize)]
+struct ParsedModel {
+    version: i32,
+    layer_count: usize,
+    voxels: Vec<ParsedVoxel>,
+    palette: Vec<[u8; 4]>,
+}
+
+/// Parses `input` as `.gox` bytes and serializes a browser-friendly summary
+/// (version, layer count, merged voxel list, palette) to a `JsValue` via
+/// `serde_wasm_bindgen`. Parse and model-assembly errors are stringified,
+/// since `wasm-bindgen` has no way to hand a typed Rust error back across
+/// the JS boundary.
+#[wasm_bindgen]
+pub fn parse_js(input: &[u8]) -> Result<JsValue, JsValue> {
+    let goxel = parser::parse(input).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let model = goxel.model().map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    let parsed = ParsedModel {
+        version: goxel.version(),
+        layer_count: goxel.stats().layer_count,
+        voxels: model
+            .iter()
+            .map(|([x, y, z], color)| ParsedVoxel { x, y, z, color })
+            .collect(),
+        palette: model.palette(),
+    };
+
+    serde_wasm_bindgen::to_value(&parsed).map_err(|err| JsValue::from_str(&err.to_string()))
+}